@@ -59,22 +59,33 @@ pub struct RootAppProps {}
 pub fn RootApp(cx: Scope<'_, RootAppProps>) -> Element<'_> {
     log::trace!("rerender root-app");
     let repository = use_state(cx, Repository::new);
-    let user = repository
+    // Users whose secrets could not be read have to log in again
+    let users: Vec<_> = repository
         .users()
-        .ok()
-        .and_then(|users| users.first().cloned());
-    let has_user = user.is_some();
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|user| !user.token_access_token.is_empty())
+        .collect();
+    let has_user = !users.is_empty();
 
     let environment_state = use_state(cx, || {
-        let model = if let Some(user) = user {
-            Model::new(user.instance_url.clone(), Some(user.token_access_token))
-        } else {
-            Model::default()
-        };
-
-        Environment::new(model, repository.get().clone())
+        let mut environment = Environment::new(Model::default(), repository.get().clone());
+        for user in users.iter() {
+            let model = Model::new(
+                user.instance_url.clone(),
+                Some(user.token_access_token.clone()),
+                user.backend,
+            );
+            environment.add_account(&user.id, model);
+        }
+        // Start with the account that was used most recently
+        if let Some(user) = repository.active_user() {
+            environment.switch_account(&user.id);
+        }
+        environment
     });
     let environment = environment_state.get();
+    let active_account = environment.accounts.active().unwrap_or_default();
 
     let window = AppWindow::retrieve(cx);
     cx.use_hook(|| {
@@ -84,11 +95,13 @@ pub fn RootApp(cx: Scope<'_, RootAppProps>) -> Element<'_> {
 
     ChangeTextsizeBehaviour::setup(cx, environment);
 
-    let should_show_login = use_state(cx, || !has_user);
+    let should_show_login = use_state(cx, || !has_user || repository.credentials_error().is_some());
 
     cx.render(rsx! {
         environment.model.has_token.then(||
             rsx!(crate::components::loggedin::LoggedInApp {
+                // Switching the account re-creates the whole logged in app
+                key: "{active_account}",
                 environment: environment_state,
                 should_show_login: should_show_login,
             })
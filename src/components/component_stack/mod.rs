@@ -15,13 +15,14 @@ use navicula::{self, reducer::ChildReducer, types::MessageContext, Effect, Reduc
 use super::conversation::ConversationReducer;
 use super::profile::ProfileReducer;
 use super::profiles::{
-    AnyProfilesTimelineProvider, FollowersTimelineProvider, ProfilesKind, ProfilesReducer,
+    AnyProfilesTimelineProvider, FollowersTimelineProvider, NotificationGroupProfilesProvider,
+    ProfilesKind, ProfilesReducer, SearchProfilesProvider, FOLLOW_REQUESTS_ID,
 };
 use super::status_timeline::{
     AccountTimelineProvider, AnyTimelineProvider, BookmarkTimelineProvider,
-    FavoritesTimelineProvider, GroupedAccountTimelineProvider, LocalTimelineProvider,
-    NotificationsTimelineProvider, PublicTimelineProvider, TimelineReducer,
-    UserProfileTimelineProvider,
+    DirectMessagesTimelineProvider, FavoritesTimelineProvider, GroupedAccountTimelineProvider,
+    HashtagTimelineProvider, LocalTimelineProvider, NotificationsTimelineProvider,
+    PublicTimelineProvider, SearchTimelineProvider, TimelineReducer, UserProfileTimelineProvider,
 };
 
 #[allow(clippy::large_enum_variant)]
@@ -43,6 +44,16 @@ pub enum Action {
 pub enum RootTimelineKind {
     GroupedAccount(AccountViewModel),
     Notifications(AccountViewModel),
+    /// A direct message conversation with the id of the conversation
+    DirectMessages(AccountViewModel, String),
+    /// The accounts of a notification group with the id of the group
+    NotificationGroup(AccountViewModel, String),
+    /// The timeline of a hashtag (without `#`)
+    Hashtag(AccountViewModel, String),
+    /// The statuses found by a search for the term
+    SearchStatuses(AccountViewModel, String),
+    /// The accounts found by a search for the term
+    SearchAccounts(AccountViewModel, String),
     Account(AccountViewModel),
     UserProfile(AccountViewModel),
     Bookmarks(AccountViewModel),
@@ -107,16 +118,46 @@ impl RootTimelineKind {
                 AnyTimelineProvider::new(LocalTimelineProvider::new(environment.clone()), &a.id)
                     .into()
             }
-            RootTimelineKind::Relationship(a, k) => AnyProfilesTimelineProvider::new(
-                FollowersTimelineProvider::new(k.clone(), environment),
-                &a.id,
-            )
-            .into(),
+            RootTimelineKind::Relationship(a, k) => {
+                let provider = FollowersTimelineProvider::new(k.clone(), environment);
+                // Lists of the same account need different providers
+                let id = match k {
+                    ProfilesKind::List(_, list) => list.id.clone(),
+                    ProfilesKind::FollowRequests(_) => FOLLOW_REQUESTS_ID.to_string(),
+                    _ => a.id.0.clone(),
+                };
+                AnyProfilesTimelineProvider::new(provider, &id).into()
+            }
             RootTimelineKind::Notifications(a) => AnyTimelineProvider::new(
                 NotificationsTimelineProvider::new(environment.clone(), a.id.clone()),
                 &a.id,
             )
             .into(),
+            RootTimelineKind::NotificationGroup(_, g) => AnyProfilesTimelineProvider::new(
+                NotificationGroupProfilesProvider::new(g.clone(), environment),
+                g,
+            )
+            .into(),
+            RootTimelineKind::DirectMessages(_, c) => AnyTimelineProvider::new(
+                DirectMessagesTimelineProvider::new(environment.clone(), c.clone()),
+                c,
+            )
+            .into(),
+            RootTimelineKind::Hashtag(_, t) => AnyTimelineProvider::new(
+                HashtagTimelineProvider::new(environment.clone(), t.clone()),
+                t,
+            )
+            .into(),
+            RootTimelineKind::SearchStatuses(_, t) => AnyTimelineProvider::new(
+                SearchTimelineProvider::new(environment.clone(), t.clone()),
+                t,
+            )
+            .into(),
+            RootTimelineKind::SearchAccounts(_, t) => AnyProfilesTimelineProvider::new(
+                SearchProfilesProvider::new(t.clone(), environment),
+                t,
+            )
+            .into(),
         }
     }
 
@@ -124,6 +165,11 @@ impl RootTimelineKind {
         match self {
             RootTimelineKind::GroupedAccount(a) => a.clone(),
             RootTimelineKind::Notifications(a) => a.clone(),
+            RootTimelineKind::DirectMessages(a, _) => a.clone(),
+            RootTimelineKind::NotificationGroup(a, _) => a.clone(),
+            RootTimelineKind::Hashtag(a, _) => a.clone(),
+            RootTimelineKind::SearchStatuses(a, _) => a.clone(),
+            RootTimelineKind::SearchAccounts(a, _) => a.clone(),
             RootTimelineKind::Account(a) => a.clone(),
             RootTimelineKind::UserProfile(a) => a.clone(),
             RootTimelineKind::Bookmarks(a) => a.clone(),
@@ -155,6 +201,18 @@ impl State {
             ui_settings: UiConfig::default(),
         }
     }
+
+    /// Open a profile on top of the root timeline
+    pub fn with_profile(mut self, profile: AccountViewModel) -> Self {
+        self.stack.push(profile);
+        self
+    }
+
+    /// Open a conversation next to the root timeline
+    pub fn with_conversation(mut self, id: StatusId) -> Self {
+        self.current_conversation = Some(id);
+        self
+    }
 }
 
 #[allow(unused)]
@@ -216,17 +274,19 @@ pub fn reduce<'a>(
             state.stack.push(p);
         }
         Action::ResolvePushProfile(p) => {
-            let Some(g) = crate::helper::parse_user_url(&p) else {
-                return Effect::NONE
-            };
+            if crate::helper::parse_user_url(&p).is_none() {
+                return Effect::NONE;
+            }
             let model = environment.model.clone();
+            let url = p.clone();
             return Effect::future(
                 async move {
+                    // resolve, so that accounts unknown to our server can be opened
                     model
-                        .search_account(g, false)
+                        .search(url, true)
                         .await
                         .ok()
-                        .and_then(|a| a.first().cloned())
+                        .and_then(|r| r.accounts.first().cloned())
                 },
                 |account| {
                     if let Some(n) = account.map(|a| AccountViewModel::new(&a)) {
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
+use crate::environment::filters::ContentFilter;
 use crate::environment::model::Model;
+use crate::environment::types::FilterContext;
 use crate::view_model::StatusId;
 use crate::view_model::StatusViewModel;
 use id_tree::*;
@@ -124,6 +126,72 @@ impl Conversation {
         }
         false
     }
+
+    /// Is there any status in the conversation that `matches`
+    pub fn contains(&self, matches: impl Fn(&StatusViewModel) -> bool) -> bool {
+        let Some(root_id) = self.tree.root_node_id() else {
+            return false
+        };
+        let Ok(mut iter) = self.tree.traverse_pre_order(root_id) else {
+            return false
+        };
+        iter.any(|node| matches(node.data()))
+    }
+
+    /// Remove a status from the conversation. Replies to it move up
+    /// to its parent. Returns `true` if the status was found
+    pub fn remove_post(&mut self, id: &StatusId) -> bool {
+        use id_tree::RemoveBehavior::*;
+        let Some(root_id) = self.tree.root_node_id() else {
+            return false
+        };
+        let Some(mut iter) = self.tree.traverse_pre_order_ids(root_id).ok() else {
+            return false
+        };
+        let found = iter.find(|node_id| {
+            self.tree
+                .get(node_id)
+                .map(|item| &item.data().id == id)
+                .unwrap_or_default()
+        });
+        let Some(node_id) = found else {
+            return false
+        };
+        self.tree.remove_node(node_id, LiftChildren).is_ok()
+    }
+
+    /// Apply the thread filters. Hidden replies are removed, the
+    /// original post is always kept but shown behind a warning
+    pub fn apply_filter(&mut self, filter: &ContentFilter) {
+        use id_tree::RemoveBehavior::*;
+        if filter.is_empty() {
+            return;
+        }
+        let Some(root_id) = self.tree.root_node_id().cloned() else {
+            return
+        };
+        let Ok(iter) = self.tree.traverse_pre_order_ids(&root_id) else {
+            return
+        };
+        let node_ids: Vec<NodeId> = iter.collect();
+        let mut hidden = Vec::new();
+        for node_id in node_ids {
+            let Ok(item) = self.tree.get_mut(&node_id) else {
+                continue
+            };
+            if filter.apply(item.data_mut(), FilterContext::Thread) {
+                continue;
+            }
+            if node_id == root_id {
+                item.data_mut().filtered = Some(crate::loc!("Hidden").to_string());
+            } else {
+                hidden.push(node_id);
+            }
+        }
+        for node_id in hidden {
+            let _ = self.tree.remove_node(node_id, LiftChildren);
+        }
+    }
 }
 
 pub async fn build_conversation(model: &Model, status_id: String) -> Result<Conversation, String> {
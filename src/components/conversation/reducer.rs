@@ -97,10 +97,11 @@ pub fn reduce<'a>(
         }
         Action::LoadedConversation(result) => {
             state.is_loading = false;
-            let Ok(selected_conv) = result else {
+            let Ok(mut selected_conv) = result else {
                 return Effect::NONE
             };
             environment.storage.with_mutation(|mut storage| {
+                selected_conv.apply_filter(&storage.content_filter);
                 storage
                     .conversations
                     .insert(selected_conv.status(), selected_conv);
@@ -159,6 +159,14 @@ fn UserConversationComponentChild<'a>(
 
     let id = child.id.dom_id();
 
+    let settings = shared_ui_config(cx);
+    let expanded = use_state(cx, || false);
+    let has_spoiler = !child.spoiler_text.is_empty();
+    let is_expanded = !has_spoiler || *expanded.get() || settings.expand_content_warnings;
+    let revealed = use_state(cx, || false);
+    let is_hidden = child.sensitive && !settings.show_sensitive_media && !*revealed.get();
+    let blur = is_hidden.then_some("sensitive-hidden").unwrap_or_default();
+
     let message = cx.render(rsx!(
         div { class: "conversation-child {cls} {is_selected}",
             div { id: "conv-{id}", class: "optionbox",
@@ -227,51 +235,74 @@ fn UserConversationComponentChild<'a>(
                     |account| ProfileState::new(account, false),
                 )
             }
-            TextContent {
-                content: Cow::from(child.content.clone()),
-                onclick: move |action| match action {
-                    TextContentAction::Tag(tag) => {
-                        store.send(Action::Public(PublicAction::OpenTag(tag)))
-                    }
-                    TextContentAction::Link(link) => {
-                        store.send(Action::Public(PublicAction::OpenLink(link)))
-                    }
-                    TextContentAction::Account(link) => {
-                        store.send(Action::Public(PublicAction::OpenLink(link)))
-                    }
-                },
-                class: ""
-            }
+            has_spoiler.then(|| rsx!(ContentWarning {
+                spoiler_text: &child.spoiler_text,
+                expanded: is_expanded,
+                ontoggle: move |_| expanded.set(!is_expanded)
+            }))
 
-            { child.status_images.iter().map(|(description, preview, url)| rsx!(div {
-            class: "media-object",
-            img {
-                src: "{preview}",
-                alt: "{description}",
-                onclick: move |_| store.send(Action::Public(PublicAction::OpenImage(url.to_string()))),
-            }
-        }))},
+            is_expanded.then(|| rsx!(
+                TextContent {
+                    content: Cow::from(child.content.clone()),
+                    onclick: move |action| match action {
+                        TextContentAction::Tag(tag) => {
+                            store.send(Action::Public(PublicAction::OpenTag(tag)))
+                        }
+                        TextContentAction::Link(link) => {
+                            store.send(Action::Public(PublicAction::OpenLink(link)))
+                        }
+                        TextContentAction::Account(link) => {
+                            store.send(Action::Public(PublicAction::OpenLink(link)))
+                        }
+                    },
+                    class: ""
+                }
+
+                { child.poll.as_ref().map(|poll| rsx!(PollComponent {
+                    poll: poll,
+                    onvote: move |choices| {
+                        store.send(Action::Public(PublicAction::VotePoll(child.cloned_status(), choices)))
+                    }
+                }))}
 
-            { child.media.iter().map(|media| if let Some(preview) = media.preview_url.as_ref() {
-            rsx!(div {
-                class: "media-object",
+                { child.status_images.iter().map(|(description, preview, url)| rsx!(div {
+                class: "media-object {blur}",
                 img {
                     src: "{preview}",
-                    alt: "{media.description}",
-                    onclick: move |_| store.send(Action::Public(PublicAction::OpenVideo(media.video_url.clone()))),
-                }
-            })
-        } else {
-            rsx!(div {
-                class: "media-object",
-                span {
-                    class: "empty label-secondary",
-                    title: "{media.description}",
-                    onclick: move |_| store.send(Action::Public(PublicAction::OpenVideo(media.video_url.clone()))),
-                    "Video"
+                    alt: "{description}",
+                    onclick: move |_| if is_hidden {
+                        revealed.set(true)
+                    } else {
+                        store.send(Action::Public(PublicAction::OpenImage(url.to_string())))
+                    },
                 }
-            })
-        })}
+            }))},
+
+                { child.media.iter().map(|media| if let Some(preview) = media.preview_url.as_ref() {
+                rsx!(div {
+                    class: "media-object {blur}",
+                    img {
+                        src: "{preview}",
+                        alt: "{media.description}",
+                        onclick: move |_| if is_hidden {
+                            revealed.set(true)
+                        } else {
+                            store.send(Action::Public(PublicAction::OpenVideo(media.video_url.clone())))
+                        },
+                    }
+                })
+            } else {
+                rsx!(div {
+                    class: "media-object",
+                    span {
+                        class: "empty label-secondary",
+                        title: "{media.description}",
+                        onclick: move |_| store.send(Action::Public(PublicAction::OpenVideo(media.video_url.clone()))),
+                        "Video"
+                    }
+                })
+            })}
+            ))
         }
     ));
 
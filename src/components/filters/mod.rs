@@ -0,0 +1,37 @@
+mod reducer;
+mod view;
+
+pub use reducer::{reduce, FiltersAction, FiltersState, ViewStore};
+pub use view::FiltersComponent;
+
+pub struct FiltersReducer;
+use navicula::reducer::Reducer;
+
+impl Reducer for FiltersReducer {
+    type Message = ();
+
+    type DelegateMessage = ();
+
+    type Action = reducer::FiltersAction;
+
+    type State = reducer::FiltersState;
+
+    type Environment = crate::environment::Environment;
+
+    fn reduce<'a, 'b>(
+        context: &'a impl navicula::types::MessageContext<
+            Self::Action,
+            Self::DelegateMessage,
+            Self::Message,
+        >,
+        action: Self::Action,
+        state: &'a mut Self::State,
+        environment: &'a Self::Environment,
+    ) -> navicula::effect::Effect<'b, Self::Action> {
+        reducer::reduce(context, action, state, environment)
+    }
+
+    fn initial_action() -> Option<Self::Action> {
+        Some(FiltersAction::Load)
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+
+use crate::environment::filters::{keyword_mute_regex, ContentFilter};
+use crate::environment::model::{Filter, FilterKeyword};
+use crate::environment::types::{FilterAction, FilterContext, FilterInput, FilterKeywordInput};
+use crate::environment::Environment;
+use crate::loc;
+use navicula::Effect;
+
+pub type ViewStore<'a> = navicula::ViewStore<'a, super::FiltersReducer>;
+
+/// The expiration choices of the filter editor, in seconds
+pub const EXPIRATIONS: [(&str, Option<u64>); 7] = [
+    (loc!("Never"), None),
+    (loc!("30 minutes"), Some(30 * 60)),
+    (loc!("1 hour"), Some(60 * 60)),
+    (loc!("6 hours"), Some(6 * 60 * 60)),
+    (loc!("12 hours"), Some(12 * 60 * 60)),
+    (loc!("1 day"), Some(24 * 60 * 60)),
+    (loc!("1 week"), Some(7 * 24 * 60 * 60)),
+];
+
+#[derive(Clone, Debug)]
+pub enum FiltersAction {
+    Load,
+    Loaded(Result<Vec<Filter>, String>),
+    New,
+    Edit(String),
+    CancelEdit,
+    UpdateTitle(String),
+    ToggleContext(FilterContext),
+    SelectAction(FilterAction),
+    /// `None` keeps the current expiration
+    SelectExpiration(Option<usize>),
+    AddKeyword,
+    UpdateKeyword(usize, String),
+    UpdateWholeWord(usize, bool),
+    RemoveKeyword(usize),
+    Save,
+    Saved(Result<Filter, String>),
+    Delete(String),
+    Deleted(String, Result<(), String>),
+    UpdateNewMute(String),
+    AddMute,
+    RemoveMute(String),
+    ClearError,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct FiltersState {
+    pub filters: Vec<Filter>,
+    /// The local regex mutes from the config
+    pub keyword_mutes: Vec<String>,
+    /// The errors of the mutes that are not valid regular expressions.
+    /// They were added to the config by hand and are ignored
+    pub invalid_mutes: HashMap<String, String>,
+    pub new_mute: String,
+    pub editor: Option<FilterEditor>,
+    pub is_loading: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct FilterEditor {
+    /// `None` for a new filter
+    pub id: Option<String>,
+    pub title: String,
+    pub context: HashSet<FilterContext>,
+    pub filter_action: FilterAction,
+    /// Index into `EXPIRATIONS`. `None` keeps the current expiration
+    pub expiration: Option<usize>,
+    pub expires_at: Option<chrono::DateTime<Utc>>,
+    pub keywords: Vec<EditedKeyword>,
+    /// The keywords of the filter before editing
+    existing: Vec<FilterKeyword>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EditedKeyword {
+    /// `None` for a new keyword
+    pub id: Option<String>,
+    pub keyword: String,
+    pub whole_word: bool,
+}
+
+impl EditedKeyword {
+    fn new() -> Self {
+        Self {
+            id: None,
+            keyword: String::new(),
+            whole_word: true,
+        }
+    }
+}
+
+impl FilterEditor {
+    pub fn new() -> Self {
+        Self {
+            context: FilterContext::all().into_iter().collect(),
+            expiration: Some(0),
+            keywords: vec![EditedKeyword::new()],
+            ..Default::default()
+        }
+    }
+
+    pub fn edit(filter: &Filter) -> Self {
+        let expires_at = filter.expires_at.filter(|e| e > &Utc::now());
+        Self {
+            id: Some(filter.id.clone()),
+            title: filter.title.clone(),
+            context: filter.context.iter().copied().collect(),
+            filter_action: filter.filter_action,
+            expiration: if expires_at.is_some() { None } else { Some(0) },
+            expires_at,
+            keywords: filter
+                .keywords
+                .iter()
+                .map(|k| EditedKeyword {
+                    id: Some(k.id.clone()),
+                    keyword: k.keyword.clone(),
+                    whole_word: k.whole_word,
+                })
+                .collect(),
+            existing: filter.keywords.clone(),
+        }
+    }
+
+    fn keywords(&self) -> impl Iterator<Item = &EditedKeyword> {
+        self.keywords
+            .iter()
+            .filter(|k| !k.keyword.trim().is_empty())
+    }
+
+    pub fn can_save(&self) -> bool {
+        !self.title.trim().is_empty()
+            && !self.context.is_empty()
+            && self.keywords().next().is_some()
+    }
+
+    fn input(&self) -> FilterInput {
+        // Existing keywords are updated or destroyed, the rest is new
+        let mut keywords_attributes: Vec<_> = self
+            .keywords()
+            .map(|k| FilterKeywordInput {
+                id: k.id.clone(),
+                keyword: k.keyword.trim().to_string(),
+                whole_word: k.whole_word,
+                destroy: false,
+            })
+            .collect();
+        keywords_attributes.extend(
+            self.existing
+                .iter()
+                .filter(|e| !self.keywords().any(|k| k.id.as_ref() == Some(&e.id)))
+                .map(|e| FilterKeywordInput {
+                    id: Some(e.id.clone()),
+                    keyword: e.keyword.clone(),
+                    whole_word: e.whole_word,
+                    destroy: true,
+                }),
+        );
+        let expires_in = match self.expiration {
+            Some(index) => EXPIRATIONS.get(index).and_then(|e| e.1),
+            None => self
+                .expires_at
+                .map(|e| (e - Utc::now()).num_seconds().max(1) as u64),
+        };
+        FilterInput {
+            title: self.title.trim().to_string(),
+            context: FilterContext::all()
+                .into_iter()
+                .filter(|c| self.context.contains(c))
+                .collect(),
+            filter_action: self.filter_action,
+            expires_in,
+            keywords_attributes,
+        }
+    }
+}
+
+pub fn reduce<'a>(
+    _context: &'a impl navicula::types::MessageContext<FiltersAction, (), ()>,
+    action: FiltersAction,
+    state: &'a mut FiltersState,
+    environment: &'a Environment,
+) -> Effect<'static, FiltersAction> {
+    log::trace!("{action:?}");
+    let model = environment.model.clone();
+    match action {
+        FiltersAction::Load => {
+            state.is_loading = true;
+            state.keyword_mutes = environment
+                .repository
+                .map_config(|c| c.keyword_mutes.clone())
+                .unwrap_or_default();
+            state.invalid_mutes = state
+                .keyword_mutes
+                .iter()
+                .filter_map(|mute| {
+                    let error = keyword_mute_regex(mute).err()?;
+                    Some((mute.clone(), error.to_string()))
+                })
+                .collect();
+            return Effect::future(async move { model.filters().await }, FiltersAction::Loaded);
+        }
+        FiltersAction::Loaded(result) => {
+            state.is_loading = false;
+            match result {
+                Ok(filters) => {
+                    state.filters = filters;
+                    update_content_filter(state, environment);
+                }
+                Err(e) => state.error = Some(e),
+            }
+        }
+        FiltersAction::New => state.editor = Some(FilterEditor::new()),
+        FiltersAction::Edit(id) => {
+            state.editor = state
+                .filters
+                .iter()
+                .find(|f| f.id == id)
+                .map(FilterEditor::edit);
+        }
+        FiltersAction::CancelEdit => state.editor = None,
+        FiltersAction::UpdateTitle(title) => {
+            if let Some(editor) = state.editor.as_mut() {
+                editor.title = title;
+            }
+        }
+        FiltersAction::ToggleContext(context) => {
+            if let Some(editor) = state.editor.as_mut() {
+                if !editor.context.remove(&context) {
+                    editor.context.insert(context);
+                }
+            }
+        }
+        FiltersAction::SelectAction(filter_action) => {
+            if let Some(editor) = state.editor.as_mut() {
+                editor.filter_action = filter_action;
+            }
+        }
+        FiltersAction::SelectExpiration(expiration) => {
+            if let Some(editor) = state.editor.as_mut() {
+                editor.expiration = expiration;
+            }
+        }
+        FiltersAction::AddKeyword => {
+            if let Some(editor) = state.editor.as_mut() {
+                editor.keywords.push(EditedKeyword::new());
+            }
+        }
+        FiltersAction::UpdateKeyword(index, keyword) => {
+            if let Some(edited) = state
+                .editor
+                .as_mut()
+                .and_then(|e| e.keywords.get_mut(index))
+            {
+                edited.keyword = keyword;
+            }
+        }
+        FiltersAction::UpdateWholeWord(index, whole_word) => {
+            if let Some(edited) = state
+                .editor
+                .as_mut()
+                .and_then(|e| e.keywords.get_mut(index))
+            {
+                edited.whole_word = whole_word;
+            }
+        }
+        FiltersAction::RemoveKeyword(index) => {
+            if let Some(editor) = state.editor.as_mut().filter(|e| index < e.keywords.len()) {
+                editor.keywords.remove(index);
+            }
+        }
+        FiltersAction::Save => {
+            let Some(editor) = state.editor.as_ref().filter(|e| e.can_save()) else {
+                return Effect::NONE
+            };
+            let id = editor.id.clone();
+            let input = editor.input();
+            state.is_loading = true;
+            return Effect::future(
+                async move { model.save_filter(id, input).await },
+                FiltersAction::Saved,
+            );
+        }
+        FiltersAction::Saved(result) => {
+            state.is_loading = false;
+            match result {
+                Ok(filter) => {
+                    match state.filters.iter_mut().find(|f| f.id == filter.id) {
+                        Some(existing) => *existing = filter,
+                        None => state.filters.push(filter),
+                    }
+                    state.editor = None;
+                    update_content_filter(state, environment);
+                }
+                Err(e) => state.error = Some(e),
+            }
+        }
+        FiltersAction::Delete(id) => {
+            let Some(filter) = state.filters.iter().find(|f| f.id == id) else {
+                return Effect::NONE
+            };
+            if !crate::environment::platform::confirm_dialog(
+                &filter.title,
+                loc!("Do you really want to delete this filter?"),
+            ) {
+                return Effect::NONE;
+            }
+            state.is_loading = true;
+            let cloned_id = id.clone();
+            return Effect::future(
+                async move { model.delete_filter(cloned_id).await },
+                move |result| FiltersAction::Deleted(id, result),
+            );
+        }
+        FiltersAction::Deleted(id, result) => {
+            state.is_loading = false;
+            match result {
+                Ok(_) => {
+                    state.filters.retain(|f| f.id != id);
+                    if state.editor.as_ref().and_then(|e| e.id.as_ref()) == Some(&id) {
+                        state.editor = None;
+                    }
+                    update_content_filter(state, environment);
+                }
+                Err(e) => state.error = Some(e),
+            }
+        }
+        FiltersAction::UpdateNewMute(mute) => state.new_mute = mute,
+        FiltersAction::AddMute => {
+            let mute = state.new_mute.trim().to_string();
+            if mute.is_empty() || state.keyword_mutes.contains(&mute) {
+                return Effect::NONE;
+            }
+            if let Err(e) = keyword_mute_regex(&mute) {
+                state.error = Some(format!("{}: {e}", loc!("Invalid regular expression")));
+                return Effect::NONE;
+            }
+            state.keyword_mutes.push(mute);
+            state.new_mute.clear();
+            save_keyword_mutes(state, environment);
+        }
+        FiltersAction::RemoveMute(mute) => {
+            state.keyword_mutes.retain(|m| m != &mute);
+            state.invalid_mutes.remove(&mute);
+            save_keyword_mutes(state, environment);
+        }
+        FiltersAction::ClearError => state.error = None,
+    }
+    Effect::NONE
+}
+
+fn save_keyword_mutes(state: &mut FiltersState, environment: &Environment) {
+    let mutes = state.keyword_mutes.clone();
+    if let Err(e) = environment
+        .repository
+        .map_config(move |c| c.keyword_mutes = mutes)
+    {
+        state.error = Some(e);
+    }
+    update_content_filter(state, environment);
+}
+
+/// Apply the changed filters to the loaded timelines
+fn update_content_filter(state: &FiltersState, environment: &Environment) {
+    let filter = ContentFilter::new(&state.filters, &state.keyword_mutes);
+    environment
+        .storage
+        .with_mutation(|mut s| s.set_content_filter(filter));
+}
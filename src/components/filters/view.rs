@@ -0,0 +1,302 @@
+use dioxus::prelude::*;
+
+use super::reducer::{FilterEditor, FiltersAction, ViewStore, EXPIRATIONS};
+use crate::environment::model::Filter;
+use crate::environment::types::{FilterAction, FilterContext};
+use crate::loc;
+use crate::widgets::*;
+
+#[inline_props]
+pub fn FiltersComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
+    let placeholder = loc!("Regular expression, e.g. (?i)spoiler");
+    render! {
+        div {
+            class: "filters-component",
+            VStack {
+                div {
+                    class: "scroll content-cell-container",
+                    { store.error.as_ref().map(|error| rsx!(ErrorBox {
+                        content: error.clone(),
+                        onclick: move |_| store.send(FiltersAction::ClearError)
+                    }))}
+
+                    { store.is_loading.then(|| rsx!(div {
+                        class: "hstack p-2 m-2 grow align-self-center",
+                        Spinner {}
+                    }))}
+
+                    HStack { class: "align-items-center p-2 pt-3",
+                        Label {
+                            class: "me-auto",
+                            style: TextStyle::Secondary,
+                            loc!("Server Filters")
+                        }
+                        button {
+                            class: "button",
+                            disabled: "{store.editor.is_some()}",
+                            onclick: move |_| store.send(FiltersAction::New),
+                            loc!("New Filter")
+                        }
+                    }
+
+                    (store.filters.is_empty() && !store.is_loading).then(|| rsx!(div {
+                        class: "p-2",
+                        Label {
+                            style: TextStyle::Tertiary,
+                            loc!("No filters")
+                        }
+                    }))
+
+                    for filter in &store.filters {
+                        FilterRow {
+                            key: "{filter.id}",
+                            store: store,
+                            filter: filter,
+                        }
+                    }
+
+                    { store.editor.as_ref().map(|editor| rsx!(FilterEditorView {
+                        store: store,
+                        editor: editor,
+                    }))}
+
+                    div { class: "p-2 pt-3",
+                        Label {
+                            style: TextStyle::Secondary,
+                            loc!("Local Keyword Mutes")
+                        }
+                        Paragraph {
+                            style: TextStyle::Tertiary,
+                            loc!("Posts matching these are hidden everywhere. They are only stored on this device.")
+                        }
+                    }
+
+                    for mute in &store.keyword_mutes {
+                        div {
+                            key: "{mute}",
+                            class: "filter-entry hstack align-items-center p-2 gap-2",
+                            VStack { class: "me-auto",
+                                Label {
+                                    "{mute}"
+                                }
+                                { store.invalid_mutes.get(mute).map(|error| rsx!(Label {
+                                    style: TextStyle::Tertiary,
+                                    "{error}"
+                                }))}
+                            }
+                            button {
+                                class: "button",
+                                onclick: move |_| store.send(FiltersAction::RemoveMute(mute.clone())),
+                                loc!("Remove")
+                            }
+                        }
+                    }
+
+                    HStack { class: "align-items-center p-2 gap-2",
+                        input {
+                            class: "grow",
+                            r#type: "text",
+                            placeholder: "{placeholder}",
+                            value: "{store.new_mute}",
+                            autocomplete: "off",
+                            spellcheck: "false",
+                            oninput: move |evt| store.send(FiltersAction::UpdateNewMute(evt.value.clone())),
+                        }
+                        button {
+                            class: "button",
+                            disabled: "{store.new_mute.trim().is_empty()}",
+                            onclick: move |_| store.send(FiltersAction::AddMute),
+                            loc!("Add")
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn FilterRow<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>, filter: &'a Filter) -> Element<'a> {
+    let contexts = filter
+        .context
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let keywords = format!("{} {}", filter.keywords.len(), loc!("keywords"));
+    render! {
+        HStack { class: "filter-entry align-items-center p-2 gap-2",
+            VStack { class: "me-auto",
+                Label {
+                    "{filter.title}"
+                }
+                Label {
+                    style: TextStyle::Tertiary,
+                    "{contexts} · {keywords} · {filter.filter_action}"
+                }
+            }
+            button {
+                class: "button",
+                disabled: "{store.is_loading}",
+                onclick: move |_| store.send(FiltersAction::Edit(filter.id.clone())),
+                loc!("Edit")
+            }
+            button {
+                class: "button",
+                disabled: "{store.is_loading}",
+                onclick: move |_| store.send(FiltersAction::Delete(filter.id.clone())),
+                loc!("Delete")
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn FilterEditorView<'a>(
+    cx: Scope<'a>,
+    store: &'a ViewStore<'a>,
+    editor: &'a FilterEditor,
+) -> Element<'a> {
+    let title_placeholder = loc!("Title");
+    let keyword_placeholder = loc!("Keyword or phrase");
+    let can_save = editor.can_save() && !store.is_loading;
+    let current_expiration = editor.expires_at.map(|expires_at| {
+        format!("{} ({})", loc!("Keep current"), expires_at.format("%Y-%m-%d %H:%M"))
+    });
+    render! {
+        VStack {
+            class: "filter-editor gap-2 p-2",
+            input {
+                r#type: "text",
+                placeholder: "{title_placeholder}",
+                value: "{editor.title}",
+                oninput: move |evt| store.send(FiltersAction::UpdateTitle(evt.value.clone())),
+            }
+
+            Label {
+                style: TextStyle::Secondary,
+                loc!("Filter in")
+            }
+            for (index, context) in FilterContext::all().into_iter().enumerate() {
+                HStack {
+                    class: "align-items-center gap-2",
+                    input {
+                        r#type: "checkbox",
+                        id: "filter-context-{index}",
+                        checked: "{editor.context.contains(&context)}",
+                        oninput: move |_| store.send(FiltersAction::ToggleContext(context)),
+                    }
+                    label {
+                        class: "label-secondary",
+                        r#for: "filter-context-{index}",
+                        "{context}"
+                    }
+                }
+            }
+
+            HStack {
+                class: "justify-content-between align-items-center",
+                Label {
+                    style: TextStyle::Secondary,
+                    loc!("Action")
+                }
+                select {
+                    onchange: move |evt| {
+                        let Some(filter_action) = evt.value.parse::<usize>().ok()
+                            .and_then(|i| FilterAction::all().get(i).copied()) else {
+                            return
+                        };
+                        store.send(FiltersAction::SelectAction(filter_action));
+                    },
+                    for (index, filter_action) in FilterAction::all().iter().enumerate() {
+                        option {
+                            value: "{index}",
+                            selected: "{editor.filter_action == *filter_action}",
+                            "{filter_action}"
+                        }
+                    }
+                }
+            }
+
+            HStack {
+                class: "justify-content-between align-items-center",
+                Label {
+                    style: TextStyle::Secondary,
+                    loc!("Expires")
+                }
+                select {
+                    onchange: move |evt| {
+                        store.send(FiltersAction::SelectExpiration(evt.value.parse::<usize>().ok()))
+                    },
+                    current_expiration.map(|title| rsx!(option {
+                        value: "current",
+                        selected: "{editor.expiration.is_none()}",
+                        "{title}"
+                    }))
+                    for (index, (title, _)) in EXPIRATIONS.iter().enumerate() {
+                        option {
+                            value: "{index}",
+                            selected: "{editor.expiration == Some(index)}",
+                            "{title}"
+                        }
+                    }
+                }
+            }
+
+            Label {
+                style: TextStyle::Secondary,
+                loc!("Keywords")
+            }
+            for (index, edited) in editor.keywords.iter().enumerate() {
+                HStack {
+                    class: "align-items-center gap-2",
+                    input {
+                        class: "grow",
+                        r#type: "text",
+                        placeholder: "{keyword_placeholder}",
+                        value: "{edited.keyword}",
+                        oninput: move |evt| store.send(FiltersAction::UpdateKeyword(index, evt.value.clone())),
+                    }
+                    input {
+                        r#type: "checkbox",
+                        id: "filter-whole-word-{index}",
+                        checked: "{edited.whole_word}",
+                        oninput: move |evt| store.send(FiltersAction::UpdateWholeWord(index, evt.value.parse().unwrap_or_default())),
+                    }
+                    label {
+                        class: "label-secondary",
+                        r#for: "filter-whole-word-{index}",
+                        loc!("Whole word")
+                    }
+                    button {
+                        class: "button",
+                        onclick: move |_| store.send(FiltersAction::RemoveKeyword(index)),
+                        loc!("Remove")
+                    }
+                }
+            }
+            HStack { class: "align-items-center",
+                button {
+                    class: "button",
+                    onclick: move |_| store.send(FiltersAction::AddKeyword),
+                    loc!("Add Keyword")
+                }
+            }
+
+            HStack { class: "align-items-center gap-2",
+                span { class: "me-auto" }
+                button {
+                    class: "button",
+                    onclick: move |_| store.send(FiltersAction::CancelEdit),
+                    loc!("Cancel")
+                }
+                button {
+                    class: "button",
+                    disabled: "{!can_save}",
+                    onclick: move |_| store.send(FiltersAction::Save),
+                    loc!("Save")
+                }
+            }
+        }
+    }
+}
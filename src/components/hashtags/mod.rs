@@ -0,0 +1,37 @@
+mod reducer;
+mod view;
+
+pub use reducer::{reduce, HashtagsAction, HashtagsState, ViewStore};
+pub use view::{HashtagsComponent, TagCellComponent};
+
+pub struct HashtagsReducer;
+use navicula::reducer::Reducer;
+
+impl Reducer for HashtagsReducer {
+    type Message = crate::environment::types::AppEvent;
+
+    type DelegateMessage = crate::PublicAction;
+
+    type Action = reducer::HashtagsAction;
+
+    type State = reducer::HashtagsState;
+
+    type Environment = crate::environment::Environment;
+
+    fn reduce<'a, 'b>(
+        context: &'a impl navicula::types::MessageContext<
+            Self::Action,
+            Self::DelegateMessage,
+            Self::Message,
+        >,
+        action: Self::Action,
+        state: &'a mut Self::State,
+        environment: &'a Self::Environment,
+    ) -> navicula::effect::Effect<'b, Self::Action> {
+        reducer::reduce(context, action, state, environment)
+    }
+
+    fn initial_action() -> Option<Self::Action> {
+        Some(HashtagsAction::Initial)
+    }
+}
@@ -0,0 +1,160 @@
+use crate::environment::model::Tag;
+use crate::environment::types::{AppEvent, MainMenuEvent};
+use crate::environment::Environment;
+use crate::view_model::{AccountViewModel, TagViewModel};
+use navicula::Effect;
+
+pub type ViewStore<'a> = navicula::ViewStore<'a, super::HashtagsReducer>;
+
+#[derive(Clone, Debug)]
+pub enum HashtagsAction {
+    Initial,
+    Load,
+    Loaded(Result<Vec<Tag>, String>),
+    DataChanged,
+    /// Open a tag (with or without `#`)
+    Open(String),
+    LoadedTag(Result<Tag, String>),
+    ToggleFollow,
+    Followed(Result<Tag, String>),
+    AppEvent(AppEvent),
+    Public(crate::PublicAction),
+    ClearError,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct HashtagsState {
+    pub account: AccountViewModel,
+    pub followed: Vec<TagViewModel>,
+    /// The name of the tag whose timeline is shown
+    pub selected: Option<String>,
+    pub tag: Option<TagViewModel>,
+    pub is_loading: bool,
+    pub error: Option<String>,
+}
+
+impl HashtagsState {
+    pub fn new(account: AccountViewModel) -> Self {
+        Self {
+            account,
+            ..Default::default()
+        }
+    }
+}
+
+pub fn reduce<'a>(
+    context: &'a impl navicula::types::MessageContext<HashtagsAction, crate::PublicAction, AppEvent>,
+    action: HashtagsAction,
+    state: &'a mut HashtagsState,
+    environment: &'a Environment,
+) -> Effect<'static, HashtagsAction> {
+    log::trace!("{action:?}");
+    match action {
+        HashtagsAction::Initial => {
+            return Effect::merge3(
+                environment
+                    .storage
+                    .subscribe("hashtags_reducer", context, |_| HashtagsAction::DataChanged),
+                Effect::action(HashtagsAction::Load),
+                Effect::action(HashtagsAction::DataChanged),
+            );
+        }
+        HashtagsAction::Load => {
+            state.is_loading = true;
+            let model = environment.model.clone();
+            return Effect::future(
+                async move { model.followed_tags().await },
+                HashtagsAction::Loaded,
+            );
+        }
+        HashtagsAction::Loaded(result) => {
+            state.is_loading = false;
+            match result {
+                Ok(tags) => {
+                    let mut tags: Vec<_> = tags.iter().map(TagViewModel::new).collect();
+                    tags.sort_by_key(|e| e.name.to_lowercase());
+                    state.followed = tags;
+                }
+                Err(e) => state.error = Some(e),
+            }
+        }
+        HashtagsAction::DataChanged => {
+            let selected = environment
+                .storage
+                .with(|data| data.selected_hashtag.clone());
+            if selected == state.selected {
+                return Effect::NONE;
+            }
+            state.selected = selected.clone();
+            // Show what we know until the tag is loaded
+            state.tag = selected
+                .as_ref()
+                .and_then(|name| state.followed.iter().find(|e| &e.name == name).cloned());
+            let Some(name) = selected else {
+                return Effect::NONE
+            };
+            let model = environment.model.clone();
+            return Effect::future(
+                async move { model.tag(name).await },
+                HashtagsAction::LoadedTag,
+            );
+        }
+        HashtagsAction::Open(name) => {
+            let name = name.trim().trim_start_matches('#').to_string();
+            if name.is_empty() {
+                return Effect::NONE;
+            }
+            environment
+                .storage
+                .with_mutation(|mut storage| storage.selected_hashtag = Some(name));
+        }
+        HashtagsAction::LoadedTag(result) => match result {
+            Ok(tag) => {
+                let tag = TagViewModel::new(&tag);
+                if state.selected.as_ref() == Some(&tag.name) {
+                    state.tag = Some(tag);
+                }
+            }
+            Err(e) => state.error = Some(e),
+        },
+        HashtagsAction::ToggleFollow => {
+            let Some(tag) = state.tag.as_ref() else {
+                return Effect::NONE
+            };
+            state.is_loading = true;
+            let model = environment.model.clone();
+            let (name, follow) = (tag.name.clone(), !tag.following);
+            return Effect::future(
+                async move { model.follow_tag(name, follow).await },
+                HashtagsAction::Followed,
+            );
+        }
+        HashtagsAction::Followed(result) => {
+            state.is_loading = false;
+            match result {
+                Ok(tag) => {
+                    let tag = TagViewModel::new(&tag);
+                    state.followed.retain(|e| e.name != tag.name);
+                    if tag.following {
+                        state.followed.push(tag.clone());
+                        state.followed.sort_by_key(|e| e.name.to_lowercase());
+                    }
+                    if state.selected.as_ref() == Some(&tag.name) {
+                        state.tag = Some(tag);
+                    }
+                }
+                Err(e) => state.error = Some(e),
+            }
+        }
+        HashtagsAction::AppEvent(event) => {
+            let is_reload = matches!(event, AppEvent::MenuEvent(MainMenuEvent::Reload));
+            context.send_children(event);
+            if is_reload {
+                return Effect::action(HashtagsAction::Load);
+            }
+        }
+        HashtagsAction::Public(action) => context.send_parent(action),
+        HashtagsAction::ClearError => state.error = None,
+    }
+    Effect::NONE
+}
@@ -0,0 +1,180 @@
+use dioxus::prelude::*;
+use navicula::reducer::ChildReducer;
+
+use super::reducer::{HashtagsAction, ViewStore};
+use super::HashtagsReducer;
+use crate::components::component_stack::{RootTimelineKind, StackReducer};
+use crate::loc;
+use crate::view_model::TagViewModel;
+use crate::widgets::*;
+
+#[inline_props]
+pub fn HashtagsComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
+    let search = use_state(cx, String::new);
+    let is_empty = store.followed.is_empty() && !store.is_loading;
+    render! {
+        div {
+            class: "hashtags-component",
+            div {
+                class: "hashtags-list scroll",
+                VStack { class: "p-3 gap-2",
+                    HStack { class: "gap-2 align-items-center",
+                        input {
+                            class: "grow",
+                            r#type: "text",
+                            value: "{search}",
+                            placeholder: "#hashtag",
+                            autocomplete: "off",
+                            spellcheck: "false",
+                            oninput: move |evt| search.set(evt.value.clone()),
+                        }
+                        button {
+                            class: "button",
+                            disabled: "{search.trim().is_empty()}",
+                            onclick: move |_| {
+                                store.send(HashtagsAction::Open(search.get().clone()));
+                                search.set(String::new());
+                            },
+                            loc!("Open")
+                        }
+                    }
+
+                    { store.error.as_ref().map(|error| rsx!(ErrorBox {
+                        content: error.clone(),
+                        onclick: move |_| store.send(HashtagsAction::ClearError)
+                    }))}
+
+                    SidebarTextHeadline { text: loc!("Followed Hashtags") }
+
+                    { store.is_loading.then(|| rsx!(div {
+                        class: "hstack p-2 m-2 grow align-self-center",
+                        Spinner {}
+                    }))}
+
+                    is_empty.then(|| rsx!(Label {
+                        style: TextStyle::Secondary,
+                        loc!("You don't follow any hashtags")
+                    }))
+
+                    for tag in &store.followed {
+                        TagCellComponent {
+                            key: "{tag.name}",
+                            tag: tag,
+                            selected: store.selected.as_ref() == Some(&tag.name),
+                            onclick: move |name| store.send(HashtagsAction::Open(name))
+                        }
+                    }
+                }
+            }
+            TagContentComponent {
+                store: store
+            }
+        }
+    }
+}
+
+#[inline_props]
+pub fn TagCellComponent<'a>(
+    cx: Scope<'a>,
+    tag: &'a TagViewModel,
+    selected: bool,
+    onclick: EventHandler<'a, String>,
+) -> Element<'a> {
+    let class = selected.then(|| "selected").unwrap_or_default();
+    let description = tag.activity_description();
+    render! {
+        div {
+            class: "hashtag-cell {class} no-selection force-pointer",
+            onclick: move |_| onclick.call(tag.name.clone()),
+            VStack { class: "gap-1",
+                Label {
+                    style: TextStyle::Primary,
+                    pointer_style: PointerStyle::Pointer,
+                    "#{tag.name}"
+                }
+                Label {
+                    style: TextStyle::Tertiary,
+                    pointer_style: PointerStyle::Pointer,
+                    "{description}"
+                }
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn TagContentComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
+    let Some(selected) = store.selected.as_ref() else {
+        return render!(div {
+            class: "hashtags-content p-3",
+            Label {
+                style: TextStyle::Secondary,
+                loc!("Select a hashtag")
+            }
+        })
+    };
+    let description = store
+        .tag
+        .as_ref()
+        .map(|e| e.activity_description())
+        .unwrap_or_default();
+    let follow_title = match store.tag.as_ref().map(|e| e.following) {
+        Some(true) => loc!("Unfollow"),
+        _ => loc!("Follow"),
+    };
+    let account = store.account.clone();
+    use crate::components::component_stack::{Stack, State};
+    render! {
+        div {
+            class: "hashtags-content",
+            HStack { class: "hashtags-header p-3 gap-2 align-items-center",
+                VStack { class: "gap-1 grow",
+                    Label {
+                        style: TextStyle::Primary,
+                        "#{selected}"
+                    }
+                    Label {
+                        style: TextStyle::Tertiary,
+                        "{description}"
+                    }
+                }
+                button {
+                    class: "button",
+                    disabled: "{store.is_loading || store.tag.is_none()}",
+                    onclick: move |_| store.send(HashtagsAction::ToggleFollow),
+                    "{follow_title}"
+                }
+            }
+            Stack {
+                store: store.host_with(
+                    cx,
+                    selected,
+                    |tag| State::new(RootTimelineKind::Hashtag(account.clone(), tag))
+                )
+            }
+        }
+    }
+}
+
+impl ChildReducer<HashtagsReducer> for StackReducer {
+    fn to_child(
+        message: <HashtagsReducer as navicula::Reducer>::Message,
+    ) -> Option<<Self as navicula::Reducer>::Action> {
+        Some(crate::components::component_stack::Action::AppEvent(
+            message,
+        ))
+    }
+
+    fn from_child(
+        message: <Self as navicula::Reducer>::DelegateMessage,
+    ) -> Option<<HashtagsReducer as navicula::Reducer>::Action> {
+        match message {
+            crate::components::component_stack::DelegateMessage::PublicAction(a) => {
+                Some(HashtagsAction::Public(a))
+            }
+            crate::components::component_stack::DelegateMessage::ConversationAction(a) => {
+                Some(HashtagsAction::Public(a))
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+mod reducer;
+mod view;
+
+pub use reducer::{reduce, ListsAction, ListsDelegate, ListsState, ViewStore};
+pub use view::ListsComponent;
+
+pub struct ListsReducer;
+use navicula::reducer::Reducer;
+
+impl Reducer for ListsReducer {
+    type Message = ();
+
+    type DelegateMessage = reducer::ListsDelegate;
+
+    type Action = reducer::ListsAction;
+
+    type State = reducer::ListsState;
+
+    type Environment = crate::environment::Environment;
+
+    fn reduce<'a, 'b>(
+        context: &'a impl navicula::types::MessageContext<
+            Self::Action,
+            Self::DelegateMessage,
+            Self::Message,
+        >,
+        action: Self::Action,
+        state: &'a mut Self::State,
+        environment: &'a Self::Environment,
+    ) -> navicula::effect::Effect<'b, Self::Action> {
+        reducer::reduce(context, action, state, environment)
+    }
+
+    fn initial_action() -> Option<Self::Action> {
+        Some(ListsAction::Load)
+    }
+}
@@ -0,0 +1,208 @@
+use crate::environment::model::AccountList;
+use crate::environment::storage::ListRequest;
+use crate::environment::types::{ListInput, ListRepliesPolicy};
+use crate::environment::Environment;
+use crate::loc;
+use navicula::Effect;
+
+pub type ViewStore<'a> = navicula::ViewStore<'a, super::ListsReducer>;
+
+#[derive(Clone, Debug)]
+pub enum ListsAction {
+    Load,
+    Loaded(Result<Vec<AccountList>, String>),
+    New,
+    Edit(String),
+    CancelEdit,
+    UpdateTitle(String),
+    SelectRepliesPolicy(ListRepliesPolicy),
+    UpdateExclusive(bool),
+    Save,
+    Saved(Result<AccountList, String>),
+    Delete(String),
+    Deleted(String, Result<(), String>),
+    ShowMembers(String),
+    ClearError,
+}
+
+#[derive(Clone, Debug)]
+pub enum ListsDelegate {
+    /// Show the members of the list
+    ShowMembers(AccountList),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct ListsState {
+    pub lists: Vec<AccountList>,
+    pub editor: Option<ListEditor>,
+    pub is_loading: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct ListEditor {
+    /// `None` for a new list
+    pub id: Option<String>,
+    pub title: String,
+    pub replies_policy: ListRepliesPolicy,
+    pub exclusive: bool,
+}
+
+impl ListEditor {
+    pub fn edit(list: &AccountList) -> Self {
+        Self {
+            id: Some(list.id.clone()),
+            title: list.title.clone(),
+            replies_policy: list.replies_policy,
+            exclusive: list.exclusive,
+        }
+    }
+
+    pub fn can_save(&self) -> bool {
+        !self.title.trim().is_empty()
+    }
+
+    fn input(&self) -> ListInput {
+        ListInput {
+            title: self.title.trim().to_string(),
+            replies_policy: self.replies_policy,
+            exclusive: self.exclusive,
+        }
+    }
+}
+
+pub fn reduce<'a>(
+    context: &'a impl navicula::types::MessageContext<ListsAction, ListsDelegate, ()>,
+    action: ListsAction,
+    state: &'a mut ListsState,
+    environment: &'a Environment,
+) -> Effect<'static, ListsAction> {
+    log::trace!("{action:?}");
+    let model = environment.model.clone();
+    match action {
+        ListsAction::Load => {
+            state.is_loading = true;
+            return Effect::future(async move { model.lists().await }, ListsAction::Loaded);
+        }
+        ListsAction::Loaded(result) => {
+            state.is_loading = false;
+            match result {
+                Ok(mut lists) => {
+                    lists.sort_by_key(|e| e.title.to_lowercase());
+                    state.lists = lists;
+                    update_timelines(state, environment);
+                }
+                Err(e) => state.error = Some(e),
+            }
+            // The sidebar asked us to create or edit a list
+            let request = environment
+                .storage
+                .with_mutation(|mut storage| storage.list_request.take());
+            match request {
+                Some(ListRequest::New) => return Effect::action(ListsAction::New),
+                Some(ListRequest::Edit(id)) => return Effect::action(ListsAction::Edit(id)),
+                None => (),
+            }
+        }
+        ListsAction::New => state.editor = Some(ListEditor::default()),
+        ListsAction::Edit(id) => {
+            state.editor = state
+                .lists
+                .iter()
+                .find(|e| e.id == id)
+                .map(ListEditor::edit);
+        }
+        ListsAction::CancelEdit => state.editor = None,
+        ListsAction::UpdateTitle(title) => {
+            if let Some(editor) = state.editor.as_mut() {
+                editor.title = title;
+            }
+        }
+        ListsAction::SelectRepliesPolicy(replies_policy) => {
+            if let Some(editor) = state.editor.as_mut() {
+                editor.replies_policy = replies_policy;
+            }
+        }
+        ListsAction::UpdateExclusive(exclusive) => {
+            if let Some(editor) = state.editor.as_mut() {
+                editor.exclusive = exclusive;
+            }
+        }
+        ListsAction::Save => {
+            let Some(editor) = state.editor.as_ref().filter(|e| e.can_save()) else {
+                return Effect::NONE
+            };
+            let id = editor.id.clone();
+            let input = editor.input();
+            state.is_loading = true;
+            return Effect::future(
+                async move { model.save_list(id, input).await },
+                ListsAction::Saved,
+            );
+        }
+        ListsAction::Saved(result) => {
+            state.is_loading = false;
+            match result {
+                Ok(list) => {
+                    match state.lists.iter_mut().find(|e| e.id == list.id) {
+                        Some(existing) => *existing = list,
+                        None => state.lists.push(list),
+                    }
+                    state.lists.sort_by_key(|e| e.title.to_lowercase());
+                    state.editor = None;
+                    update_timelines(state, environment);
+                }
+                Err(e) => state.error = Some(e),
+            }
+        }
+        ListsAction::Delete(id) => {
+            let Some(list) = state.lists.iter().find(|e| e.id == id) else {
+                return Effect::NONE
+            };
+            if !crate::environment::platform::confirm_dialog(
+                &list.title,
+                loc!("Do you really want to delete this list?"),
+            ) {
+                return Effect::NONE;
+            }
+            state.is_loading = true;
+            let cloned_id = id.clone();
+            return Effect::future(
+                async move { model.delete_list(cloned_id).await },
+                move |result| ListsAction::Deleted(id, result),
+            );
+        }
+        ListsAction::Deleted(id, result) => {
+            state.is_loading = false;
+            match result {
+                Ok(_) => {
+                    state.lists.retain(|e| e.id != id);
+                    if state.editor.as_ref().and_then(|e| e.id.as_ref()) == Some(&id) {
+                        state.editor = None;
+                    }
+                    update_timelines(state, environment);
+                }
+                Err(e) => state.error = Some(e),
+            }
+        }
+        ListsAction::ShowMembers(id) => {
+            if let Some(list) = state.lists.iter().find(|e| e.id == id) {
+                context.send_parent(ListsDelegate::ShowMembers(list.clone()));
+            }
+        }
+        ListsAction::ClearError => state.error = None,
+    }
+    Effect::NONE
+}
+
+/// Update the lists of the sidebar
+fn update_timelines(state: &ListsState, environment: &Environment) {
+    let timelines: Vec<_> = state
+        .lists
+        .iter()
+        .map(|e| (e.title.clone(), e.id.clone()))
+        .collect();
+    environment
+        .storage
+        .with_mutation(|mut s| s.update_timelines(&timelines));
+}
@@ -0,0 +1,189 @@
+use dioxus::prelude::*;
+
+use super::reducer::{ListEditor, ListsAction, ViewStore};
+use crate::environment::model::AccountList;
+use crate::environment::types::ListRepliesPolicy;
+use crate::loc;
+use crate::widgets::*;
+
+#[inline_props]
+pub fn ListsComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
+    render! {
+        div {
+            class: "filters-component",
+            VStack {
+                div {
+                    class: "scroll content-cell-container",
+                    { store.error.as_ref().map(|error| rsx!(ErrorBox {
+                        content: error.clone(),
+                        onclick: move |_| store.send(ListsAction::ClearError)
+                    }))}
+
+                    { store.is_loading.then(|| rsx!(div {
+                        class: "hstack p-2 m-2 grow align-self-center",
+                        Spinner {}
+                    }))}
+
+                    HStack { class: "align-items-center p-2 pt-3",
+                        Label {
+                            class: "me-auto",
+                            style: TextStyle::Secondary,
+                            loc!("Lists")
+                        }
+                        button {
+                            class: "button",
+                            disabled: "{store.editor.is_some()}",
+                            onclick: move |_| store.send(ListsAction::New),
+                            loc!("New List")
+                        }
+                    }
+
+                    { store.editor.as_ref().filter(|e| e.id.is_none()).map(|editor| rsx!(ListEditorView {
+                        store: store,
+                        editor: editor,
+                    }))}
+
+                    (store.lists.is_empty() && !store.is_loading).then(|| rsx!(div {
+                        class: "p-2",
+                        Label {
+                            style: TextStyle::Tertiary,
+                            loc!("No lists")
+                        }
+                    }))
+
+                    for list in &store.lists {
+                        ListRow {
+                            key: "{list.id}",
+                            store: store,
+                            list: list,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn ListRow<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>, list: &'a AccountList) -> Element<'a> {
+    // The editor of an existing list is shown in place of its row
+    if let Some(editor) = store
+        .editor
+        .as_ref()
+        .filter(|e| e.id.as_ref() == Some(&list.id))
+    {
+        return render!(ListEditorView {
+            store: store,
+            editor: editor,
+        });
+    }
+    let exclusive = list
+        .exclusive
+        .then(|| format!(" · {}", loc!("Hidden from home")))
+        .unwrap_or_default();
+    render! {
+        HStack { class: "filter-entry align-items-center p-2 gap-2",
+            VStack { class: "me-auto",
+                Label {
+                    "{list.title}"
+                }
+                Label {
+                    style: TextStyle::Tertiary,
+                    "{list.replies_policy}{exclusive}"
+                }
+            }
+            button {
+                class: "button",
+                onclick: move |_| store.send(ListsAction::ShowMembers(list.id.clone())),
+                loc!("Members")
+            }
+            button {
+                class: "button",
+                disabled: "{store.is_loading || store.editor.is_some()}",
+                onclick: move |_| store.send(ListsAction::Edit(list.id.clone())),
+                loc!("Edit")
+            }
+            button {
+                class: "button",
+                disabled: "{store.is_loading}",
+                onclick: move |_| store.send(ListsAction::Delete(list.id.clone())),
+                loc!("Delete")
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn ListEditorView<'a>(
+    cx: Scope<'a>,
+    store: &'a ViewStore<'a>,
+    editor: &'a ListEditor,
+) -> Element<'a> {
+    let title_placeholder = loc!("Title");
+    let can_save = editor.can_save() && !store.is_loading;
+    render! {
+        VStack {
+            class: "filter-editor gap-2 p-2",
+            input {
+                r#type: "text",
+                placeholder: "{title_placeholder}",
+                value: "{editor.title}",
+                oninput: move |evt| store.send(ListsAction::UpdateTitle(evt.value.clone())),
+            }
+
+            HStack {
+                class: "justify-content-between align-items-center",
+                Label {
+                    style: TextStyle::Secondary,
+                    loc!("Show replies")
+                }
+                select {
+                    onchange: move |evt| {
+                        let Some(policy) = evt.value.parse::<usize>().ok()
+                            .and_then(|i| ListRepliesPolicy::all().get(i).copied()) else {
+                            return
+                        };
+                        store.send(ListsAction::SelectRepliesPolicy(policy));
+                    },
+                    for (index, policy) in ListRepliesPolicy::all().iter().enumerate() {
+                        option {
+                            value: "{index}",
+                            selected: "{editor.replies_policy == *policy}",
+                            "{policy}"
+                        }
+                    }
+                }
+            }
+
+            HStack {
+                class: "align-items-center gap-2",
+                input {
+                    r#type: "checkbox",
+                    id: "list-exclusive",
+                    checked: "{editor.exclusive}",
+                    oninput: move |evt| store.send(ListsAction::UpdateExclusive(evt.value.parse().unwrap_or_default())),
+                }
+                label {
+                    class: "label-secondary",
+                    r#for: "list-exclusive",
+                    loc!("Hide posts of members from the home timeline")
+                }
+            }
+
+            HStack { class: "align-items-center gap-2",
+                span { class: "me-auto" }
+                button {
+                    class: "button",
+                    onclick: move |_| store.send(ListsAction::CancelEdit),
+                    loc!("Cancel")
+                }
+                button {
+                    class: "button",
+                    disabled: "{!can_save}",
+                    onclick: move |_| store.send(ListsAction::Save),
+                    loc!("Save")
+                }
+            }
+        }
+    }
+}
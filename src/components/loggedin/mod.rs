@@ -3,7 +3,9 @@ pub mod view;
 use crate::environment::Environment;
 use reducer::ReducerState;
 
-pub use reducer::{Action, SCOPE_UPDATER};
+pub use reducer::{
+    mark_conversations_read, subscribe_stream, Action, StreamSubscription, SCOPE_UPDATER,
+};
 pub use view::LoggedInApp;
 
 type ViewStore<'a> = navicula::ViewStore<'a, RootReducer>;
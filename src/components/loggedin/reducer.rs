@@ -1,13 +1,21 @@
 use crate::behaviours::{Behaviour, ChangeTextsizeBehaviour};
-use crate::components::post::{PostAction, PostKind};
-use crate::environment::model::{Account, Message, Model, Status};
-use crate::environment::storage::{Data, UiTab};
-use crate::environment::types::{AppEvent, FileEvent, MainMenuEvent, UiConfig};
-use crate::environment::Environment;
+use crate::components::post::PostAction;
+use crate::environment::filters::ContentFilter;
+use crate::environment::model::{
+    Account, Filter, Model, Notification, Poll, PostedStatus, ServerMarkers, Status, StatusSource,
+    StatusVisibility,
+};
+use crate::environment::storage::{CachedData, Data, UiTab};
+use crate::environment::types::{
+    AccountModeration, AppEvent, CacheRetention, FileEvent, MainMenuEvent, PostKind, StreamEvent,
+    StreamKind, StreamState, UiConfig,
+};
+use crate::environment::{Environment, Repository};
 use crate::widgets::StatusAction;
 use crate::windows::image_window::{ImageWindowKind, ImageWindowState};
 use crate::windows::post_window::PostWindowState;
 use crate::windows::preferences_window::{PreferencesChange, PreferencesWindowState};
+use crate::windows::report_window::ReportWindowState;
 use crate::{loc, view_model::*};
 use crate::{PublicAction, StatusMutation};
 use debug_panic::debug_panic;
@@ -15,13 +23,18 @@ use navicula::publisher::RefPublisher;
 use navicula::Effect;
 
 use crate::components::sidebar::MoreSelection;
-use std::cell::{Cell, RefMut};
+use std::cell::{RefCell, RefMut};
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 const NEW_TOOT_TITLE: &str = "New Toot";
+const EDIT_TOOT_TITLE: &str = "Edit Toot";
 const NEW_TOOT_SIZE: (f64, f64) = (420., 320.);
+const REPORT_SIZE: (f64, f64) = (480., 640.);
+/// How often the data of the active account is written to the cache
+const CACHE_INTERVAL: Duration = Duration::from_secs(120);
 
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct State {
@@ -51,15 +64,33 @@ pub struct ReducerState {
     pub active_tab: UiTab,
     pub selected_account: Option<AccountViewModel>,
     pub selected_notifications: Option<AccountViewModel>,
+    pub selected_notification_group: Option<NotificationGroupViewModel>,
+    pub selected_direct_conversation: Option<DirectConversationViewModel>,
 
     pub error: Option<String>,
-    pub did_logout: Cell<Option<bool>>,
+    /// A pending change of the active account. Switching accounts requires
+    /// replacing the environment, which happens in the view
+    pub account_change: RefCell<Option<AccountChange>>,
     pub has_new_notifications: bool,
     pub logged_in: bool,
     pub is_replying: Option<(PostKind, Vec<PathBuf>)>,
     /// The current more-selection
     pub more_selection: MoreSelection,
     pub current_user: Option<Account>,
+    /// The server can't be reached, the content comes from the cache
+    pub is_offline: bool,
+    /// The connection of the user stream of the active account
+    pub stream_state: StreamState,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum AccountChange {
+    /// Make the account with this id the active one
+    Switch(String),
+    /// Show the login to add another account
+    Add,
+    /// The account with this id was logged out
+    Removed(String),
 }
 
 // Terrible hack to allow updating the app on drag and drop events.
@@ -72,6 +103,82 @@ lazy_static::lazy_static! {
     pub static ref SCOPE_UPDATER: ScopeUpdaterMutex = Mutex::new(None);
 }
 
+// The streams of all accounts outlive the root reducer of the
+// active account (which is re-created when switching accounts). They
+// always deliver to the most recent updater
+pub type StreamUpdaterMutex =
+    Mutex<Option<Arc<dyn Fn(String, StreamKind, StreamEvent) + Send + Sync>>>;
+lazy_static::lazy_static! {
+    pub static ref STREAM_UPDATER: StreamUpdaterMutex = Mutex::new(None);
+}
+
+/// The events of a stream of an account, delivered to the root reducer
+pub fn stream_sender(id: String, kind: StreamKind) -> Arc<dyn Fn(StreamEvent) + Send + Sync> {
+    Arc::new(move |event| {
+        let Ok(updater) = STREAM_UPDATER.lock() else {
+            return
+        };
+        if let Some(updater) = updater.as_ref() {
+            updater(id.clone(), kind.clone(), event)
+        }
+    })
+}
+
+/// Keeps a stream open until it is dropped, e.g. with the timeline that shows it
+#[derive(Debug)]
+pub struct StreamSubscription {
+    model: Model,
+    kind: StreamKind,
+}
+
+impl Drop for StreamSubscription {
+    fn drop(&mut self) {
+        self.model.release_stream(&self.kind);
+    }
+}
+
+/// Subscribe to a stream of the active account. Timelines that show the
+/// same stream share it
+pub fn subscribe_stream<A>(
+    environment: &Environment,
+    kind: StreamKind,
+) -> (Arc<StreamSubscription>, Effect<'static, A>) {
+    let model = environment.model.clone();
+    model.retain_stream(&kind);
+    let subscription = Arc::new(StreamSubscription {
+        model: model.clone(),
+        kind: kind.clone(),
+    });
+    let Some(id) = environment.accounts.active() else {
+        return (subscription, Effect::NONE)
+    };
+    let effect = Effect::fire_forget(async move {
+        let sender = stream_sender(id, kind.clone());
+        if let Err(e) = model.subscribe_stream(kind, sender).await {
+            log::error!("Could not subscribe to stream: {e}");
+        }
+    });
+    (subscription, effect)
+}
+
+/// Tell the server that the direct message conversations were read
+pub fn mark_conversations_read<A>(
+    environment: &Environment,
+    unread_ids: Vec<String>,
+) -> Effect<'static, A> {
+    if unread_ids.is_empty() {
+        return Effect::NONE;
+    }
+    let model = environment.model.clone();
+    Effect::fire_forget(async move {
+        for id in unread_ids {
+            if let Err(e) = model.mark_conversation_read(id).await {
+                log::error!("Could not mark conversation as read: {e}");
+            }
+        }
+    })
+}
+
 pub fn reduce<'a>(
     context: &'a impl navicula::types::MessageContext<Action, Action, Action>,
     action: Action,
@@ -96,12 +203,23 @@ pub fn reduce<'a>(
 
     match action {
         Action::Login => {
+            // A new root reducer is created whenever the active account
+            // changes, so the file drop handler has to use the new one
+            if let Ok(mut m) = SCOPE_UPDATER.lock() {
+                let updater = context.updater().clone();
+                *m = Some(Arc::new(move |app_event| {
+                    updater(Action::AppEvent(app_event))
+                }));
+            }
             reducer_state.ui_settings = environment.repository.config().unwrap_or_default();
             reducer_state.flags.logging_in = true;
+            // Show the content of the previous launch until the server answers
+            hydrate_cache(reducer_state.ui_settings.cache_retention, environment);
             return Effect::future(async move { model.login().await }, Action::LoggedIn);
         }
         Action::LoggedIn(result) => {
             reducer_state.flags.logging_in = false;
+            let mut recover_draft = Effect::NONE;
             match result {
                 Ok(n) => {
                     reducer_state.current_user = Some(n.clone());
@@ -113,6 +231,18 @@ pub fn reduce<'a>(
                         false,
                     );
 
+                    // If the app didn't quit properly, the composer was still
+                    // open. Open it again so that the draft is restored
+                    if let Some(draft) = environment
+                        .repository
+                        .drafts(&n.id)
+                        .into_iter()
+                        .find(|e| e.is_open)
+                    {
+                        reducer_state.user_account = Some(n.clone());
+                        recover_draft = Effect::action(Action::Post(draft.kind));
+                    }
+
                     environment.storage.with_mutation(|mut s| {
                         s.user_account = Some(n);
                     });
@@ -121,6 +251,14 @@ pub fn reduce<'a>(
                     });
                     reducer_state.logged_in = true;
                 }
+                Err(e) if environment.storage.with(|s| s.has_content()) => {
+                    // Without a connection, the cached content can still be read
+                    log::error!("Login Error: {e:?}");
+                    environment
+                        .storage
+                        .with_mutation(|mut s| s.is_offline = true);
+                    reducer_state.logged_in = true;
+                }
                 Err(e) => {
                     reducer_state.logged_in = false;
                     reducer_state.error = Some(format!("Login Error: {e:?}"))
@@ -128,33 +266,120 @@ pub fn reduce<'a>(
             }
 
             let updater = context.updater().clone();
+            if let Ok(mut m) = STREAM_UPDATER.lock() {
+                *m = Some(Arc::new(move |id, kind, event| {
+                    // We have to wrap the updater into an updater that maps
+                    updater(Action::StreamEvent(id, kind, event))
+                }));
+            }
 
-            // The future to start the subscription
+            // Subscribe to the user stream of every account that isn't
+            // streaming yet. Inactive accounts have to log in first.
+            let pending: Vec<_> = environment
+                .accounts
+                .all()
+                .into_iter()
+                .filter(|(_, account)| {
+                    account.model.backend.supports_streaming()
+                        && !account.model.is_streaming(&StreamKind::User)
+                })
+                .map(|(id, account)| (id, account.model))
+                .collect();
+
+            // The future to start the subscriptions
             let fut = Effect::fire_forget(async move {
-                let _ = model
-                    .subscribe_user_stream(std::sync::Arc::new(move |msg| {
-                        // We have to wrap the updater into an updater that maps
-                        updater(Action::MessageEvent(msg))
-                    }))
-                    .await;
+                for (id, model) in pending {
+                    if !model.is_loggedin() && model.login().await.is_err() {
+                        log::error!("Could not log in account {id} for streaming");
+                        continue;
+                    }
+                    // The user stream stays open as long as the account exists
+                    model.retain_stream(&StreamKind::User);
+                    let sender = stream_sender(id.clone(), StreamKind::User);
+                    if let Err(e) = model.subscribe_stream(StreamKind::User, sender).await {
+                        log::error!("Could not subscribe account {id} to its stream: {e}");
+                    }
+                }
             });
 
-            Effect::merge2(
+            // Server side filters have to be known before the timelines
+            // can be filtered. The markers have the read positions
+            // from other clients
+            let (filters, markers) = if reducer_state.logged_in && model.is_loggedin() {
+                let cloned_model = model.clone();
+                (
+                    Effect::future(async move { model.filters().await }, Action::FiltersLoaded),
+                    Effect::future(
+                        async move { cloned_model.markers().await },
+                        Action::MarkersLoaded,
+                    ),
+                )
+            } else {
+                (Effect::NONE, Effect::NONE)
+            };
+
+            Effect::merge6(
                 // Start the subscription
                 fut,
                 // Subscribe to storage changes
                 environment
                     .storage
                     .subscribe("root_reducer", context, |_| Action::DataUpdated),
+                recover_draft,
+                filters,
+                markers,
+                Effect::timer(CACHE_INTERVAL, Action::SaveCache, "root-save-cache"),
             )
         }
+        Action::SaveCache => save_cache(reducer_state.ui_settings.cache_retention, environment),
+        Action::FiltersLoaded(result) => {
+            // Servers without v2 filters still get the local keyword mutes
+            let filters = result
+                .map_err(|e| log::error!("Could not load filters: {e}"))
+                .unwrap_or_default();
+            let keyword_mutes = environment
+                .repository
+                .map_config(|c| c.keyword_mutes.clone())
+                .unwrap_or_default();
+            let filter = ContentFilter::new(&filters, &keyword_mutes);
+            environment
+                .storage
+                .with_mutation(|mut s| s.set_content_filter(filter));
+            Effect::NONE
+        }
+        Action::MarkersLoaded(result) => {
+            match result {
+                Ok(markers) => environment
+                    .storage
+                    .with_mutation(|mut s| s.markers = markers),
+                Err(e) => log::error!("Could not load markers: {e}"),
+            }
+            Effect::NONE
+        }
         Action::DataUpdated => {
+            let was_offline = reducer_state.is_offline;
             environment.storage.with(|s| {
+                reducer_state.is_offline = s.is_offline;
+                reducer_state.stream_state = s.stream_state;
                 reducer_state.user_account = s.user_account.clone();
                 reducer_state.active_tab = s.active_tab;
                 reducer_state.selected_account = s.selected_account.clone();
                 reducer_state.selected_notifications = s.selected_notifications.clone();
+                reducer_state.selected_notification_group = s
+                    .selected_notification_group
+                    .as_ref()
+                    .and_then(|id| s.notification_groups.iter().find(|g| &g.id == id))
+                    .cloned();
+                reducer_state.selected_direct_conversation = s
+                    .selected_direct_conversation
+                    .as_ref()
+                    .and_then(|id| s.direct_conversations.iter().find(|c| &c.id == id))
+                    .cloned();
             });
+            // We started offline and the server is reachable again
+            if was_offline && !reducer_state.is_offline && !model.is_loggedin() {
+                return Effect::action(Action::Login);
+            }
             Effect::NONE
         }
         Action::SelectAccount(account) => {
@@ -166,9 +391,25 @@ pub fn reduce<'a>(
         Action::SelectNotifications(account) => {
             environment.storage.with_mutation(|mut storage| {
                 storage.selected_notifications = Some(account.clone());
+                storage.selected_notification_group = None;
             });
             Effect::NONE
         }
+        Action::SelectNotificationGroup(id) => {
+            environment.storage.with_mutation(|mut storage| {
+                storage.selected_notification_group = Some(id);
+                storage.selected_notifications = None;
+            });
+            Effect::NONE
+        }
+        Action::SelectDirectConversation(id) => {
+            let unread_ids = environment.storage.with_mutation(|mut storage| {
+                let unread_ids = storage.mark_direct_conversation_read(&id);
+                storage.selected_direct_conversation = Some(id);
+                unread_ids
+            });
+            mark_conversations_read(environment, unread_ids)
+        }
 
         Action::SelectConversation(status) => {
             context.send_children(Action::SelectConversation(status));
@@ -223,18 +464,82 @@ pub fn reduce<'a>(
                     Effect::NONE
                 }
                 PublicAction::OpenTag(name) => {
+                    // Tags are shown in the hashtags section of the more tab
                     let name = name.replace('#', "");
+                    environment
+                        .storage
+                        .with_mutation(|mut storage| storage.selected_hashtag = Some(name));
+                    Effect::action(Action::SelectMore(MoreSelection::Hashtags))
+                }
+                PublicAction::VotePoll(status, choices) => {
+                    let Some(poll_id) = status.poll.as_ref().map(|e| e.id.clone()) else {
+                        return Effect::NONE
+                    };
+                    let status_id = status.id.0.clone();
+                    Effect::future(
+                        async move { model.vote_poll(poll_id, choices, status_id).await },
+                        move |result| Action::VotePollResult(result, status.clone()),
+                    )
+                }
+                PublicAction::DeleteStatus(status, redraft) => {
+                    let title = if redraft {
+                        loc!("Delete & Redraft")
+                    } else {
+                        loc!("Delete")
+                    };
+                    if !crate::environment::platform::confirm_dialog(
+                        title,
+                        loc!("Do you really want to delete this post?"),
+                    ) {
+                        return Effect::NONE;
+                    }
+                    let id = status.id.0.clone();
                     Effect::future(
                         async move {
-                            model
-                                .tag(name.clone())
-                                .await
-                                .map(|e| e.url)
-                                .unwrap_or_else(|_| format!("https://mastodon.social/tags/{name}"))
+                            // The text as it was written is gone once the status is deleted
+                            let source = match redraft {
+                                true => model.status_source(id.clone()).await.ok(),
+                                false => None,
+                            };
+                            model.delete_status(id).await.map(|_| source)
+                        },
+                        move |result| Action::DeleteStatusResult(result, status.clone(), redraft),
+                    )
+                }
+                PublicAction::Moderate(account, moderation) => {
+                    if let Some(message) = moderation.confirmation() {
+                        if !crate::environment::platform::confirm_dialog(&account.acct, message) {
+                            return Effect::NONE;
+                        }
+                    }
+                    let id = account.id.0.clone();
+                    let domain = account.domain().map(|e| e.to_string());
+                    Effect::future(
+                        async move { model.moderate(id, domain, moderation).await },
+                        move |result| {
+                            Action::ModerateResult(result.map(|_| ()), account.clone(), moderation)
                         },
-                        |a| Action::Public(PublicAction::OpenLink(a)),
                     )
                 }
+                PublicAction::UpdateListMembership(account, list_id, add) => {
+                    let id = account.id.0.clone();
+                    Effect::future(
+                        async move { model.update_list_membership(list_id, id, add).await },
+                        Action::ListMembershipResult,
+                    )
+                }
+                PublicAction::Report(account, status) => {
+                    let title = format!("Report {}", account.acct);
+                    environment.open_window(
+                        window,
+                        ReportWindowState::new(account, status),
+                        REPORT_SIZE.0,
+                        REPORT_SIZE.1,
+                        title,
+                        Rc::new(|_: ()| {}),
+                    );
+                    Effect::NONE
+                }
                 PublicAction::OpenImage(url) => {
                     environment.open_window(
                         window,
@@ -310,24 +615,84 @@ pub fn reduce<'a>(
                 Effect::NONE
             }
         },
+        Action::VotePollResult(result, status) => {
+            match result {
+                Ok(poll) => {
+                    let poll = PollViewModel::new(&poll);
+                    environment.storage.with_mutation(|mut s| {
+                        s.mutate_post(status.id.clone(), status.account.id.clone(), |post| {
+                            post.poll = Some(poll.clone());
+                        })
+                    });
+                }
+                Err(e) => {
+                    reducer_state.error = Some(format!("Could not vote: {e}"));
+                }
+            }
+            Effect::NONE
+        }
+        Action::DeleteStatusResult(result, mut status, redraft) => match result {
+            Ok(source) => {
+                environment.storage.with_mutation(|mut s| s.remove_post(&status.id));
+                if let Some(source) = source {
+                    status.text = source.text;
+                    status.spoiler_text = source.spoiler_text;
+                }
+                if redraft {
+                    Effect::action(Action::Post(PostKind::Redraft(status)))
+                } else {
+                    Effect::NONE
+                }
+            }
+            Err(e) => {
+                reducer_state.error = Some(format!("Could not delete: {e}"));
+                Effect::NONE
+            }
+        },
+        Action::ModerateResult(result, account, moderation) => {
+            match result {
+                Ok(_) => {
+                    environment
+                        .storage
+                        .with_mutation(|mut s| s.apply_moderation(&account, moderation));
+                }
+                Err(e) => {
+                    reducer_state.error = Some(format!("Could not mute or block: {e}"));
+                }
+            }
+            Effect::NONE
+        }
+        Action::ListMembershipResult(result) => {
+            if let Err(e) = result {
+                reducer_state.error = Some(format!("Could not update list: {e}"));
+            }
+            Effect::NONE
+        }
         Action::Post(kind) => {
             // Convert a notifications reply into a private reply (mastodon web)
-            // does the same thing
+            // does the same thing. Replies to direct messages stay direct
             let kind = match (kind, reducer_state.active_tab) {
-                (PostKind::Reply(n), UiTab::Mentions) => PostKind::ReplyPrivate(n),
+                (PostKind::Reply(n), UiTab::Mentions | UiTab::Messages) => {
+                    PostKind::ReplyPrivate(n)
+                }
                 (PostKind::Reply(n), _) => PostKind::Reply(n),
                 (PostKind::ReplyPrivate(n), _) => PostKind::ReplyPrivate(n),
                 (PostKind::Post, _) => PostKind::Post,
+                (PostKind::Edit(n), _) => PostKind::Edit(n),
+                (PostKind::Redraft(n), _) => PostKind::Redraft(n),
+                (PostKind::Quote(n), _) => PostKind::Quote(n),
             };
             if inline_window {
                 reducer_state.is_replying = Some((kind, vec![]));
                 return Effect::NONE;
             }
             let title = match &kind {
-                &PostKind::Post => NEW_TOOT_TITLE.to_string(),
+                &PostKind::Post | &PostKind::Redraft(_) => NEW_TOOT_TITLE.to_string(),
+                &PostKind::Edit(_) => EDIT_TOOT_TITLE.to_string(),
                 &PostKind::Reply(ref status) | &PostKind::ReplyPrivate(ref status) => {
                     format!("Reply to {}", status.account.acct)
                 }
+                &PostKind::Quote(ref status) => format!("Quote {}", status.account.acct),
             };
             let Some(ref account) = reducer_state.user_account else {
                 return Effect::NONE
@@ -362,7 +727,12 @@ pub fn reduce<'a>(
             if let Some(ref reply_id) = status.in_reply_to_id {
                 storage.replied_to_status(reply_id);
             }
+            // If this was an edit, update the existing copies
+            storage.replace_post(&status);
             storage.possibly_update_conversation_with_reply(&status);
+            if matches!(status.visibility, StatusVisibility::Direct) {
+                storage.update_direct_messages(&[status]);
+            }
             Effect::NONE
         }),
         Action::Preferences => {
@@ -373,7 +743,7 @@ pub fn reduce<'a>(
                 window,
                 PreferencesWindowState::new(),
                 500.,
-                300.,
+                420.,
                 loc!("Settings"),
                 mapped_waker,
             );
@@ -387,9 +757,13 @@ pub fn reduce<'a>(
                         reducer_state.ui_settings = s;
                     }
                 }
-                PreferencesChange::PostWindow => {
+                PreferencesChange::PostWindow | PreferencesChange::SensitiveContent => {
                     reducer_state.ui_settings = environment.repository.config().unwrap_or_default();
                 }
+                PreferencesChange::Cache => {
+                    reducer_state.ui_settings = environment.repository.config().unwrap_or_default();
+                    return save_cache(reducer_state.ui_settings.cache_retention, environment);
+                }
             }
             Effect::NONE
         }
@@ -443,6 +817,11 @@ pub fn reduce<'a>(
         }
         Action::AppEvent(AppEvent::ClosingWindow) => {
             // FIXME: Maybe stop listening to events?
+            // The app may quit before a background task is done, so this is written right away
+            let retention = reducer_state.ui_settings.cache_retention;
+            if let Some((id, cache, max_age)) = cache_snapshot(retention, environment) {
+                write_cache(&environment.repository, &id, cache, max_age);
+            }
             Effect::NONE
         }
         Action::AppEvent(AppEvent::MenuEvent(m)) => {
@@ -454,7 +833,24 @@ pub fn reduce<'a>(
                     });
                     Effect::action(Action::Logout)
                 }
-                MainMenuEvent::ScrollDown | MainMenuEvent::ScrollUp => {
+                MainMenuEvent::AddAccount => Effect::action(Action::AddAccount),
+                MainMenuEvent::NextAccount => {
+                    let ids = environment.accounts.ids();
+                    let active = environment.accounts.active();
+                    let position = ids
+                        .iter()
+                        .position(|id| Some(id) == active.as_ref())
+                        .unwrap_or_default();
+                    match ids.into_iter().cycle().nth(position + 1) {
+                        Some(next) if Some(&next) != active.as_ref() => {
+                            Effect::action(Action::SwitchAccount(next))
+                        }
+                        _ => Effect::NONE,
+                    }
+                }
+                MainMenuEvent::ScrollDown
+                | MainMenuEvent::ScrollUp
+                | MainMenuEvent::JumpToLastRead => {
                     context.send_children(action);
                     Effect::NONE
                 }
@@ -487,10 +883,76 @@ pub fn reduce<'a>(
                 }
             }
         }
-        Action::MessageEvent(message) => {
-            environment.storage.with_mutation(|mut storage| {
-                storage.handle_push_message(message, reducer_state.ui_settings.direction)
-            });
+        Action::StreamEvent(id, kind, event) => {
+            // Events for inactive accounts go into their own storage
+            let (storage, model) = environment
+                .accounts
+                .get(&id)
+                .map(|account| (account.storage, account.model))
+                .unwrap_or_else(|| (environment.storage.clone(), model));
+            let direction = reducer_state.ui_settings.direction;
+            match event {
+                StreamEvent::State(stream_state) => {
+                    // Only the user stream is shown
+                    if kind == StreamKind::User {
+                        storage.with_mutation(|mut s| s.stream_state = stream_state);
+                    }
+                    Effect::NONE
+                }
+                StreamEvent::Reconnected => {
+                    // Fetch what was posted while the stream was disconnected
+                    let cloned_kind = kind.clone();
+                    Effect::future(
+                        async move {
+                            let statuses = model.stream_timeline(cloned_kind.clone()).await?;
+                            let notifications = match cloned_kind {
+                                StreamKind::User => model.notifications(None, 0).await?,
+                                _ => Vec::new(),
+                            };
+                            Ok::<_, String>((statuses, notifications))
+                        },
+                        move |result| Action::StreamCaughtUp(id.clone(), kind.clone(), result),
+                    )
+                }
+                StreamEvent::Message(message) => {
+                    storage
+                        .with_mutation(|mut s| s.handle_stream_message(&kind, message, direction));
+                    Effect::NONE
+                }
+            }
+        }
+        Action::StreamCaughtUp(id, kind, result) => {
+            let storage = environment
+                .accounts
+                .get(&id)
+                .map(|account| account.storage)
+                .unwrap_or_else(|| environment.storage.clone());
+            let direction = reducer_state.ui_settings.direction;
+            match result {
+                Ok((statuses, notifications)) => storage.with_mutation(|mut s| {
+                    s.merge_stream_statuses(&kind, &statuses, direction);
+                    s.update_notifications(&notifications);
+                }),
+                Err(e) => log::error!("Could not catch up with {kind:?}: {e}"),
+            }
+            Effect::NONE
+        }
+        Action::SwitchAccount(id) => {
+            if environment.accounts.active().as_ref() == Some(&id) {
+                return Effect::NONE;
+            }
+            if let Err(e) = environment.repository.set_active_user(&id) {
+                log::error!("Could not switch account: {e:?}");
+                return Effect::NONE;
+            }
+            let save = save_cache(reducer_state.ui_settings.cache_retention, environment);
+            reducer_state
+                .account_change
+                .replace(Some(AccountChange::Switch(id)));
+            save
+        }
+        Action::AddAccount => {
+            reducer_state.account_change.replace(Some(AccountChange::Add));
             Effect::NONE
         }
         Action::ClearError => {
@@ -500,10 +962,21 @@ pub fn reduce<'a>(
         Action::Logout => {
             reducer_state.flags.logging_in = true;
             environment.platform.loggedout_toolbar(window);
-            let Some(user) = environment.repository.users().ok().and_then(|e| e.first().cloned()) else {
+            let Some(active) = environment
+                .accounts
+                .active()
+                .or_else(|| reducer_state.current_user.as_ref().map(|e| e.id.clone())) else {
+                return Effect::NONE
+            };
+            let Some(user) = environment
+                .repository
+                .users()
+                .ok()
+                .and_then(|e| e.into_iter().find(|user| user.id == active)) else {
                 return Effect::NONE
             };
             let _ = environment.repository.remove_user(user.id.clone());
+            let id = user.id.clone();
             Effect::future(
                 async move {
                     model
@@ -514,12 +987,14 @@ pub fn reduce<'a>(
                         )
                         .await
                 },
-                Action::LogoutDone,
+                move |result| Action::LogoutDone(id.clone(), result),
             )
         }
-        Action::LogoutDone(_) => {
+        Action::LogoutDone(id, _) => {
             reducer_state.logged_in = false;
-            reducer_state.did_logout.set(Some(true));
+            reducer_state
+                .account_change
+                .replace(Some(AccountChange::Removed(id)));
             reducer_state.flags.logging_in = false;
             environment.platform.update_menu(window, |config| {
                 config.logged_in = true;
@@ -529,6 +1004,73 @@ pub fn reduce<'a>(
     }
 }
 
+/// Fill the (empty) data of the active account from the cache
+fn hydrate_cache(retention: CacheRetention, environment: &Environment) {
+    let Some(max_age) = retention.max_age() else {
+        return
+    };
+    let Some(id) = environment.accounts.active() else {
+        return
+    };
+    if environment.storage.with(|s| s.has_content()) {
+        return;
+    }
+    let Some(mut cache) = environment.repository.cache(&id) else {
+        return
+    };
+    cache.retain_since(chrono::Utc::now() - max_age);
+    environment.storage.with_mutation(|mut s| s.hydrate(cache));
+}
+
+/// Write the data of the active account to the cache in the background.
+/// If the cache is disabled, the existing one is removed
+fn save_cache<A>(retention: CacheRetention, environment: &Environment) -> Effect<'static, A> {
+    let Some((id, cache, max_age)) = cache_snapshot(retention, environment) else {
+        return Effect::NONE
+    };
+    let repository = environment.repository.clone();
+    Effect::fire_forget(async move {
+        let written =
+            tokio::task::spawn_blocking(move || write_cache(&repository, &id, cache, max_age))
+                .await;
+        if let Err(e) = written {
+            log::error!("Could not save cache: {e:?}");
+        }
+    })
+}
+
+/// The data to cache. This clones the cacheable part of the storage and
+/// `write_cache` rewrites the whole file. That is fine because the data is
+/// bounded by the retention, the write happens off the main thread and
+/// only every `CACHE_INTERVAL`, and a single file can't be left half-updated
+fn cache_snapshot(
+    retention: CacheRetention,
+    environment: &Environment,
+) -> Option<(String, CachedData, chrono::Duration)> {
+    let id = environment.accounts.active()?;
+    let Some(max_age) = retention.max_age() else {
+        environment.repository.remove_cache(&id);
+        return None;
+    };
+    let cache = environment.storage.with(|s| s.cache());
+    Some((id, cache, max_age))
+}
+
+fn write_cache(
+    repository: &Repository,
+    id: &str,
+    mut cache: CachedData,
+    max_age: chrono::Duration,
+) {
+    cache.retain_since(chrono::Utc::now() - max_age);
+    if cache.is_empty() {
+        return;
+    }
+    if let Err(e) = repository.save_cache(id, &cache) {
+        log::error!("Could not save cache: {e:?}");
+    }
+}
+
 fn mutate_status<'a>(
     storage: &RefPublisher<Data>,
     mutation: StatusMutation,
@@ -573,15 +1115,24 @@ fn mutate_status<'a>(
 pub enum Action {
     Login,
     LoggedIn(Result<Account, String>),
+    FiltersLoaded(Result<Vec<Filter>, String>),
+    MarkersLoaded(Result<ServerMarkers, String>),
     DataUpdated,
+    SaveCache,
 
     SelectAccount(AccountViewModel),
     SelectNotifications(AccountViewModel),
+    SelectNotificationGroup(String),
+    SelectDirectConversation(String),
     SelectConversation(StatusId),
     SelectMore(MoreSelection),
 
     Public(PublicAction),
     StatusMutationResult(Result<Status, String>, StatusViewModel, StatusMutation),
+    VotePollResult(Result<Poll, String>, StatusViewModel),
+    DeleteStatusResult(Result<Option<StatusSource>, String>, StatusViewModel, bool),
+    ModerateResult(Result<(), String>, AccountViewModel, AccountModeration),
+    ListMembershipResult(Result<(), String>),
 
     Post(PostKind),
     PostDone(Status),
@@ -591,11 +1142,19 @@ pub enum Action {
     PreferencesChanged(PreferencesChange),
 
     AppEvent(AppEvent),
-    MessageEvent(Message),
+    StreamEvent(String, StreamKind, StreamEvent),
+    StreamCaughtUp(
+        String,
+        StreamKind,
+        Result<(Vec<Status>, Vec<Notification>), String>,
+    ),
+
+    SwitchAccount(String),
+    AddAccount,
 
     ClearError,
     Logout,
-    LogoutDone(Result<(), String>),
+    LogoutDone(String, Result<(), String>),
 }
 
 impl std::fmt::Debug for Action {
@@ -603,15 +1162,28 @@ impl std::fmt::Debug for Action {
         match self {
             Self::Login => write!(f, "Login"),
             Self::DataUpdated => write!(f, "DataUpdated"),
+            Self::SaveCache => write!(f, "SaveCache"),
             Self::Logout => write!(f, "Logout"),
-            Self::LogoutDone(_) => write!(f, "LogoutDone"),
+            Self::LogoutDone(arg0, _) => f.debug_tuple("LogoutDone").field(arg0).finish(),
+            Self::SwitchAccount(arg0) => f.debug_tuple("SwitchAccount").field(arg0).finish(),
+            Self::AddAccount => write!(f, "AddAccount"),
             Self::ClearError => write!(f, "ClearError"),
             Self::LoggedIn(arg0) => f.debug_tuple("LoggedIn").field(arg0).finish(),
+            Self::FiltersLoaded(arg0) => f.debug_tuple("FiltersLoaded").field(arg0).finish(),
+            Self::MarkersLoaded(arg0) => f.debug_tuple("MarkersLoaded").field(arg0).finish(),
 
             Self::SelectAccount(arg0) => f.debug_tuple("SelectAccount").field(arg0).finish(),
             Self::SelectNotifications(arg0) => {
                 f.debug_tuple("SelectNotifications").field(arg0).finish()
             }
+            Self::SelectNotificationGroup(arg0) => f
+                .debug_tuple("SelectNotificationGroup")
+                .field(arg0)
+                .finish(),
+            Self::SelectDirectConversation(arg0) => f
+                .debug_tuple("SelectDirectConversation")
+                .field(arg0)
+                .finish(),
 
             Self::SelectConversation(arg0) => {
                 f.debug_tuple("SelectConversation").field(arg0).finish()
@@ -623,6 +1195,26 @@ impl std::fmt::Debug for Action {
                 .field(arg1)
                 .field(arg2)
                 .finish(),
+            Self::VotePollResult(arg0, arg1) => f
+                .debug_tuple("VotePollResult")
+                .field(arg0)
+                .field(arg1)
+                .finish(),
+            Self::DeleteStatusResult(arg0, arg1, arg2) => f
+                .debug_tuple("DeleteStatusResult")
+                .field(arg0)
+                .field(arg1)
+                .field(arg2)
+                .finish(),
+            Self::ModerateResult(arg0, arg1, arg2) => f
+                .debug_tuple("ModerateResult")
+                .field(arg0)
+                .field(arg1)
+                .field(arg2)
+                .finish(),
+            Self::ListMembershipResult(arg0) => {
+                f.debug_tuple("ListMembershipResult").field(arg0).finish()
+            }
             Self::Public(_arg0) => f.debug_tuple("Public").finish(),
             Self::Post(kind) => f.debug_tuple("PostNew").field(kind).finish(),
             Self::PostDone(kind) => f.debug_tuple("PostDone").field(&kind.id).finish(),
@@ -630,14 +1222,24 @@ impl std::fmt::Debug for Action {
             Self::Preferences => write!(f, "Preferences"),
             Self::PreferencesChanged(_) => write!(f, "PreferencesChanged"),
             Self::AppEvent(kind) => f.debug_tuple("AppEvent").field(&kind).finish(),
-            Self::MessageEvent(kind) => f.debug_tuple("MessageEvent").field(&kind).finish(),
+            Self::StreamEvent(id, kind, event) => f
+                .debug_tuple("StreamEvent")
+                .field(id)
+                .field(kind)
+                .field(event)
+                .finish(),
+            Self::StreamCaughtUp(id, kind, _) => f
+                .debug_tuple("StreamCaughtUp")
+                .field(id)
+                .field(kind)
+                .finish(),
         }
     }
 }
 
 fn handle_new_post_window_event(action: PostAction) -> Option<Action> {
     match action {
-        PostAction::PostResult(Ok(status)) => Some(Action::PostDone(status)),
+        PostAction::PostResult(Ok(PostedStatus::Status(status))) => Some(Action::PostDone(status)),
         PostAction::Close => None,
         PostAction::AppEvent(AppEvent::MenuEvent(evt)) => {
             Some(Action::AppEvent(AppEvent::MenuEvent(evt)))
@@ -666,6 +1268,15 @@ impl From<(StatusAction, StatusViewModel)> for PublicAction {
             StatusAction::OpenImage(a) => PublicAction::OpenImage(a),
             StatusAction::OpenVideo(a) => PublicAction::OpenVideo(a),
             StatusAction::Copy(a) => PublicAction::Copy(a),
+            StatusAction::VotePoll(choices) => PublicAction::VotePoll(value.1.clone(), choices),
+            StatusAction::Edit => PublicAction::Post(PostKind::Edit(value.1.clone())),
+            StatusAction::Delete => PublicAction::DeleteStatus(value.1.clone(), false),
+            StatusAction::Redraft => PublicAction::DeleteStatus(value.1.clone(), true),
+            StatusAction::Report => {
+                PublicAction::Report(value.1.account.clone(), Some(value.1.clone()))
+            }
+            StatusAction::Quote => PublicAction::Post(PostKind::Quote(value.1.clone())),
+            StatusAction::OpenQuote(quote) => PublicAction::Conversation(quote),
         }
     }
 }
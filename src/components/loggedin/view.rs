@@ -3,14 +3,17 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::components::more::MoreReducer;
-use crate::components::post::PostKind;
-use crate::environment::types::{AppEvent, FileEvent, MainMenuEvent};
+use crate::environment::types::PostKind;
+use crate::environment::types::{
+    AppEvent, Backend, FileEvent, MainMenuEvent, StreamState, UiConfig,
+};
 use crate::view_model::*;
 
 use navicula::root;
 
-use super::reducer::{Action, ReducerState};
-use crate::environment::{model::Model, Environment};
+use super::reducer::{AccountChange, Action, ReducerState};
+use crate::environment::Environment;
+use crate::loc;
 
 use super::RootReducer;
 use super::ViewStore;
@@ -58,17 +61,62 @@ pub fn LoggedInApp<'a>(
         ReducerState::default()
     });
 
+    // Share the ui settings with the widgets (e.g. for sensitive content)
+    use_shared_state_provider(cx, || view_store.ui_settings.clone());
+    if let Some(settings) = use_shared_state::<UiConfig>(cx) {
+        if *settings.read() != view_store.ui_settings {
+            *settings.write() = view_store.ui_settings.clone();
+        }
+    }
+
+    // Share our account so that our own posts can be edited / deleted
+    let account_id = CurrentAccountId(
+        view_store
+            .user_account
+            .as_ref()
+            .map(|e| AccountId(e.id.clone())),
+    );
+    use_shared_state_provider(cx, || account_id.clone());
+    if let Some(current) = use_shared_state::<CurrentAccountId>(cx) {
+        if *current.read() != account_id {
+            *current.write() = account_id;
+        }
+    }
+
+    // Share the server software so that quotes are only offered where they work
+    let backend = environment.get().model.backend;
+    use_shared_state_provider(cx, || backend);
+    if let Some(current) = use_shared_state::<Backend>(cx) {
+        if *current.read() != backend {
+            *current.write() = backend;
+        }
+    }
+
     // FIXME: At some point, move the side effects so that it also works
     // with one absolutely-root reducer
-    if view_store.did_logout.get().is_some() {
+    if let Some(change) = view_store.account_change.take() {
         let mut mutable_environment = environment.get().clone();
-        mutable_environment.update_model(Model::default());
-        environment.set(mutable_environment);
-        should_show_login.set(true);
-        view_store.did_logout.set(None);
+        match change {
+            AccountChange::Switch(id) => {
+                if mutable_environment.switch_account(&id) {
+                    environment.set(mutable_environment);
+                }
+            }
+            AccountChange::Add => {
+                should_show_login.set(true);
+            }
+            AccountChange::Removed(id) => {
+                if mutable_environment.remove_account(&id).is_none() {
+                    should_show_login.set(true);
+                }
+                environment.set(mutable_environment);
+            }
+        }
     }
 
     let is_dropping = view_store.flags.is_dropping;
+    let is_offline = view_store.is_offline && !view_store.flags.logging_in;
+    let show_stream_state = view_store.logged_in && !is_offline;
     let error = view_store.error.clone();
 
     cx.render(rsx!(
@@ -85,6 +133,25 @@ pub fn LoggedInApp<'a>(
                             }
                         }
                 }))},
+            {
+                is_offline.then(|| rsx!(div {
+                    class: "offline-banner hstack align-items-center gap-2 p-2",
+                    Label {
+                        class: "me-auto",
+                        loc!("Offline. Showing cached posts")
+                    }
+                    button {
+                        class: "button",
+                        onclick: move |_| view_store.send(Action::Login),
+                        loc!("Retry")
+                    }
+                }))
+            }
+            {
+                show_stream_state.then(|| rsx!(StreamStateComponent {
+                    stream_state: view_store.stream_state
+                }))
+            }
             {
                 is_dropping.then(|| rsx!(div {
                     class: "fullscreen file-drop-box"
@@ -94,6 +161,28 @@ pub fn LoggedInApp<'a>(
     ))
 }
 
+#[inline_props]
+fn StreamStateComponent(cx: Scope, stream_state: StreamState) -> Element {
+    let class = match stream_state {
+        StreamState::Connected => "connected",
+        StreamState::Connecting => "connecting",
+        StreamState::Disconnected | StreamState::Reconnecting(_) => "disconnected",
+    };
+    let title = stream_state.to_string();
+    let is_reconnecting = matches!(stream_state, StreamState::Reconnecting(_));
+    render! {
+        div {
+            class: "stream-state hstack align-items-center gap-2 {class}",
+            title: "{title}",
+            span { class: "stream-state-dot" }
+            is_reconnecting.then(|| rsx!(Label {
+                style: TextStyle::Tertiary,
+                "{title}"
+            }))
+        }
+    }
+}
+
 #[inline_props]
 fn ReplyComponent<'a>(
     cx: Scope<'a>,
@@ -134,9 +223,16 @@ impl ChildReducer<RootReducer> for crate::components::post::PostReducer {
     }
 
     fn from_child(
-        _message: <Self as Reducer>::DelegateMessage,
+        message: <Self as Reducer>::DelegateMessage,
     ) -> Option<<RootReducer as Reducer>::Action> {
-        Some(Action::PostCancel)
+        use crate::components::post::PostAction;
+        use crate::environment::model::PostedStatus;
+        match message {
+            PostAction::PostResult(Ok(PostedStatus::Status(status))) => {
+                Some(Action::PostDone(status))
+            }
+            _ => Some(Action::PostCancel),
+        }
     }
 }
 
@@ -183,9 +279,12 @@ impl ChildReducer<RootReducer> for SidebarReducer {
                 Some(SidebarAction::Reload(true))
             }
             Action::AppEvent(AppEvent::MenuEvent(
-                MainMenuEvent::ScrollUp | MainMenuEvent::ScrollDown,
+                MainMenuEvent::ScrollUp
+                    | MainMenuEvent::ScrollDown
+                    | MainMenuEvent::JumpToLastRead,
             )) => None,
             Action::AppEvent(m @ AppEvent::MenuEvent(_)) => Some(SidebarAction::AppEvent(m)),
+            Action::SelectMore(s) => Some(SidebarAction::ShowMore(s)),
             _ => None,
         }
     }
@@ -197,6 +296,12 @@ impl ChildReducer<RootReducer> for SidebarReducer {
         Some(match message {
             SidebarDelegateAction::SelectAccount(a) => Action::SelectAccount(a),
             SidebarDelegateAction::SelectedNotifications(a) => Action::SelectNotifications(a),
+            SidebarDelegateAction::SelectedNotificationGroup(a) => {
+                Action::SelectNotificationGroup(a)
+            }
+            SidebarDelegateAction::SelectedDirectConversation(a) => {
+                Action::SelectDirectConversation(a)
+            }
             SidebarDelegateAction::Root(a) => a,
             SidebarDelegateAction::AppEvent(a) => Action::AppEvent(a),
             SidebarDelegateAction::SelectMore(a) => Action::SelectMore(a),
@@ -217,6 +322,10 @@ fn ContentComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a>
                 store: store,
                 hidden: !tab.is_mentions()
             }
+            MessagesContentComponent {
+                store: store,
+                hidden: !tab.is_messages()
+            }
             MoreComponent {
                 store: store,
                 hidden: !tab.is_more()
@@ -278,12 +387,33 @@ fn NotificationContentComponent<'a>(
     store: &'a ViewStore<'a>,
     hidden: bool,
 ) -> Element<'a> {
+    use crate::components::component_stack::{Stack, State};
+    if let Some(group) = store.selected_notification_group.as_ref() {
+        let account = group.accounts.first().cloned().unwrap_or_default();
+        return render!(HideableView {
+            hidden: *hidden,
+
+            VStack { class: "notification-group-content",
+                div { class: "notification-group-header p-3",
+                    Label { style: TextStyle::Primary, "{group.message}" }
+                    Label { style: TextStyle::Secondary, "{group.date_human}" }
+                    Paragraph { style: TextStyle::Tertiary, "{group.content}" }
+                }
+                Stack {
+                    store: store.host_with(
+                        cx,
+                        &group.id,
+                        |id| State::new(RootTimelineKind::NotificationGroup(account.clone(), id))
+                    )
+                }
+            }
+        });
+    }
     let Some(account) = store.selected_notifications.as_ref() else {
         return render! {
             div {}
         }
     };
-    use crate::components::component_stack::{Stack, State};
 
     render!(HideableView {
         hidden: *hidden,
@@ -298,6 +428,32 @@ fn NotificationContentComponent<'a>(
     })
 }
 
+#[inline_props]
+fn MessagesContentComponent<'a>(
+    cx: Scope<'a>,
+    store: &'a ViewStore<'a>,
+    hidden: bool,
+) -> Element<'a> {
+    let Some(conversation) = store.selected_direct_conversation.as_ref() else {
+        return render! {
+            div {}
+        }
+    };
+    use crate::components::component_stack::{Stack, State};
+
+    render!(HideableView {
+        hidden: *hidden,
+
+        Stack {
+            store: store.host_with(
+                cx,
+                &conversation.id,
+                |id| State::new(RootTimelineKind::DirectMessages(conversation.account.clone(), id))
+            )
+        }
+    })
+}
+
 #[inline_props]
 fn MoreComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>, hidden: bool) -> Element<'a> {
     log::trace!("render MoreComponent {}", cx.scope_id().0);
@@ -359,6 +515,22 @@ impl From<(StatusAction, &StatusViewModel)> for Action {
             StatusAction::OpenImage(a) => Action::Public(PublicAction::OpenImage(a)),
             StatusAction::OpenVideo(a) => Action::Public(PublicAction::OpenVideo(a)),
             StatusAction::Copy(a) => Action::Public(PublicAction::Copy(a)),
+            StatusAction::VotePoll(choices) => {
+                Action::Public(PublicAction::VotePoll(status.clone(), choices))
+            }
+            StatusAction::Edit => Action::Post(PostKind::Edit(status.clone())),
+            StatusAction::Delete => {
+                Action::Public(PublicAction::DeleteStatus(status.clone(), false))
+            }
+            StatusAction::Redraft => {
+                Action::Public(PublicAction::DeleteStatus(status.clone(), true))
+            }
+            StatusAction::Report => Action::Public(PublicAction::Report(
+                status.account.clone(),
+                Some(status.clone()),
+            )),
+            StatusAction::Quote => Action::Post(PostKind::Quote(status.clone())),
+            StatusAction::OpenQuote(quote) => Action::SelectConversation(quote.id),
         }
     }
 }
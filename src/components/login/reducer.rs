@@ -2,9 +2,11 @@ use std::cell::RefCell;
 
 use crate::environment::{
     model::{Account, AppData, Model, TokenData},
+    oauth::RedirectListener,
     types::{Instance, User},
     Environment,
 };
+use crate::loc;
 use navicula::effect::Effect;
 
 pub type ViewStore<'a> = navicula::ViewStore<'a, LoginReducer>;
@@ -28,7 +30,9 @@ pub enum LoginAction {
     LoadedInstances(Vec<Instance>),
     SelectInstance(Selection),
     ChosenInstance,
-    RetrieveUrl(Model, Result<AppData, String>),
+    RetrieveUrl(Model, Result<AppData, String>, Option<RedirectListener>),
+    /// The code from the redirect listener for the redirect uri
+    ReceivedCode(String, Result<String, String>),
     EnteredCode(String),
     ValidatedCode(Result<TokenData, String>),
     RetrievedUser(Result<Account, String>),
@@ -47,7 +51,11 @@ pub struct LoginState {
     pub selected_instance: Option<Instance>,
     pub selected_instance_url: Option<String>,
     pub app_data: Option<AppData>,
+    /// The url that is opened in the browser to authorize Ebou
+    pub authorize_url: Option<String>,
     pub code: Option<String>,
+    /// The code will arrive via the redirect listener, no need to paste it
+    pub is_waiting_for_redirect: bool,
     pub access_token: Option<TokenData>,
     pub model: Option<ModelContainer>,
     pub account: Option<Account>,
@@ -67,10 +75,14 @@ pub fn reduce<'a>(
     log::trace!("{action:?}");
     let instances = environment.instances.clone();
     match action {
-        LoginAction::Load => Effect::future(
-            async move { instances.search(None).await },
-            LoginAction::LoadedInstances,
-        ),
+        LoginAction::Load => {
+            // e.g. a wrong passphrase for the saved logins
+            state.error_message = environment.repository.credentials_error();
+            Effect::future(
+                async move { instances.search(None).await },
+                LoginAction::LoadedInstances,
+            )
+        }
         LoginAction::LoadedInstances(instances) => {
             state.instances = instances;
             state.is_loading = false;
@@ -104,25 +116,64 @@ pub fn reduce<'a>(
             else {
                 return Effect::NONE
             };
-            let model = Model::new(url, None);
-            let cloned = model.clone();
             state.is_loading = true;
-            return Effect::future(async move { model.register().await }, move |result| {
-                LoginAction::RetrieveUrl(cloned, result)
-            });
+            state.is_waiting_for_redirect = false;
+            return Effect::future(
+                async move {
+                    let backend = Model::detect_backend(&url).await;
+                    let model = Model::new(url, None, backend);
+                    if !backend.is_supported() {
+                        let error = format!("{backend} {}", loc!("is not supported yet"));
+                        return (model, Err(error), None);
+                    }
+                    // If we can't listen for the redirect, the user has to paste the code
+                    let listener = RedirectListener::bind()
+                        .await
+                        .map_err(|e| log::error!("{e}"))
+                        .ok();
+                    let redirect_uri = listener.as_ref().map(|e| e.redirect_uri());
+                    let result = model.register(redirect_uri).await;
+                    (model, result, listener)
+                },
+                |(model, result, listener)| LoginAction::RetrieveUrl(model, result, listener),
+            );
         }
-        LoginAction::RetrieveUrl(model, result) => {
+        LoginAction::RetrieveUrl(model, result, listener) => {
+            state.is_loading = false;
             match result {
                 Ok(n) => {
                     state.model = Some(ModelContainer::new(n.id.clone(), model));
-                    if let Some(ref url) = n.url {
+                    // The redirect has to return our `state` to be accepted
+                    state.authorize_url = match (n.url.as_ref(), listener.as_ref()) {
+                        (Some(url), Some(listener)) => Some(listener.authorize_url(url)),
+                        (url, _) => url.cloned(),
+                    };
+                    if let Some(ref url) = state.authorize_url {
                         environment.open_url(url)
                     }
                     state.app_data = Some(n);
+                    if let Some(listener) = listener {
+                        state.is_waiting_for_redirect = true;
+                        let redirect_uri = listener.redirect_uri();
+                        return Effect::future(async move { listener.code().await }, move |code| {
+                            LoginAction::ReceivedCode(redirect_uri, code)
+                        });
+                    }
                 }
                 Err(e) => state.error_message = Some(format!("Mastodon Error: {e:?}")),
             }
-            state.is_loading = false;
+            Effect::NONE
+        }
+        LoginAction::ReceivedCode(redirect_uri, result) => {
+            // Ignore listeners from an earlier attempt
+            if state.app_data.as_ref().map(|e| &e.redirect_uri) != Some(&redirect_uri) {
+                return Effect::NONE;
+            }
+            state.is_waiting_for_redirect = false;
+            match result {
+                Ok(code) => return Effect::action(LoginAction::EnteredCode(code)),
+                Err(e) => state.error_message = Some(e),
+            }
             Effect::NONE
         }
         LoginAction::EnteredCode(code) => {
@@ -134,8 +185,13 @@ pub fn reduce<'a>(
             };
             state.is_loading = true;
             let (client_id, client_secret) = (data.client_id.clone(), data.client_secret.clone());
+            let redirect_uri = data.redirect_uri.clone();
             Effect::future(
-                async move { model.authenticate(client_id, client_secret, code).await },
+                async move {
+                    model
+                        .authenticate(client_id, client_secret, code, redirect_uri)
+                        .await
+                },
                 LoginAction::ValidatedCode,
             )
         }
@@ -147,7 +203,8 @@ pub fn reduce<'a>(
                         return Effect::NONE
                     };
                     // create a new model with the new access token
-                    let new_model = Model::new(model.url, Some(n.access_token.clone()));
+                    let new_model =
+                        Model::new(model.url, Some(n.access_token.clone()), model.backend);
                     state.model = Some(ModelContainer::new(n.access_token.clone() , new_model.clone()));
                     return Effect::future(
                         async move { new_model.login().await },
@@ -184,7 +241,7 @@ pub fn reduce<'a>(
                 return Effect::NONE 
             };
 
-            let user = User::new(model.url(), account, token, appdata);
+            let user = User::new(model.url(), account, token, appdata, model.model.backend);
 
             if let Err(e) = environment.repository.update_or_insert_user(user) {
                 state.error_message = Some(format!("{e:?}"));
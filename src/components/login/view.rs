@@ -17,36 +17,66 @@ pub fn LoginApp<'a>(
 
     let did_close = use_state(cx, || false);
 
-    // We're done, return the model
-    if view_store.done && !environment.get().model.has_token {
+    // We're done, add the account and make it the active one
+    if view_store.done {
         if let Some(model) = view_store.send_model.take().take().map(|o| o.cloned()) {
             let mut mutable_environment = environment.get().clone();
-            mutable_environment.update_model(model);
+            if let Some(account) = view_store.account.as_ref() {
+                mutable_environment.add_account(&account.id, model);
+                mutable_environment.switch_account(&account.id);
+            } else {
+                mutable_environment.update_model(model);
+            }
             environment.set(mutable_environment);
         }
     }
 
+    // If there're other accounts, the login can be cancelled
+    let can_cancel = !view_store.done && !environment.get().accounts.is_empty();
+
     if !(*did_close.get()) && view_store.close && view_store.done {
         did_close.set(true);
         should_show_login.set(false);
     }
 
     cx.render(rsx!(
-        div { class: "login-container", MainView { view_store: view_store } }
+        div { class: "login-container",
+            MainView {
+                view_store: view_store,
+                can_cancel: can_cancel,
+                oncancel: move |_| should_show_login.set(false)
+            }
+        }
     ))
 }
 
 #[inline_props]
-fn MainView<'a>(cx: Scope<'a>, view_store: ViewStore<'a>) -> Element<'a> {
+fn MainView<'a>(
+    cx: Scope<'a>,
+    view_store: ViewStore<'a>,
+    can_cancel: bool,
+    oncancel: EventHandler<'a, ()>,
+) -> Element<'a> {
     cx.render(rsx!(
         div { class: "vstack p-2 m-2 grow align-items-center justify-content-center justify-items-center",
-            div { class: "justify-self-center", Welcome { view_store: view_store } }
+            div { class: "justify-self-center",
+                Welcome {
+                    view_store: view_store,
+                    can_cancel: *can_cancel,
+                    oncancel: move |_| oncancel.call(())
+                }
+            }
         }
     ))
 }
 
 #[inline_props]
-fn Welcome<'a>(cx: Scope<'a>, view_store: &'a ViewStore<'a>) -> Element<'a> {
+fn Welcome<'a>(
+    cx: Scope<'a>,
+    view_store: &'a ViewStore<'a>,
+    can_cancel: bool,
+    oncancel: EventHandler<'a, ()>,
+) -> Element<'a> {
     use LoginAction::*;
     use PageVisibility::*;
 
@@ -71,6 +101,9 @@ fn Welcome<'a>(cx: Scope<'a>, view_store: &'a ViewStore<'a>) -> Element<'a> {
         _ => (true, false, true, "", loc!("Done")),
     };
 
+    // There's nothing to confirm while we wait for the redirect
+    let visible_r = visible_r && !(b == Visible && view_store.is_waiting_for_redirect);
+
     if view_store.selected_instance_url.is_some() && matches!(action, LoginAction::ChosenInstance) {
         t2 = loc!("Use Custom");
     }
@@ -83,6 +116,14 @@ fn Welcome<'a>(cx: Scope<'a>, view_store: &'a ViewStore<'a>) -> Element<'a> {
                 HStack { class: "gap-3 align-items-center",
                     h3 { loc!("Welcome to Ebou") }
                     span { "𝛼" }
+                    can_cancel.then(|| rsx!(
+                        div { class: "grow" }
+                        button {
+                            class: "button",
+                            onclick: move |_| oncancel.call(()),
+                            loc!("Cancel")
+                        }
+                    ))
                 }
                 div { class: "page-container",
                     Page1 { visibility: a, view_store: view_store }
@@ -269,13 +310,18 @@ fn Page2<'a>(
 ) -> Element<'a> {
     use copypasta::ClipboardProvider;
     let class = visibility.class();
+    let instructions = if view_store.is_waiting_for_redirect {
+        loc!("Please authorize Ebou there. Ebou will continue automatically.")
+    } else {
+        loc!("Please authorize Ebou and then copy & paste the code into the box below.")
+    };
     cx.render(rsx!(
         VStack { class: "page2 page gap-3 {class}",
             div { class: "p-2",
                 Paragraph { loc!("A website should just have opened in your browser.") }
-                Paragraph { style: TextStyle::Secondary, loc!("Please authorize Ebou and then copy & paste the code into the box below.") }
+                Paragraph { style: TextStyle::Secondary, "{instructions}" }
             }
-            HStack { class: "gap-2 align-items-center",
+            (!view_store.is_waiting_for_redirect).then(|| rsx!(HStack { class: "gap-2 align-items-center",
                 input {
                     r#type: "text",
                     class: "grow",
@@ -299,12 +345,12 @@ fn Page2<'a>(
                     },
                     loc!("Paste")
                 }
-            }
+            }))
             small { class: "p-2 label-tertiary",
                 a {
                     style: "text-decoration: underline; cursor: pointer;",
                     onclick: move |_| {
-                        if let Some(url) = view_store.app_data.as_ref().and_then(|e| e.url.clone()) {
+                        if let Some(url) = view_store.authorize_url.clone() {
                             if let Ok(mut ctx) = copypasta::ClipboardContext::new() {
                                 let _ = ctx.set_contents(url);
                             }
@@ -1,11 +1,18 @@
 pub mod component_stack;
 pub mod conversation;
+pub mod filters;
+pub mod hashtags;
+pub mod lists;
 pub mod loggedin;
 pub mod login;
+pub mod moderation;
 pub mod more;
 pub mod post;
 pub mod profile;
 pub mod profile_preview;
 pub mod profiles;
+pub mod report;
+pub mod scheduled;
+pub mod search;
 pub mod sidebar;
 pub mod status_timeline;
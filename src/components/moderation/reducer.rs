@@ -0,0 +1,112 @@
+use crate::environment::model::{Account, Relationship};
+use crate::environment::Environment;
+use crate::view_model::AccountViewModel;
+use navicula::Effect;
+
+pub type ViewStore<'a> = navicula::ViewStore<'a, super::ModerationReducer>;
+
+#[derive(Clone, Debug)]
+pub enum ModerationAction {
+    Load,
+    Loaded(Result<(Vec<Account>, Vec<Account>, Vec<String>), String>),
+    Unmute(String),
+    Unmuted(String, Result<Relationship, String>),
+    Unblock(String),
+    Unblocked(String, Result<Relationship, String>),
+    UnblockDomain(String),
+    UnblockedDomain(String, Result<(), String>),
+    ClearError,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct ModerationState {
+    pub mutes: Vec<AccountViewModel>,
+    pub blocks: Vec<AccountViewModel>,
+    pub domain_blocks: Vec<String>,
+    pub is_loading: bool,
+    pub error: Option<String>,
+}
+
+pub fn reduce<'a>(
+    _context: &'a impl navicula::types::MessageContext<ModerationAction, (), ()>,
+    action: ModerationAction,
+    state: &'a mut ModerationState,
+    environment: &'a Environment,
+) -> Effect<'static, ModerationAction> {
+    log::trace!("{action:?}");
+    let model = environment.model.clone();
+    match action {
+        ModerationAction::Load => {
+            state.is_loading = true;
+            return Effect::future(
+                async move {
+                    futures_util::future::try_join3(
+                        model.mutes(),
+                        model.blocks(),
+                        model.domain_blocks(),
+                    )
+                    .await
+                },
+                ModerationAction::Loaded,
+            );
+        }
+        ModerationAction::Loaded(result) => {
+            state.is_loading = false;
+            match result {
+                Ok((mutes, blocks, domain_blocks)) => {
+                    state.mutes = mutes.iter().map(AccountViewModel::new).collect();
+                    state.blocks = blocks.iter().map(AccountViewModel::new).collect();
+                    state.domain_blocks = domain_blocks;
+                }
+                Err(e) => state.error = Some(e),
+            }
+        }
+        ModerationAction::Unmute(id) => {
+            state.is_loading = true;
+            let cloned_id = id.clone();
+            return Effect::future(
+                async move { model.unmute(cloned_id).await },
+                move |result| ModerationAction::Unmuted(id, result),
+            );
+        }
+        ModerationAction::Unmuted(id, result) => {
+            state.is_loading = false;
+            match result {
+                Ok(_) => state.mutes.retain(|e| e.id.0 != id),
+                Err(e) => state.error = Some(e),
+            }
+        }
+        ModerationAction::Unblock(id) => {
+            state.is_loading = true;
+            let cloned_id = id.clone();
+            return Effect::future(
+                async move { model.unblock(cloned_id).await },
+                move |result| ModerationAction::Unblocked(id, result),
+            );
+        }
+        ModerationAction::Unblocked(id, result) => {
+            state.is_loading = false;
+            match result {
+                Ok(_) => state.blocks.retain(|e| e.id.0 != id),
+                Err(e) => state.error = Some(e),
+            }
+        }
+        ModerationAction::UnblockDomain(domain) => {
+            state.is_loading = true;
+            let cloned_domain = domain.clone();
+            return Effect::future(
+                async move { model.block_domain(cloned_domain, false).await },
+                move |result| ModerationAction::UnblockedDomain(domain, result),
+            );
+        }
+        ModerationAction::UnblockedDomain(domain, result) => {
+            state.is_loading = false;
+            match result {
+                Ok(_) => state.domain_blocks.retain(|e| e != &domain),
+                Err(e) => state.error = Some(e),
+            }
+        }
+        ModerationAction::ClearError => state.error = None,
+    }
+    Effect::NONE
+}
@@ -0,0 +1,128 @@
+use dioxus::prelude::*;
+
+use super::reducer::{ModerationAction, ViewStore};
+use crate::loc;
+use crate::view_model::AccountViewModel;
+use crate::widgets::*;
+
+#[inline_props]
+pub fn ModerationComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
+    let is_empty = store.mutes.is_empty()
+        && store.blocks.is_empty()
+        && store.domain_blocks.is_empty()
+        && !store.is_loading;
+    render! {
+        div {
+            class: "moderation-list-component",
+            VStack {
+                div {
+                    class: "scroll content-cell-container",
+                    { store.error.as_ref().map(|error| rsx!(ErrorBox {
+                        content: error.clone(),
+                        onclick: move |_| store.send(ModerationAction::ClearError)
+                    }))}
+
+                    { store.is_loading.then(|| rsx!(div {
+                        class: "hstack p-2 m-2 grow align-self-center",
+                        Spinner {}
+                    }))}
+
+                    is_empty.then(|| rsx!(div {
+                        class: "p-3",
+                        Label {
+                            style: TextStyle::Secondary,
+                            loc!("No muted or blocked accounts")
+                        }
+                    }))
+
+                    (!store.mutes.is_empty()).then(|| rsx!(SectionHeader {
+                        title: loc!("Muted Accounts")
+                    }))
+                    for account in &store.mutes {
+                        ModeratedAccountView {
+                            key: "mute-{account.id.0}",
+                            store: store,
+                            account: account,
+                            title: loc!("Unmute"),
+                            onclick: move |_| store.send(ModerationAction::Unmute(account.id.0.clone())),
+                        }
+                    }
+
+                    (!store.blocks.is_empty()).then(|| rsx!(SectionHeader {
+                        title: loc!("Blocked Accounts")
+                    }))
+                    for account in &store.blocks {
+                        ModeratedAccountView {
+                            key: "block-{account.id.0}",
+                            store: store,
+                            account: account,
+                            title: loc!("Unblock"),
+                            onclick: move |_| store.send(ModerationAction::Unblock(account.id.0.clone())),
+                        }
+                    }
+
+                    (!store.domain_blocks.is_empty()).then(|| rsx!(SectionHeader {
+                        title: loc!("Blocked Domains")
+                    }))
+                    for domain in &store.domain_blocks {
+                        div {
+                            key: "{domain}",
+                            class: "moderation-entry hstack align-items-center p-2 gap-2",
+                            Label {
+                                class: "me-auto",
+                                "{domain}"
+                            }
+                            button {
+                                class: "button",
+                                disabled: "{store.is_loading}",
+                                onclick: move |_| store.send(ModerationAction::UnblockDomain(domain.clone())),
+                                loc!("Unblock")
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn SectionHeader<'a>(cx: Scope<'a>, title: &'a str) -> Element<'a> {
+    render! {
+        div { class: "p-2 pt-3",
+            Label {
+                style: TextStyle::Secondary,
+                "{title}"
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn ModeratedAccountView<'a>(
+    cx: Scope<'a>,
+    store: &'a ViewStore<'a>,
+    account: &'a AccountViewModel,
+    title: &'a str,
+    onclick: EventHandler<'a, ()>,
+) -> Element<'a> {
+    render! {
+        HStack { class: "moderation-entry align-items-center p-2 gap-2",
+            img {
+                class: "image-author-small",
+                src: "{account.image}",
+                alt: "{account.username}",
+            }
+            Label {
+                class: "me-auto",
+                "{account.acct}"
+            }
+            button {
+                class: "button",
+                disabled: "{store.is_loading}",
+                onclick: move |_| onclick.call(()),
+                "{title}"
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::components::component_stack::RootTimelineKind;
 use crate::components::profiles::ProfilesKind;
 use crate::components::sidebar::MoreSelection;
-use crate::environment::model::Account;
+use crate::environment::model::{Account, AccountList};
 use crate::environment::types::{AppEvent, UiConfig};
 use crate::environment::Environment;
 use crate::view_model::AccountViewModel;
@@ -20,6 +20,9 @@ pub struct Providers {
     pub public: Option<RootTimelineKind>,
     pub follows: Option<RootTimelineKind>,
     pub following: Option<RootTimelineKind>,
+    pub follow_requests: Option<RootTimelineKind>,
+    /// The members of the list that is shown on the lists page
+    pub list_members: Option<RootTimelineKind>,
 }
 
 #[derive(Clone, Debug)]
@@ -39,6 +42,8 @@ pub enum Action {
     Selection(MoreSelection),
     AppEvent(AppEvent),
     Conversation(crate::PublicAction),
+    ShowListMembers(AccountList),
+    CloseListMembers,
 }
 
 #[derive(Clone, Debug)]
@@ -80,6 +85,9 @@ pub fn reduce<'a>(
         Action::Selection(a) => {
             let re_selected = state.selection == a;
             state.selection = a;
+            if a == MoreSelection::Lists {
+                state.providers.list_members = None;
+            }
             handle_selection(
                 state.selection,
                 &mut state.providers,
@@ -89,6 +97,14 @@ pub fn reduce<'a>(
         }
         Action::AppEvent(a) => context.send_children(Message::AppEvent(a)),
         Action::Conversation(a) => context.send_parent(PublicAction::Timeline(a)),
+        Action::ShowListMembers(list) => {
+            let account = AccountViewModel::new(&state.user_account);
+            state.providers.list_members = Some(RootTimelineKind::Relationship(
+                account.clone(),
+                ProfilesKind::List(account.id, list),
+            ));
+        }
+        Action::CloseListMembers => state.providers.list_members = None,
     }
     Effect::NONE
 }
@@ -124,7 +140,19 @@ fn handle_selection(
                 ProfilesKind::Following(account.id),
             ));
         }
+        MoreSelection::FollowRequests => {
+            providers.follow_requests = Some(RootTimelineKind::Relationship(
+                account.clone(),
+                ProfilesKind::FollowRequests(account.id),
+            ));
+        }
+        // Scheduled posts, moderation, filters, lists and hashtags host their own views
+        MoreSelection::Scheduled => (),
+        MoreSelection::Moderation => (),
+        MoreSelection::Filters => (),
+        MoreSelection::Lists => (),
+        MoreSelection::Hashtags => (),
+        MoreSelection::Search => (),
         MoreSelection::Posts => todo!(),
-        MoreSelection::Hashtags => todo!(),
     }
 }
@@ -5,8 +5,16 @@ use super::{
 use crate::{
     components::{
         component_stack::{RootTimelineKind, StackReducer},
+        filters::{FiltersAction, FiltersComponent, FiltersReducer, FiltersState},
+        hashtags::{HashtagsAction, HashtagsComponent, HashtagsReducer, HashtagsState},
+        lists::{ListsAction, ListsComponent, ListsDelegate, ListsReducer, ListsState},
+        moderation::{ModerationAction, ModerationComponent, ModerationReducer, ModerationState},
+        scheduled::{ScheduledAction, ScheduledComponent, ScheduledReducer, ScheduledState},
+        search::{SearchAction, SearchComponent, SearchReducer, SearchState},
         sidebar::MoreSelection,
     },
+    loc,
+    view_model::AccountViewModel,
     widgets::*,
 };
 use dioxus::prelude::*;
@@ -71,7 +79,137 @@ pub fn MoreViewComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a>
             provider: store.providers.following.clone(),
             hidden: store.selection != MoreSelection::Following
         }
+
+        StatusesPageComponent {
+            title: "follow requests",
+            store: store,
+            provider: store.providers.follow_requests.clone(),
+            hidden: store.selection != MoreSelection::FollowRequests
+        }
+
+        ScheduledPageComponent {
+            store: store,
+            hidden: store.selection != MoreSelection::Scheduled
+        }
+
+        ModerationPageComponent {
+            store: store,
+            hidden: store.selection != MoreSelection::Moderation
+        }
+
+        FiltersPageComponent {
+            store: store,
+            hidden: store.selection != MoreSelection::Filters
+        }
+
+        ListsPageComponent {
+            store: store,
+            hidden: store.selection != MoreSelection::Lists
+        }
+
+        HashtagsPageComponent {
+            store: store,
+            hidden: store.selection != MoreSelection::Hashtags
+        }
+
+        SearchPageComponent {
+            store: store,
+            hidden: store.selection != MoreSelection::Search
+        }
+    }
+}
+
+#[inline_props]
+fn ScheduledPageComponent<'a>(
+    cx: Scope<'a>,
+    store: &'a ViewStore<'a>,
+    hidden: bool,
+) -> Element<'a> {
+    // Scheduled posts change outside of this view, so they're loaded
+    // freshly whenever the page is shown
+    if *hidden {
+        return render!(div {});
     }
+    render!(ScheduledComponent {
+        store: store.host(cx, ScheduledState::default)
+    })
+}
+
+#[inline_props]
+fn ModerationPageComponent<'a>(
+    cx: Scope<'a>,
+    store: &'a ViewStore<'a>,
+    hidden: bool,
+) -> Element<'a> {
+    // Mutes and blocks are mostly changed from profiles, so they're
+    // loaded freshly whenever the page is shown
+    if *hidden {
+        return render!(div {});
+    }
+    render!(ModerationComponent {
+        store: store.host(cx, ModerationState::default)
+    })
+}
+
+#[inline_props]
+fn FiltersPageComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>, hidden: bool) -> Element<'a> {
+    if *hidden {
+        return render!(div {});
+    }
+    render!(FiltersComponent {
+        store: store.host(cx, FiltersState::default)
+    })
+}
+
+#[inline_props]
+fn ListsPageComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>, hidden: bool) -> Element<'a> {
+    if *hidden {
+        return render!(div {});
+    }
+    // The members of a list replace the lists until they're closed
+    if let Some(provider) = store.providers.list_members.as_ref() {
+        use crate::components::component_stack::{Stack, State};
+        return render!(VStack {
+            class: "grow",
+            HStack { class: "align-items-center p-2 gap-2",
+                button {
+                    class: "button",
+                    onclick: move |_| store.send(Action::CloseListMembers),
+                    loc!("Back")
+                }
+            }
+            Stack {
+                store: store.host_with(cx, provider, State::new)
+            }
+        });
+    }
+    render!(ListsComponent {
+        store: store.host(cx, ListsState::default)
+    })
+}
+
+#[inline_props]
+fn HashtagsPageComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>, hidden: bool) -> Element<'a> {
+    if *hidden {
+        return render!(div {});
+    }
+    let account = AccountViewModel::new(&store.user_account);
+    render!(HashtagsComponent {
+        store: store.host_with(cx, &account, HashtagsState::new)
+    })
+}
+
+#[inline_props]
+fn SearchPageComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>, hidden: bool) -> Element<'a> {
+    // Stays alive while hidden, so that the results are kept and
+    // searches from the sidebar are performed
+    let account = AccountViewModel::new(&store.user_account);
+    render!(HideableView {
+        hidden: *hidden,
+        SearchComponent {
+            store: store.host_with(cx, &account, SearchState::new)
+        }
+    })
 }
 
 #[derive(Props)]
@@ -104,6 +242,132 @@ fn StatusesPageComponent<'a>(cx: Scope<'a, StatusesPageComponentProps<'a>>) -> E
     })
 }
 
+impl ChildReducer<MoreReducer> for ScheduledReducer {
+    fn to_child(
+        message: <MoreReducer as navicula::Reducer>::Message,
+    ) -> Option<<Self as navicula::Reducer>::Action> {
+        use crate::environment::types::{AppEvent, MainMenuEvent};
+        match message {
+            super::reducer::Message::Selection(MoreSelection::Scheduled, true) => {
+                Some(ScheduledAction::Load)
+            }
+            super::reducer::Message::AppEvent(AppEvent::MenuEvent(MainMenuEvent::Reload)) => {
+                Some(ScheduledAction::Load)
+            }
+            _ => None,
+        }
+    }
+
+    fn from_child(
+        _message: <Self as navicula::Reducer>::DelegateMessage,
+    ) -> Option<<MoreReducer as navicula::Reducer>::Action> {
+        None
+    }
+}
+
+impl ChildReducer<MoreReducer> for ModerationReducer {
+    fn to_child(
+        message: <MoreReducer as navicula::Reducer>::Message,
+    ) -> Option<<Self as navicula::Reducer>::Action> {
+        use crate::environment::types::{AppEvent, MainMenuEvent};
+        match message {
+            super::reducer::Message::Selection(MoreSelection::Moderation, true) => {
+                Some(ModerationAction::Load)
+            }
+            super::reducer::Message::AppEvent(AppEvent::MenuEvent(MainMenuEvent::Reload)) => {
+                Some(ModerationAction::Load)
+            }
+            _ => None,
+        }
+    }
+
+    fn from_child(
+        _message: <Self as navicula::Reducer>::DelegateMessage,
+    ) -> Option<<MoreReducer as navicula::Reducer>::Action> {
+        None
+    }
+}
+
+impl ChildReducer<MoreReducer> for FiltersReducer {
+    fn to_child(
+        message: <MoreReducer as navicula::Reducer>::Message,
+    ) -> Option<<Self as navicula::Reducer>::Action> {
+        use crate::environment::types::{AppEvent, MainMenuEvent};
+        match message {
+            super::reducer::Message::Selection(MoreSelection::Filters, true) => {
+                Some(FiltersAction::Load)
+            }
+            super::reducer::Message::AppEvent(AppEvent::MenuEvent(MainMenuEvent::Reload)) => {
+                Some(FiltersAction::Load)
+            }
+            _ => None,
+        }
+    }
+
+    fn from_child(
+        _message: <Self as navicula::Reducer>::DelegateMessage,
+    ) -> Option<<MoreReducer as navicula::Reducer>::Action> {
+        None
+    }
+}
+
+impl ChildReducer<MoreReducer> for ListsReducer {
+    fn to_child(
+        message: <MoreReducer as navicula::Reducer>::Message,
+    ) -> Option<<Self as navicula::Reducer>::Action> {
+        use crate::environment::types::{AppEvent, MainMenuEvent};
+        match message {
+            super::reducer::Message::Selection(MoreSelection::Lists, _) => Some(ListsAction::Load),
+            super::reducer::Message::AppEvent(AppEvent::MenuEvent(MainMenuEvent::Reload)) => {
+                Some(ListsAction::Load)
+            }
+            _ => None,
+        }
+    }
+
+    fn from_child(
+        message: <Self as navicula::Reducer>::DelegateMessage,
+    ) -> Option<<MoreReducer as navicula::Reducer>::Action> {
+        match message {
+            ListsDelegate::ShowMembers(list) => Some(Action::ShowListMembers(list)),
+        }
+    }
+}
+
+impl ChildReducer<MoreReducer> for HashtagsReducer {
+    fn to_child(
+        message: <MoreReducer as navicula::Reducer>::Message,
+    ) -> Option<<Self as navicula::Reducer>::Action> {
+        match message {
+            super::reducer::Message::AppEvent(a) => Some(HashtagsAction::AppEvent(a)),
+            super::reducer::Message::Selection(_, _) => None,
+        }
+    }
+
+    fn from_child(
+        message: <Self as navicula::Reducer>::DelegateMessage,
+    ) -> Option<<MoreReducer as navicula::Reducer>::Action> {
+        Some(Action::Conversation(message))
+    }
+}
+
+impl ChildReducer<MoreReducer> for SearchReducer {
+    fn to_child(
+        message: <MoreReducer as navicula::Reducer>::Message,
+    ) -> Option<<Self as navicula::Reducer>::Action> {
+        match message {
+            super::reducer::Message::AppEvent(a) => Some(SearchAction::AppEvent(a)),
+            super::reducer::Message::Selection(_, _) => None,
+        }
+    }
+
+    fn from_child(
+        message: <Self as navicula::Reducer>::DelegateMessage,
+    ) -> Option<<MoreReducer as navicula::Reducer>::Action> {
+        Some(Action::Conversation(message))
+    }
+}
+
 impl ChildReducer<MoreReducer> for StackReducer {
     fn to_child(
         message: <MoreReducer as navicula::Reducer>::Message,
@@ -2,7 +2,7 @@
 
 use std::path::PathBuf;
 
-use crate::environment::model::{Status, UploadMedia};
+use crate::environment::model::{PostedStatus, StatusSource, UploadMedia};
 use crate::environment::types::AppEvent;
 use crate::view_model::AttachmentMedia;
 
@@ -10,21 +10,48 @@ use crate::view_model::AttachmentMedia;
 #[derive(Clone, Debug)]
 pub enum PostAction {
     Open(Vec<PathBuf>),
+    /// The text of an edited status as it was written
+    LoadedSource(Result<StatusSource, String>),
     Close,
     FileDialog,
-    FileDialogDone(Option<AttachmentMedia>),
-    UploadMediaDone((AttachmentMedia, Result<UploadMedia, String>)),
+    FileDialogDone(Result<AttachmentMedia, String>),
     RemoveImage(usize),
     ShowImageDisk(usize),
     UpdateImageDescription(usize, String),
     UpdateImageDescriptionResult(Result<(), String>),
     UpdateVisibility(String),
     UpdateText(String),
+    TogglePoll,
+    AddPollOption,
+    RemovePollOption(usize),
+    UpdatePollOption(usize, String),
+    UpdatePollExpiry(String),
+    UpdatePollMultiple(bool),
+    ToggleContentWarning,
+    UpdateContentWarning(String),
+    UpdateSensitive(bool),
+    ToggleSchedule,
+    SaveDraft,
+    ResumeDraft(String),
+    DeleteDraft(String),
+    UpdateScheduledAt(String),
+    ToggleThread,
+    UpdateThreadNumbered(bool),
+    /// Post the attachment at the index with the given part of the thread
+    UpdateMediaPart(usize, usize),
     Post,
-    PostResult(Result<Status, String>),
+    PostResult(Result<PostedStatus, String>),
+    PostThreadPart,
+    PostedThreadPart(Result<PostedStatus, String>),
     ClearError,
     AppEvent(AppEvent),
     DroppedPaths(Vec<PathBuf>),
-    DroppedMedia(Vec<AttachmentMedia>),
-    DroppedMediaUploaded(Vec<(Result<UploadMedia, String>, AttachmentMedia)>),
+    DroppedMedia(Vec<Result<AttachmentMedia, String>>),
+    MediaUploaded(AttachmentMedia, Result<UploadMedia, String>),
+    /// Upload the attachment at the index again
+    RetryUpload(usize),
+    /// Check if the server finished processing the attachments
+    PollMedia,
+    /// By the server id of the attachment
+    PolledMedia(Vec<(String, Result<bool, String>)>),
 }
@@ -3,8 +3,9 @@ mod reducer;
 mod state;
 mod view;
 
+pub use crate::environment::types::{PostKind, Visibility};
 pub use action::PostAction;
-pub use state::{PostKind, State, Visibility};
+pub use state::{PollLimits, PollState, State, ThreadState};
 
 pub use reducer::{reduce, ViewStore};
 
@@ -1,15 +1,20 @@
 #![allow(non_snake_case)]
 
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use crate::behaviours::{Behaviour, ChangeTextsizeBehaviour};
-use crate::environment::model::{Instance, UploadMedia};
-use crate::environment::types::{AppEvent, FileEvent};
+use crate::environment::model::{Instance, PostedStatus, UploadMedia};
+use crate::environment::types::{AppEvent, Draft, FileEvent, MediaLimits};
 use crate::environment::{Environment, UploadMediaExt};
-use crate::view_model::AttachmentMedia;
+use crate::view_model::{AttachmentMedia, UploadState};
+use chrono::{Duration, Utc};
 use navicula::Effect;
 
-use super::{PostAction, PostKind, State, Visibility};
+use super::{PollLimits, PollState, PostAction, PostKind, State, ThreadState, Visibility};
+
+/// The attachments are checked every two seconds, for up to five minutes
+const MAX_PROCESSING_CHECKS: u32 = 150;
 
 pub type ViewStore<'a> = navicula::ViewStore<'a, super::PostReducer>;
 
@@ -22,13 +27,28 @@ pub fn reduce<'a>(
     log::trace!("{action:?}");
     let window = context.window();
 
+    if matches!(
+        action,
+        PostAction::UpdateText(_)
+            | PostAction::UpdateVisibility(_)
+            | PostAction::UpdateContentWarning(_)
+            | PostAction::ToggleContentWarning
+            | PostAction::UpdateSensitive(_)
+            | PostAction::UpdateImageDescription(_, _)
+            | PostAction::RemoveImage(_)
+            | PostAction::MediaUploaded(..)
+            | PostAction::ToggleThread
+    ) {
+        state.draft_dirty = true;
+    }
+
     match action {
         PostAction::Open(images) => {
             environment.platform.update_menu(window, |config| {
                 config.enable_postwindow = true;
             });
             state.text = match state.kind {
-                PostKind::Post => "".to_string(),
+                PostKind::Post | PostKind::Quote(_) => "".to_string(),
                 PostKind::Reply(ref s) | PostKind::ReplyPrivate(ref s) => {
                     // have to ignore our own username for a mention
                     let compare = format!("@{}", state.account.username);
@@ -44,12 +64,23 @@ pub fn reduce<'a>(
                     }
                     format!("@{} {others}", s.account.acct)
                 }
+                PostKind::Edit(ref s) | PostKind::Redraft(ref s) => {
+                    if !s.spoiler_text.is_empty() {
+                        state.spoiler_text = Some(s.spoiler_text.clone());
+                    }
+                    state.sensitive = s.sensitive;
+                    state.visibility = s.visibility;
+                    state.poll = s.poll.as_ref().map(PollState::from_poll);
+                    s.text.clone()
+                }
             };
             if matches!(state.kind, PostKind::ReplyPrivate(_)) {
                 state.visibility = Some(Visibility::Direct);
             }
             let instance = environment.model.instance();
+            state.poll_limits = PollLimits::new(instance.as_ref());
             state.validity = validate_text(instance, &state.text);
+            update_thread(state);
 
             let imgs = if images.is_empty() {
                 &state.image_paths
@@ -57,25 +88,72 @@ pub fn reduce<'a>(
                 &images
             };
 
+            let autosave = Effect::timer(
+                std::time::Duration::from_secs(5),
+                PostAction::SaveDraft,
+                "post-draft-autosave",
+            );
+
+            // Restore the draft if the composer was still open when the app quit
+            state.drafts = environment.repository.drafts(&state.account.id);
+            let recovered = state
+                .drafts
+                .iter()
+                .find(|e| e.is_open && e.kind == state.kind)
+                .map(|e| e.id.clone());
+            if let Some(id) = recovered {
+                return Effect::merge2(Effect::action(PostAction::ResumeDraft(id)), autosave);
+            }
+
+            // The text of the status is cleaned up HTML. Redrafted statuses
+            // are gone already, they have the source text
+            let source = match state.kind {
+                PostKind::Edit(ref s) => {
+                    let model = environment.model.clone();
+                    let id = s.id.0.clone();
+                    Effect::future(
+                        async move { model.status_source(id).await },
+                        PostAction::LoadedSource,
+                    )
+                }
+                _ => Effect::NONE,
+            };
+
             if imgs.is_empty() {
-                Effect::NONE
+                Effect::merge2(source, autosave)
             } else {
-                Effect::action(PostAction::DroppedPaths(imgs.clone()))
+                Effect::merge3(
+                    Effect::action(PostAction::DroppedPaths(imgs.clone())),
+                    source,
+                    autosave,
+                )
             }
         }
-        PostAction::DroppedPaths(images) => Effect::future(
-            async move {
-                let mut m = Vec::new();
-                for i in images {
-                    if let Some(p) = crate::environment::platform::read_file_to_attachment(&i) {
-                        m.push(p)
-                    }
+        PostAction::LoadedSource(result) => {
+            let source = match result {
+                Ok(source) => source,
+                Err(e) => {
+                    log::error!("Could not load the source of the status: {e}");
+                    return Effect::NONE;
+                }
+            };
+            // Don't replace what was typed in the meantime
+            if let PostKind::Edit(ref s) = state.kind {
+                if state.text != s.text {
+                    return Effect::NONE;
                 }
-                m
-            },
-            PostAction::DroppedMedia,
-        ),
+            }
+            state.spoiler_text = (!source.spoiler_text.is_empty()).then_some(source.spoiler_text);
+            state.text = source.text;
+            state.validity = validate_text(environment.model.instance(), &state.text);
+            update_thread(state);
+            Effect::NONE
+        }
+        PostAction::DroppedPaths(images) => {
+            read_attachments(images, environment.model.media_limits())
+        }
         PostAction::Close => {
+            close_draft(state, environment);
             environment.platform.update_menu(window, |config| {
                 config.enable_postwindow = false;
             });
@@ -86,33 +164,88 @@ pub fn reduce<'a>(
             Effect::NONE
         }
         PostAction::FileDialog => {
-            let f = crate::environment::platform::open_file_dialog("~");
-            Effect::action(PostAction::FileDialogDone(f))
+            let Some(path) = crate::environment::platform::open_file_dialog("~") else {
+                return Effect::NONE
+            };
+            let limits = environment.model.media_limits();
+            Effect::future(
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        crate::environment::platform::read_file_to_attachment(&path, &limits)
+                    })
+                    .await
+                    .unwrap_or_else(|e| Err(format!("Could not read the file: {e}")))
+                },
+                PostAction::FileDialogDone,
+            )
         }
         PostAction::FileDialogDone(result) => {
-            if let Some(image) = result {
-                state.images.push(image.clone());
-                let model = environment.model.clone();
-
-                let cloned_image = image.clone();
-                let cloned_image2 = image;
-                Effect::future(
-                    async move {
-                        model
-                            .upload_media(&cloned_image.path, cloned_image.description)
-                            .await
-                    },
-                    move |result| PostAction::UploadMediaDone((cloned_image2, result)),
-                )
-            } else {
-                Effect::NONE
+            if state.poll.is_some() {
+                state.error_message = Some("Polls can't be combined with attachments".to_string());
+                return Effect::NONE;
             }
+            let image = match result {
+                Ok(image) => image,
+                Err(e) => {
+                    state.error_message = Some(e);
+                    return Effect::NONE;
+                }
+            };
+            state.images.push(image);
+            upload_media(state, vec![state.images.len() - 1], environment)
         }
-        PostAction::UploadMediaDone((image, result)) => {
-            if let Some(e) = handle_image_upload(image, result, &mut state.images) {
-                state.error_message = Some(e);
+        PostAction::RetryUpload(index) => {
+            let Some(image) = state.images.get_mut(index) else {
+                return Effect::NONE
+            };
+            image.server_id = None;
+            image.is_uploaded = false;
+            upload_media(state, vec![index], environment)
+        }
+        PostAction::PollMedia => {
+            state.is_polling_media = false;
+            let processing: Vec<_> = state
+                .images
+                .iter()
+                .filter(|e| e.upload_state == UploadState::Processing)
+                .filter_map(|e| e.server_id.clone())
+                .collect();
+            if processing.is_empty() {
+                return Effect::NONE;
             }
-            Effect::NONE
+            let model = environment.model.clone();
+            Effect::future(
+                async move {
+                    let mut results = Vec::new();
+                    for id in processing {
+                        let result = model.is_media_processed(id.clone()).await;
+                        results.push((id, result));
+                    }
+                    results
+                },
+                PostAction::PolledMedia,
+            )
+        }
+        PostAction::PolledMedia(results) => {
+            for (id, result) in results {
+                // It may have been removed or uploaded again in the meantime
+                let Some(image) = state.images.iter_mut().find(|e| {
+                    e.server_id.as_ref() == Some(&id) && e.upload_state == UploadState::Processing
+                }) else {
+                    continue
+                };
+                image.processing_checks += 1;
+                match result {
+                    Ok(true) => image.upload_state = UploadState::Ready,
+                    Ok(false) if image.processing_checks >= MAX_PROCESSING_CHECKS => {
+                        let error = crate::loc!("The server took too long to process it");
+                        image.upload_state = UploadState::Failed(error.to_string());
+                    }
+                    Ok(false) => (),
+                    Err(e) => image.upload_state = UploadState::Failed(e),
+                }
+            }
+            poll_media(state)
         }
         PostAction::RemoveImage(index) => {
             state.images.remove(index);
@@ -152,39 +285,385 @@ pub fn reduce<'a>(
             Effect::NONE
         }
         PostAction::UpdateText(text) => {
+            // The parts of a thread that is being posted can't change
+            if state.is_posting_thread() {
+                return Effect::NONE;
+            }
             let instance = environment.model.instance();
             state.validity = validate_text(instance, &text);
             state.text = text;
+            update_thread(state);
+            Effect::NONE
+        }
+        PostAction::ToggleThread => {
+            if state.is_posting_thread() {
+                return Effect::NONE;
+            }
+            state.thread = match state.thread {
+                Some(_) => None,
+                None => Some(ThreadState::default()),
+            };
+            update_thread(state);
+            Effect::NONE
+        }
+        PostAction::UpdateThreadNumbered(numbered) => {
+            if let Some(ref mut thread) = state.thread {
+                if !thread.is_started() {
+                    thread.numbered = numbered;
+                }
+            }
+            update_thread(state);
+            Effect::NONE
+        }
+        PostAction::UpdateMediaPart(index, part) => {
+            let Some(image) = state.images.get(index) else {
+                return Effect::NONE
+            };
+            if let Some(ref mut thread) = state.thread {
+                thread.media_parts.insert(image.path.clone(), part);
+            }
+            Effect::NONE
+        }
+        PostAction::TogglePoll => {
+            if state.poll.is_some() {
+                state.poll = None;
+            } else if !state.images.is_empty() {
+                state.error_message = Some("Polls can't be combined with attachments".to_string());
+            } else {
+                state.poll = Some(PollState::default());
+            }
+            Effect::NONE
+        }
+        PostAction::AddPollOption => {
+            let max = state.poll_limits.max_options;
+            if let Some(ref mut poll) = state.poll {
+                if poll.options.len() < max {
+                    poll.options.push(String::new());
+                }
+            }
+            Effect::NONE
+        }
+        PostAction::RemovePollOption(index) => {
+            if let Some(ref mut poll) = state.poll {
+                if poll.options.len() > 2 && index < poll.options.len() {
+                    poll.options.remove(index);
+                }
+            }
+            Effect::NONE
+        }
+        PostAction::UpdatePollOption(index, text) => {
+            if let Some(option) = state.poll.as_mut().and_then(|e| e.options.get_mut(index)) {
+                *option = text;
+            }
+            Effect::NONE
+        }
+        PostAction::UpdatePollExpiry(value) => {
+            let Ok(expires_in) = value.parse::<u64>() else {
+                state.error_message = Some(format!("Invalid Poll Duration: {value:?}"));
+                return Effect::NONE
+            };
+            if let Some(ref mut poll) = state.poll {
+                poll.expires_in = expires_in;
+            }
+            Effect::NONE
+        }
+        PostAction::UpdatePollMultiple(multiple) => {
+            if let Some(ref mut poll) = state.poll {
+                poll.multiple = multiple;
+            }
+            Effect::NONE
+        }
+        PostAction::ToggleContentWarning => {
+            state.spoiler_text = match state.spoiler_text {
+                Some(_) => None,
+                None => Some(String::new()),
+            };
+            update_thread(state);
+            Effect::NONE
+        }
+        PostAction::UpdateContentWarning(text) => {
+            state.spoiler_text = Some(text);
+            update_thread(state);
+            Effect::NONE
+        }
+        PostAction::UpdateSensitive(sensitive) => {
+            state.sensitive = sensitive;
+            Effect::NONE
+        }
+        PostAction::ToggleSchedule => {
+            state.scheduled_at = match state.scheduled_at {
+                Some(_) => None,
+                None => Some(Utc::now() + Duration::hours(1)),
+            };
+            Effect::NONE
+        }
+        PostAction::UpdateScheduledAt(value) => {
+            let Some(date) = crate::helper::parse_local_datetime(&value) else {
+                state.error_message = Some(format!("Invalid Date: {value:?}"));
+                return Effect::NONE
+            };
+            state.scheduled_at = Some(date);
+            Effect::NONE
+        }
+        PostAction::SaveDraft => {
+            if state.draft_dirty && !state.posting {
+                save_draft(state, environment, true);
+            }
+            Effect::NONE
+        }
+        PostAction::ResumeDraft(id) => {
+            let Some(draft) = environment.repository.draft(&id) else {
+                return Effect::NONE
+            };
+            // Keep what we have so far
+            if state.draft_dirty {
+                save_draft(state, environment, false);
+            }
+            state.draft_id = draft.id;
+            state.kind = draft.kind;
+            state.text = draft.text;
+            state.visibility = draft.visibility;
+            state.spoiler_text = draft.spoiler_text;
+            state.sensitive = draft.sensitive;
+            state.poll = None;
+            state.draft_dirty = false;
+            state.validity = validate_text(environment.model.instance(), &state.text);
+            update_thread(state);
+
+            // Media that was uploaded is linked again, otherwise it is uploaded
+            // again from the temporary directory, if it still exists
+            let (images, missing): (Vec<_>, Vec<_>) = draft
+                .images
+                .into_iter()
+                .partition(|e| e.server_id.is_some() || e.path.exists());
+            if !missing.is_empty() {
+                state.error_message = Some(format!(
+                    "{} attachments could not be restored",
+                    missing.len()
+                ));
+            }
+            let mut images = images;
+            for image in images.iter_mut() {
+                // Check again if the server is done with the uploaded ones
+                image.upload_state = match image.server_id {
+                    Some(_) if image.is_ready() => UploadState::Ready,
+                    Some(_) => UploadState::Processing,
+                    None => UploadState::Uploading,
+                };
+            }
+            let pending: Vec<_> = images
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| e.server_id.is_none())
+                .map(|(index, _)| index)
+                .collect();
+            state.images = images;
+            let poll = poll_media(state);
+            if pending.is_empty() {
+                return poll;
+            }
+            let upload = upload_media(state, pending, environment);
+            Effect::merge2(upload, poll)
+        }
+        PostAction::DeleteDraft(id) => {
+            if let Err(e) = environment.repository.remove_draft(&id) {
+                state.error_message = Some(e);
+            }
+            // The current text is not saved anymore
+            if id == state.draft_id {
+                state.draft_id = Draft::new_id();
+                state.draft_dirty = true;
+            }
+            state.drafts = environment.repository.drafts(&state.account.id);
             Effect::NONE
         }
         PostAction::Post => {
+            if !state.is_media_ready() {
+                state.error_message =
+                    Some("Wait until all attachments are uploaded and processed".to_string());
+                return Effect::NONE;
+            }
+            if let Some(error) = state
+                .poll
+                .as_ref()
+                .and_then(|p| p.validate(&state.poll_limits))
+            {
+                state.error_message = Some(error.to_string());
+                return Effect::NONE;
+            }
+            if let Some(ref thread) = state.thread {
+                // The parts reply to each other, which doesn't work with scheduled posts
+                if state.scheduled_at.is_some() {
+                    state.error_message = Some("Threads can't be scheduled".to_string());
+                    return Effect::NONE;
+                }
+                if thread.parts.is_empty() {
+                    return Effect::NONE;
+                }
+                state.posting = true;
+                return Effect::action(PostAction::PostThreadPart);
+            }
+            // Mastodon requires scheduled statuses to be at least 5 minutes in the future
+            if let Some(date) = state.scheduled_at {
+                if date < Utc::now() + Duration::minutes(5) {
+                    state.error_message =
+                        Some("Posts can only be scheduled 5 minutes ahead or more".to_string());
+                    return Effect::NONE;
+                }
+            }
             state.posting = true;
             let model = environment.model.clone();
-            let reply_to = match state.kind {
-                PostKind::Post => None,
-                PostKind::Reply(ref i) => Some(i.id.0.clone()),
-                PostKind::ReplyPrivate(ref i) => Some(i.id.0.clone()),
+            let reply_to = state.reply_to_id();
+            let quote_id = state.quote_id();
+            // Edited and redrafted posts keep their existing media
+            let existing_media = match state.kind {
+                PostKind::Edit(ref s) | PostKind::Redraft(ref s) => s.media_ids.clone(),
+                _ => Vec::new(),
             };
+            let media_ids: Vec<_> = existing_media
+                .into_iter()
+                .chain(state.images.iter().flat_map(|e| e.server_id.clone()))
+                .collect();
+            let media_ids = (!media_ids.is_empty()).then_some(media_ids);
+            let visibility = state.visibility.as_ref().map(|e| e.into());
+            let text = state.text.clone();
+            let poll = state.poll.as_ref().map(PollState::options);
+            let spoiler_text = state.spoiler_text.clone().filter(|e| !e.trim().is_empty());
+            let sensitive = (media_ids.is_some() && state.sensitive).then_some(true);
+            let scheduled_at = state.scheduled_at;
+            if let PostKind::Edit(ref status) = state.kind {
+                let id = status.id.0.clone();
+                return Effect::future(
+                    async move {
+                        model
+                            .edit_status(id, text, media_ids, poll, spoiler_text, sensitive)
+                            .await
+                            .map(PostedStatus::Status)
+                    },
+                    PostAction::PostResult,
+                );
+            }
+            Effect::future(
+                async move {
+                    model
+                        .post_status(
+                            text,
+                            media_ids,
+                            reply_to,
+                            quote_id,
+                            visibility,
+                            poll,
+                            spoiler_text,
+                            sensitive,
+                            scheduled_at,
+                        )
+                        .await
+                },
+                PostAction::PostResult,
+            )
+        }
+        PostAction::PostThreadPart => {
+            let Some(ref thread) = state.thread else {
+                return Effect::NONE
+            };
+            let index = thread.posted.len();
+            let Some(text) = thread.parts.get(index).cloned() else {
+                return Effect::NONE
+            };
+            let last = thread.parts.len() - 1;
+            // Every part replies to the previous one
+            let reply_to = thread
+                .posted
+                .last()
+                .cloned()
+                .or_else(|| state.reply_to_id());
+            let quote_id = (index == 0).then(|| state.quote_id()).flatten();
+            let poll = (index == 0)
+                .then(|| state.poll.as_ref().map(PollState::options))
+                .flatten();
             let media_ids: Vec<_> = state
                 .images
                 .iter()
+                .filter(|e| thread.media_part(e).min(last) == index)
                 .flat_map(|e| e.server_id.clone())
                 .collect();
             let media_ids = (!media_ids.is_empty()).then_some(media_ids);
             let visibility = state.visibility.as_ref().map(|e| e.into());
-            let text = state.text.clone();
+            let spoiler_text = state.spoiler_text.clone().filter(|e| !e.trim().is_empty());
+            let sensitive = (media_ids.is_some() && state.sensitive).then_some(true);
+            let model = environment.model.clone();
             Effect::future(
                 async move {
                     model
-                        .post_status(text, media_ids, reply_to, None, visibility)
+                        .post_status(
+                            text,
+                            media_ids,
+                            reply_to,
+                            quote_id,
+                            visibility,
+                            poll,
+                            spoiler_text,
+                            sensitive,
+                            None,
+                        )
                         .await
                 },
-                PostAction::PostResult,
+                PostAction::PostedThreadPart,
             )
         }
+        PostAction::PostedThreadPart(result) => {
+            let Some(ref mut thread) = state.thread else {
+                return Effect::NONE
+            };
+            match result {
+                Ok(PostedStatus::Status(status)) => {
+                    thread.posted.push(status.id.clone());
+                    if thread.posted.len() < thread.parts.len() {
+                        return Effect::action(PostAction::PostThreadPart);
+                    }
+                    Effect::action(PostAction::PostResult(Ok(PostedStatus::Status(status))))
+                }
+                Ok(PostedStatus::Scheduled(_)) => {
+                    state.posting = false;
+                    state.error_message = Some("The thread was scheduled".to_string());
+                    Effect::NONE
+                }
+                Err(e) => {
+                    // What was posted stays, posting again resumes with this part
+                    state.posting = false;
+                    state.error_message = Some(format!(
+                        "Part {} of {} could not be posted: {e}",
+                        thread.posted.len() + 1,
+                        thread.parts.len()
+                    ));
+                    Effect::NONE
+                }
+            }
+        }
         PostAction::PostResult(ref result) => {
             state.posting = false;
+            if result.is_ok() {
+                // The post was sent, the draft isn't needed anymore
+                if let Err(e) = environment.repository.remove_draft(&state.draft_id) {
+                    log::error!("Could not remove draft: {e:?}");
+                }
+                state.draft_dirty = false;
+                state.drafts = environment.repository.drafts(&state.account.id);
+            }
             match result {
+                Ok(PostedStatus::Scheduled(scheduled)) => {
+                    let date = scheduled
+                        .scheduled_at
+                        .with_timezone(&chrono::Local)
+                        .format("%x %H:%M");
+                    state.info_message = Some(format!("Scheduled for {date}"));
+                    context.send_parent(action);
+                    Effect::timer(
+                        std::time::Duration::from_secs(2),
+                        PostAction::Close,
+                        "close-scheduled",
+                    )
+                }
                 Ok(_) => {
                     context.send_parent(action);
                     Effect::action(PostAction::Close)
@@ -201,27 +680,14 @@ pub fn reduce<'a>(
                 Effect::NONE
             }
             AppEvent::FileEvent(FileEvent::Dropped(images)) => {
-                let cloned = images.clone();
-                Effect::future(
-                    async move {
-                        let mut m = Vec::new();
-                        for i in cloned {
-                            if let Some(p) =
-                                crate::environment::platform::read_file_to_attachment(&i)
-                            {
-                                m.push(p)
-                            }
-                        }
-                        m
-                    },
-                    PostAction::DroppedMedia,
-                )
+                read_attachments(images.clone(), environment.model.media_limits())
             }
             AppEvent::FileEvent(FileEvent::Cancelled) => {
                 state.dropping_file = false;
                 Effect::NONE
             }
             AppEvent::ClosingWindow => {
+                close_draft(state, environment);
                 environment.platform.update_menu(window, |config| {
                     config.enable_postwindow = false;
                 });
@@ -266,31 +732,26 @@ pub fn reduce<'a>(
                 }
             }
         },
-        PostAction::DroppedMedia(m) => {
+        PostAction::DroppedMedia(results) => {
             state.dropping_file = false;
-            let current = &mut state.images;
-            current.extend(m.clone());
-            let model = environment.model.clone();
-
-            Effect::future(
-                async move {
-                    let mut results = Vec::new();
-                    for image in m {
-                        let i = model
-                            .upload_media(&image.path, image.description.clone())
-                            .await;
-                        results.push((i, image));
-                    }
-                    results
-                },
-                PostAction::DroppedMediaUploaded,
-            )
-        }
-        PostAction::DroppedMediaUploaded(m) => {
-            for (result, image) in m {
-                handle_image_upload(image, result, &mut state.images);
+            if state.poll.is_some() {
+                state.error_message = Some("Polls can't be combined with attachments".to_string());
+                return Effect::NONE;
             }
-            Effect::NONE
+            let mut m = Vec::new();
+            for result in results {
+                match result {
+                    Ok(media) => m.push(media),
+                    Err(e) => state.error_message = Some(e),
+                }
+            }
+            let added = state.images.len()..state.images.len() + m.len();
+            state.images.extend(m);
+            upload_media(state, added.collect(), environment)
+        }
+        PostAction::MediaUploaded(image, result) => {
+            handle_image_upload(image, result, &mut state.images);
+            poll_media(state)
         }
         PostAction::ClearError => {
             state.error_message = None;
@@ -299,6 +760,54 @@ pub fn reduce<'a>(
     }
 }
 
+/// Write the current content as a draft. Empty drafts are removed
+fn save_draft(state: &mut State, environment: &Environment, is_open: bool) {
+    state.draft_dirty = false;
+    let result = if state.text.trim().is_empty() && state.images.is_empty() {
+        environment.repository.remove_draft(&state.draft_id)
+    } else {
+        environment.repository.save_draft(Draft {
+            id: state.draft_id.clone(),
+            account_id: state.account.id.clone(),
+            kind: state.kind.clone(),
+            text: state.text.clone(),
+            visibility: state.visibility,
+            spoiler_text: state.spoiler_text.clone(),
+            sensitive: state.sensitive,
+            images: state.images.clone(),
+            updated: chrono::Utc::now(),
+            is_open,
+        })
+    };
+    if let Err(e) = result {
+        log::error!("Could not save draft: {e:?}");
+    }
+    state.drafts = environment.repository.drafts(&state.account.id);
+}
+
+/// Keep the draft when the composer is closed, but don't restore it
+/// on the next start
+fn close_draft(state: &mut State, environment: &Environment) {
+    let is_saved = state.drafts.iter().any(|e| e.id == state.draft_id);
+    if state.draft_dirty || is_saved {
+        save_draft(state, environment, false);
+    }
+}
+
+/// Split the text again, e.g. after it changed
+fn update_thread(state: &mut State) {
+    // Every part is posted with the content warning, which counts as well
+    let warning = state
+        .spoiler_text
+        .as_ref()
+        .map(|e| e.chars().count())
+        .unwrap_or_default();
+    let max_characters = (state.validity.2 as usize).saturating_sub(warning);
+    if let Some(ref mut thread) = state.thread {
+        thread.update(&state.text, max_characters);
+    }
+}
+
 fn validate_text(instance: Option<Instance>, text: &str) -> (bool, u32, u32) {
     let Some(instance) = instance else {
         return (false, 0, 500)
@@ -325,25 +834,91 @@ fn validate_text(instance: Option<Instance>, text: &str) -> (bool, u32, u32) {
 fn handle_image_upload(
     image: AttachmentMedia,
     result: Result<UploadMedia, String>,
-    media: &mut Vec<AttachmentMedia>,
-) -> Option<String> {
+    media: &mut [AttachmentMedia],
+) {
+    // It may have been removed while uploading, maybe even added again
+    let Some(entry) = media.iter_mut().find(|e| {
+        e.path == image.path && e.upload == image.upload && e.upload_state == UploadState::Uploading
+    }) else {
+        return
+    };
     match result {
         Ok(m) => {
-            for n in media.iter_mut() {
-                if n.path == image.path {
-                    n.server_id = Some(m.id().to_string());
-                    n.is_uploaded = true;
-                    break;
-                }
-            }
-            None
-        }
-        Err(error) => {
-            let Some(index) = media.iter().position(|s| s.path == image.path) else {
-                return None
+            entry.server_id = Some(m.id().to_string());
+            entry.is_uploaded = true;
+            entry.processing_checks = 0;
+            // Large attachments (e.g. videos) are processed after the upload
+            entry.upload_state = match m {
+                UploadMedia::Attachment(_) => UploadState::Ready,
+                UploadMedia::AsyncAttachment(_) => UploadState::Processing,
             };
-            media.remove(index);
-            Some(error)
         }
+        // Kept, so that it can be retried
+        Err(error) => entry.upload_state = UploadState::Failed(error),
+    }
+}
+
+/// Images are processed before they are attached, which takes a while
+fn read_attachments(paths: Vec<PathBuf>, limits: MediaLimits) -> Effect<'static, PostAction> {
+    Effect::future(
+        async move {
+            tokio::task::spawn_blocking(move || {
+                paths
+                    .iter()
+                    .map(|path| {
+                        crate::environment::platform::read_file_to_attachment(path, &limits)
+                    })
+                    .collect()
+            })
+            .await
+            .unwrap_or_else(|e| vec![Err(format!("Could not read the files: {e}"))])
+        },
+        PostAction::DroppedMedia,
+    )
+}
+
+/// Upload the attachments at the indices, each one on its own. Every upload
+/// gets its own number, so the results can arrive in any order and results
+/// of cancelled uploads are ignored
+fn upload_media(
+    state: &mut State,
+    indices: Vec<usize>,
+    environment: &Environment,
+) -> Effect<'static, PostAction> {
+    let mut effect = Effect::NONE;
+    for index in indices {
+        let Some(image) = state.images.get_mut(index) else {
+            continue
+        };
+        state.uploads += 1;
+        image.upload = state.uploads;
+        image.upload_state = UploadState::Uploading;
+        let image = image.clone();
+        let model = environment.model.clone();
+        let path = image.path.clone();
+        let description = image.description.clone();
+        let upload = Effect::future(
+            async move { model.upload_media(&path, description).await },
+            move |result| PostAction::MediaUploaded(image, result),
+        );
+        effect = Effect::merge2(effect, upload);
+    }
+    effect
+}
+
+/// Check the attachments that are processed by the server again in a bit
+fn poll_media(state: &mut State) -> Effect<'static, PostAction> {
+    let is_processing = state
+        .images
+        .iter()
+        .any(|e| e.upload_state == UploadState::Processing);
+    if !is_processing || state.is_polling_media {
+        return Effect::NONE;
     }
+    state.is_polling_media = true;
+    Effect::timer(
+        std::time::Duration::from_secs(2),
+        PostAction::PollMedia,
+        "post-poll-media",
+    )
 }
@@ -1,16 +1,11 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::str::FromStr;
 
-use crate::environment::model::{Account, StatusVisibility};
-use crate::environment::types::UiConfig;
-use crate::view_model::{AttachmentMedia, StatusViewModel};
+use chrono::{DateTime, Utc};
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub enum PostKind {
-    Post,
-    Reply(StatusViewModel),
-    ReplyPrivate(StatusViewModel),
-}
+use crate::environment::model::{Account, Instance, PollOptions};
+use crate::environment::types::{Draft, PostKind, UiConfig, Visibility};
+use crate::view_model::{AttachmentMedia, PollViewModel};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct State {
@@ -26,6 +21,28 @@ pub struct State {
     pub text: String,
     pub validity: (bool, u32, u32),
     pub config: UiConfig,
+    pub poll: Option<PollState>,
+    pub poll_limits: PollLimits,
+    /// The content warning, if one was added
+    pub spoiler_text: Option<String>,
+    /// Mark the attached media as sensitive
+    pub sensitive: bool,
+    /// Post the status at this date instead of right away
+    pub scheduled_at: Option<DateTime<Utc>>,
+    /// Shown after a status was scheduled, before the window closes
+    pub info_message: Option<String>,
+    /// The id under which the current text is saved as a draft
+    pub draft_id: String,
+    /// Did the text change since the draft was saved
+    pub draft_dirty: bool,
+    /// The drafts of the account, to resume from
+    pub drafts: Vec<Draft>,
+    /// Post the text as a thread of replies, if it is too long
+    pub thread: Option<ThreadState>,
+    /// Is a check for attachments that are processed by the server scheduled
+    pub is_polling_media: bool,
+    /// The number of uploads that were started, to tell them apart
+    pub uploads: u64,
 }
 
 impl State {
@@ -43,39 +60,376 @@ impl State {
             text: Default::default(),
             validity: Default::default(),
             config: Default::default(),
+            poll: Default::default(),
+            poll_limits: Default::default(),
+            spoiler_text: Default::default(),
+            sensitive: Default::default(),
+            scheduled_at: Default::default(),
+            info_message: Default::default(),
+            draft_id: Draft::new_id(),
+            draft_dirty: Default::default(),
+            drafts: Default::default(),
+            thread: Default::default(),
+            is_polling_media: Default::default(),
+            uploads: Default::default(),
+        }
+    }
+
+    /// Are all attachments uploaded and processed by the server
+    pub fn is_media_ready(&self) -> bool {
+        self.images.iter().all(|e| e.is_ready())
+    }
+
+    /// Was a part of the thread posted already
+    pub fn is_posting_thread(&self) -> bool {
+        self.thread
+            .as_ref()
+            .map(|e| e.is_started())
+            .unwrap_or_default()
+    }
+
+    /// The status this post replies to
+    pub fn reply_to_id(&self) -> Option<String> {
+        match self.kind {
+            PostKind::Reply(ref i) | PostKind::ReplyPrivate(ref i) => Some(i.id.0.clone()),
+            PostKind::Redraft(ref i) => i.in_reply_to_id.clone(),
+            _ => None,
+        }
+    }
+
+    /// The status this post quotes
+    pub fn quote_id(&self) -> Option<String> {
+        match self.kind {
+            PostKind::Quote(ref i) => Some(i.id.0.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// A long text that is posted as a chain of replies
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct ThreadState {
+    /// Append a `1/n` counter to every part
+    pub numbered: bool,
+    /// The text, split into parts that fit into a post
+    pub parts: Vec<String>,
+    /// The part an attachment is posted with, keyed by the attachment path.
+    /// Attachments that are not in here go with the first part
+    pub media_parts: HashMap<PathBuf, usize>,
+    /// The ids of the parts that were posted so far. If posting failed,
+    /// the thread resumes after the last one
+    pub posted: Vec<String>,
+}
+
+impl ThreadState {
+    pub fn update(&mut self, text: &str, max_characters: usize) {
+        self.parts = split_thread(text, max_characters, self.numbered);
+    }
+
+    /// Did posting start, so that the parts can't change anymore
+    pub fn is_started(&self) -> bool {
+        !self.posted.is_empty()
+    }
+
+    pub fn media_part(&self, media: &AttachmentMedia) -> usize {
+        self.media_parts
+            .get(&media.path)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Split `text` into parts of at most `max_characters`. The text is split
+/// between paragraphs, then between sentences, then between words.
+/// Links are counted with their full length, so parts with links may be
+/// shorter than needed. If there is no room for the counter, the parts
+/// are not numbered
+pub fn split_thread(text: &str, max_characters: usize, numbered: bool) -> Vec<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Vec::new();
+    }
+    if !numbered {
+        return split_text(text, max_characters);
+    }
+    // The counter needs room, which depends on the number of parts
+    let mut count = 1;
+    loop {
+        // The widest counter, e.g. " 10/12"
+        let reserved = format!(" {count}/{count}").chars().count();
+        if reserved >= max_characters {
+            return split_text(text, max_characters);
+        }
+        let parts = split_text(text, max_characters - reserved);
+        if parts.len().to_string().len() > count.to_string().len() {
+            count = parts.len();
+            continue;
+        }
+        let count = parts.len();
+        return parts
+            .into_iter()
+            .enumerate()
+            .map(|(index, part)| format!("{part} {}/{count}", index + 1))
+            .collect();
+    }
+}
+
+fn split_text(text: &str, max: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for paragraph in text.split("\n\n").map(str::trim).filter(|e| !e.is_empty()) {
+        if append(&mut current, paragraph, "\n\n", max) {
+            continue;
+        }
+        push_part(&mut parts, &mut current);
+        if append(&mut current, paragraph, "", max) {
+            continue;
+        }
+        // The paragraph is too long on its own
+        for sentence in sentences(paragraph) {
+            if append(&mut current, sentence, " ", max) {
+                continue;
+            }
+            push_part(&mut parts, &mut current);
+            if append(&mut current, sentence, "", max) {
+                continue;
+            }
+            for word in sentence.split_whitespace() {
+                if append(&mut current, word, " ", max) {
+                    continue;
+                }
+                push_part(&mut parts, &mut current);
+                // A word that is longer than a post is cut
+                let chars: Vec<char> = word.chars().collect();
+                for chunk in chars.chunks(max.max(1)) {
+                    push_part(&mut parts, &mut current);
+                    current = chunk.iter().collect();
+                }
+            }
         }
     }
+    push_part(&mut parts, &mut current);
+    parts
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub enum Visibility {
-    Public,
-    Unlisted,
-    Private,
-    Direct,
+/// Append `text` to `current` if the result fits into `max`
+fn append(current: &mut String, text: &str, separator: &str, max: usize) -> bool {
+    let separator = if current.is_empty() { "" } else { separator };
+    let length = current.chars().count() + separator.chars().count() + text.chars().count();
+    if length > max {
+        return false;
+    }
+    current.push_str(separator);
+    current.push_str(text);
+    true
 }
 
-impl FromStr for Visibility {
-    type Err = String;
+fn push_part(parts: &mut Vec<String>, current: &mut String) {
+    if !current.is_empty() {
+        parts.push(std::mem::take(current));
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, String> {
-        match s {
-            "public" => Ok(Visibility::Public),
-            "unlisted" => Ok(Visibility::Unlisted),
-            "private" => Ok(Visibility::Private),
-            "direct" => Ok(Visibility::Direct),
-            _ => Err("INVALID".to_string()),
+/// The sentences of a paragraph, including their punctuation
+fn sentences(paragraph: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut chars = paragraph.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let is_end = matches!(c, '.' | '!' | '?' | '\n')
+            && chars.peek().map(|(_, n)| n.is_whitespace()).unwrap_or(true);
+        if is_end {
+            let end = index + c.len_utf8();
+            let sentence = paragraph[start..end].trim();
+            if !sentence.is_empty() {
+                result.push(sentence);
+            }
+            start = end;
         }
     }
+    let rest = paragraph[start..].trim();
+    if !rest.is_empty() {
+        result.push(rest);
+    }
+    result
 }
 
-impl From<&Visibility> for StatusVisibility {
-    fn from(value: &Visibility) -> Self {
-        match value {
-            Visibility::Public => StatusVisibility::Public,
-            Visibility::Unlisted => StatusVisibility::Unlisted,
-            Visibility::Private => StatusVisibility::Private,
-            Visibility::Direct => StatusVisibility::Direct,
+/// A poll that is being composed
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PollState {
+    pub options: Vec<String>,
+    /// Duration in seconds
+    pub expires_in: u64,
+    pub multiple: bool,
+}
+
+impl Default for PollState {
+    fn default() -> Self {
+        Self {
+            options: vec![String::new(), String::new()],
+            expires_in: 60 * 60 * 24,
+            multiple: false,
         }
     }
 }
+
+impl PollState {
+    /// The poll of a status that is edited or redrafted. Mastodon keeps
+    /// the votes as long as the options don't change
+    pub fn from_poll(poll: &PollViewModel) -> Self {
+        let remaining = poll
+            .expires_at
+            .map(|e| e.signed_duration_since(Utc::now()).num_seconds())
+            .filter(|e| *e > 0)
+            .map(|e| (e as u64).max(60 * 5));
+        Self {
+            options: poll.options.iter().map(|e| e.title.clone()).collect(),
+            expires_in: remaining.unwrap_or(Self::default().expires_in),
+            multiple: poll.multiple,
+        }
+    }
+
+    /// Returns an error message if the poll can't be posted
+    pub fn validate(&self, limits: &PollLimits) -> Option<&'static str> {
+        if self.options.iter().filter(|e| !e.trim().is_empty()).count() < 2 {
+            return Some("A poll needs at least two options");
+        }
+        if self.options.len() > limits.max_options {
+            return Some("The poll has too many options");
+        }
+        if self
+            .options
+            .iter()
+            .any(|e| e.chars().count() > limits.max_characters_per_option)
+        {
+            return Some("A poll option is too long");
+        }
+        None
+    }
+
+    pub fn options(&self) -> PollOptions {
+        PollOptions {
+            options: self
+                .options
+                .iter()
+                .filter(|e| !e.trim().is_empty())
+                .cloned()
+                .collect(),
+            expires_in: Some(self.expires_in),
+            multiple: Some(self.multiple),
+            hide_totals: None,
+        }
+    }
+}
+
+/// The poll limits of the instance. Defaults to the Mastodon defaults
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PollLimits {
+    pub max_options: usize,
+    pub max_characters_per_option: usize,
+    pub min_expiration: u64,
+    pub max_expiration: u64,
+}
+
+impl Default for PollLimits {
+    fn default() -> Self {
+        Self {
+            max_options: 4,
+            max_characters_per_option: 50,
+            min_expiration: 300,
+            max_expiration: 2629746,
+        }
+    }
+}
+
+impl PollLimits {
+    pub fn new(instance: Option<&Instance>) -> Self {
+        let Some(polls) = instance.and_then(|e| e.configuration.polls.as_ref()) else {
+            return Self::default()
+        };
+        Self {
+            max_options: polls.max_options as usize,
+            max_characters_per_option: polls.max_characters_per_option as usize,
+            min_expiration: polls.min_expiration as u64,
+            max_expiration: polls.max_expiration as u64,
+        }
+    }
+
+    /// The selectable poll durations (in seconds, title) within the limits
+    pub fn durations(&self) -> Vec<(u64, &'static str)> {
+        [
+            (60 * 5, "5 minutes"),
+            (60 * 30, "30 minutes"),
+            (60 * 60, "1 hour"),
+            (60 * 60 * 6, "6 hours"),
+            (60 * 60 * 24, "1 day"),
+            (60 * 60 * 24 * 3, "3 days"),
+            (60 * 60 * 24 * 7, "7 days"),
+        ]
+        .into_iter()
+        .filter(|(d, _)| *d >= self.min_expiration && *d <= self.max_expiration)
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lengths(parts: &[String]) -> Vec<usize> {
+        parts.iter().map(|e| e.chars().count()).collect()
+    }
+
+    #[test]
+    fn splits_between_words() {
+        let parts = split_thread("one two three four", 9, false);
+        assert_eq!(parts, vec!["one two", "three", "four"]);
+    }
+
+    #[test]
+    fn splits_between_sentences() {
+        let parts = split_thread("First one. Second one! Third?", 24, false);
+        assert_eq!(parts, vec!["First one. Second one!", "Third?"]);
+    }
+
+    #[test]
+    fn keeps_links_whole() {
+        let text = "Read https://example.com/a.b?c=d. Then this.";
+        let parts = split_thread(text, 34, false);
+        assert_eq!(
+            parts,
+            vec!["Read https://example.com/a.b?c=d.", "Then this."]
+        );
+    }
+
+    #[test]
+    fn cuts_words_that_are_too_long() {
+        let parts = split_thread("abcdefghij", 4, false);
+        assert_eq!(parts, vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn numbered_parts_fit() {
+        let text = "word ".repeat(20);
+        let parts = split_thread(&text, 20, true);
+        assert!(parts.len() < 10);
+        assert!(lengths(&parts).iter().all(|e| *e <= 20));
+        assert!(parts[0].ends_with(&format!(" 1/{}", parts.len())));
+    }
+
+    #[test]
+    fn numbered_parts_fit_with_ten_or_more_parts() {
+        let text = "word ".repeat(100);
+        let parts = split_thread(&text, 20, true);
+        assert!(parts.len() >= 10);
+        assert!(lengths(&parts).iter().all(|e| *e <= 20));
+        let last = format!(" {0}/{0}", parts.len());
+        assert!(parts.last().unwrap().ends_with(&last));
+    }
+
+    #[test]
+    fn no_numbers_without_room() {
+        let parts = split_thread("abc def", 4, true);
+        assert_eq!(parts, vec!["abc", "def"]);
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(non_snake_case)]
 
-use crate::view_model::AttachmentMedia;
+use crate::environment::menu::{self, ViewStoreContextMenu};
+use crate::view_model::{AttachmentMedia, UploadState};
 use crate::{loc, widgets::*};
 
 use dioxus::prelude::*;
@@ -14,9 +15,11 @@ pub fn PostView<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
 
     let is_dropping_file = store.dropping_file;
 
-    if is_dropping_file {
+    // The parts of a thread can't change once it is being posted
+    if is_dropping_file || store.is_posting_thread() {
         is_posting_class = "true";
     }
+    let show_count = store.thread.is_none();
 
     // FIXME: Hack
     crate::environment::platform::execute_js_once(
@@ -38,6 +41,16 @@ pub fn PostView<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
                 ToolbarView {
                     store: store
                 }
+                store.spoiler_text.as_ref().map(|spoiler_text| rsx!(input {
+                    class: "content-warning-input",
+                    r#type: "text",
+                    placeholder: "Content Warning",
+                    value: "{spoiler_text}",
+                    oninput: move |evt| {
+                        store.send(PostAction::UpdateContentWarning(evt.value.clone()));
+                    },
+                }))
+                ScheduleView { store: store }
                 textarea {
                     id: "text-area",
                     disabled: is_posting_class,
@@ -54,7 +67,11 @@ pub fn PostView<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
                     store.send(PostAction::ClearError);
                 }
             })) },
-                {
+                store.info_message.as_ref().map(|info| rsx!(div {
+                    class: "info-box",
+                    "{info}"
+                }))
+                { show_count.then(|| {
                 if !store.validity.0 {
                     rsx!(Label {
                         class: "char-count over",
@@ -66,19 +83,81 @@ pub fn PostView<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
                         "{store.validity.1} / {store.validity.2}"
                     })
                 }
-            },
+            })},
+                ThreadView { store: store }
+                QuotePreview { store: store }
+                PollEditorView { store: store }
                 ImagesView { store: store }
             }
         }
     ))
 }
 
+#[inline_props]
+fn ThreadView<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
+    let Some(thread) = store.thread.as_ref() else {
+        return cx.render(rsx!(div {}));
+    };
+    let numbered = thread.numbered;
+    let posted = thread.posted.len();
+    let count = thread.parts.len();
+    let is_started = thread.is_started();
+    cx.render(rsx!(
+        VStack { class: "thread-editor p-2 gap-2",
+            HStack { class: "align-items-center gap-2",
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: "{numbered}",
+                        disabled: "{is_started}",
+                        onclick: move |_| store.send(PostAction::UpdateThreadNumbered(!numbered)),
+                    }
+                    loc!("Number the posts")
+                }
+                span { class: "me-auto" }
+                Label {
+                    style: TextStyle::Secondary,
+                    "{posted} / {count}"
+                }
+            }
+            thread.parts.iter().enumerate().map(|(index, part)| {
+                let class = if index < posted { "thread-part posted" } else { "thread-part" };
+                rsx!(div {
+                    class: "{class}",
+                    Label {
+                        style: TextStyle::Tertiary,
+                        "{index + 1}"
+                    }
+                    Paragraph {
+                        style: TextStyle::Secondary,
+                        "{part}"
+                    }
+                })
+            })
+        }
+    ))
+}
+
+#[inline_props]
+fn QuotePreview<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
+    let super::PostKind::Quote(ref status) = store.kind else {
+        return cx.render(rsx!(div {}));
+    };
+    cx.render(rsx!(
+        div { class: "quote-preview p-2",
+            QuotedStatusCard {
+                status: status
+            }
+        }
+    ))
+}
+
 #[inline_props]
 fn ToolbarView<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
     let mut is_posting_class = if store.posting { "true" } else { "false" };
 
-    // can't post if there're still images being uploaded
-    let are_images_uploading = store.images.iter().any(|i| i.server_id.is_none());
+    // can't post if there're still images being uploaded or processed
+    let are_images_uploading = store.images.iter().any(|i| !i.is_ready());
     if are_images_uploading {
         is_posting_class = "true";
     }
@@ -86,6 +165,25 @@ fn ToolbarView<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
     let current = store.visibility.unwrap_or(super::Visibility::Public);
     let is_direct = current == super::Visibility::Direct;
 
+    // Polls and attachments can't be combined
+    let has_poll = store.poll.is_some();
+    let attach_disabled = if has_poll { "true" } else { is_posting_class };
+    let poll_disabled = if store.images.is_empty() { is_posting_class } else { "true" };
+    let poll_title = if has_poll { loc!("Remove Poll") } else { loc!("Poll") };
+    let cw_class = if store.spoiler_text.is_some() { "highlighted" } else { "" };
+    let schedule_class = if store.scheduled_at.is_some() { "highlighted" } else { "" };
+    // Edits are applied right away
+    let is_edit = matches!(store.kind, super::PostKind::Edit(_));
+    let post_title = if is_edit {
+        loc!("Save")
+    } else if store.is_posting_thread() {
+        loc!("Resume")
+    } else {
+        loc!("Toot")
+    };
+    let thread_class = if store.thread.is_some() { "highlighted" } else { "" };
+    let thread_disabled = if store.is_posting_thread() { "true" } else { is_posting_class };
+
     cx.render(rsx!(
         HStack { class: "p-1 justify-content-between align-items-center posting-toolbar",
             (!store.is_window).then(|| rsx! {
@@ -127,8 +225,55 @@ fn ToolbarView<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
                 }
             }
             button {
-                class: "button me-3",
+                class: "button me-3 {cw_class}",
+                title: loc!("Content Warning"),
                 disabled: is_posting_class,
+                onclick: move |_| store.send(PostAction::ToggleContentWarning),
+                "CW"
+            }
+            (!is_edit).then(|| rsx!(button {
+                class: "button me-3 {thread_class}",
+                title: loc!("Split long text into a thread"),
+                disabled: thread_disabled,
+                onclick: move |_| store.send(PostAction::ToggleThread),
+                loc!("Thread")
+            }))
+            (!is_edit).then(|| rsx!(button {
+                class: "button me-3 {schedule_class}",
+                title: loc!("Schedule"),
+                disabled: is_posting_class,
+                onclick: move |_| store.send(PostAction::ToggleSchedule),
+                loc!("Schedule")
+            }))
+            button {
+                class: "button me-3",
+                disabled: "{store.drafts.is_empty()}",
+                onclick: move |e: Event<MouseData>| {
+                    let items = store.drafts.iter().map(|draft| {
+                        menu::ContextMenuItem::submenu(draft.title(), vec![
+                            menu::ContextMenuItem::item(
+                                loc!("Open"),
+                                PostAction::ResumeDraft(draft.id.clone())
+                            ),
+                            menu::ContextMenuItem::item(
+                                loc!("Delete"),
+                                PostAction::DeleteDraft(draft.id.clone())
+                            ),
+                        ])
+                    }).collect();
+                    store.context_menu(cx, &e.data, menu::ContextMenu::new(loc!("Drafts"), true, items))
+                },
+                loc!("Drafts")
+            }
+            button {
+                class: "button me-3",
+                disabled: poll_disabled,
+                onclick: move |_| store.send(PostAction::TogglePoll),
+                "{poll_title}"
+            }
+            button {
+                class: "button me-3",
+                disabled: attach_disabled,
                 r#type: "file",
                 onclick: move |_| store.send(PostAction::FileDialog),
                 "Attach"
@@ -137,7 +282,105 @@ fn ToolbarView<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
                 class: "button me-2 highlighted",
                 disabled: is_posting_class,
                 onclick: move |_| store.send(PostAction::Post),
-                loc!("Toot")
+                "{post_title}"
+            }
+        }
+    ))
+}
+
+#[inline_props]
+fn ScheduleView<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
+    let Some(scheduled_at) = store.scheduled_at.as_ref() else {
+        return cx.render(rsx!(div {}));
+    };
+    let value = crate::helper::format_local_datetime(scheduled_at);
+    cx.render(rsx!(
+        HStack { class: "schedule-editor p-2 gap-2 align-items-center",
+            label {
+                class: "label-secondary",
+                r#for: "scheduled-at",
+                loc!("Post at")
+            }
+            input {
+                id: "scheduled-at",
+                r#type: "datetime-local",
+                value: "{value}",
+                onchange: move |evt| {
+                    store.send(PostAction::UpdateScheduledAt(evt.value.clone()));
+                },
+            }
+        }
+    ))
+}
+
+#[inline_props]
+fn PollEditorView<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
+    let Some(poll) = store.poll.as_ref() else {
+        return cx.render(rsx!(div {}));
+    };
+    let limits = &store.poll_limits;
+    let can_add = poll.options.len() < limits.max_options;
+    let can_remove = poll.options.len() > 2;
+    let max_length = limits.max_characters_per_option;
+    let multiple = poll.multiple;
+    let durations = limits.durations();
+    // The remaining time of the poll of an edited or redrafted status
+    let is_remaining = !durations.iter().any(|(d, _)| *d == poll.expires_in);
+    let expires_in = poll.expires_in;
+    cx.render(rsx!(
+        VStack { class: "poll-editor p-2 gap-2",
+            poll.options.iter().enumerate().map(|(index, option)| rsx!(HStack {
+                class: "align-items-center gap-2",
+                input {
+                    class: "grow",
+                    r#type: "text",
+                    placeholder: "Option {index + 1}",
+                    maxlength: "{max_length}",
+                    value: "{option}",
+                    oninput: move |evt| {
+                        store.send(PostAction::UpdatePollOption(index, evt.value.clone()));
+                    },
+                }
+                can_remove.then(|| rsx!(IconButton {
+                    icon: crate::icons::ICON_DELETE,
+                    title: "Remove Option",
+                    onclick: move |_| {
+                        store.send(PostAction::RemovePollOption(index))
+                    }
+                }))
+            }))
+            HStack { class: "align-items-center gap-2",
+                button {
+                    class: "button",
+                    disabled: "{!can_add}",
+                    onclick: move |_| store.send(PostAction::AddPollOption),
+                    loc!("Add Option")
+                }
+                span { class: "me-auto" }
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: "{multiple}",
+                        onclick: move |_| store.send(PostAction::UpdatePollMultiple(!multiple)),
+                    }
+                    loc!("Multiple Choice")
+                }
+                select {
+                    name: "poll-duration",
+                    onchange: move |evt| {
+                        store.send(PostAction::UpdatePollExpiry(evt.value.clone()));
+                    },
+                    is_remaining.then(|| rsx!(option {
+                        value: "{expires_in}",
+                        selected: "true",
+                        loc!("Unchanged")
+                    })),
+                    durations.into_iter().map(|(duration, title)| rsx!(option {
+                        value: "{duration}",
+                        selected: "{duration == poll.expires_in}",
+                        "{title}"
+                    }))
+                }
             }
         }
     ))
@@ -148,8 +391,22 @@ fn ImagesView<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
     if store.images.is_empty() {
         return cx.render(rsx!(div {}));
     }
+    let sensitive = store.sensitive;
     cx.render(rsx!(
         VStack { class: "images",
+            HStack { class: "p-2 align-items-center gap-2",
+                input {
+                    r#type: "checkbox",
+                    id: "sensitive",
+                    checked: "{sensitive}",
+                    onclick: move |_| store.send(PostAction::UpdateSensitive(!sensitive)),
+                }
+                label {
+                    class: "label-secondary",
+                    r#for: "sensitive",
+                    loc!("Mark media as sensitive")
+                }
+            }
             store.images.iter().enumerate().map(|(index, image)| {
             cx.render(rsx!(SingleImageView {
                 store: store,
@@ -171,6 +428,27 @@ fn SingleImageView<'a>(
     let is_editing = use_state(cx, || false);
     let text = use_ref(cx, String::new);
     let is_uploaded = image.server_id.is_some();
+    let is_busy = matches!(
+        image.upload_state,
+        UploadState::Uploading | UploadState::Processing
+    );
+    let is_failed = matches!(image.upload_state, UploadState::Failed(_));
+    let state_class = match image.upload_state {
+        UploadState::Uploading | UploadState::Processing => "busy",
+        UploadState::Ready => "ready",
+        UploadState::Failed(_) => "failed",
+    };
+    let remove_title = if image.is_ready() {
+        "Remove"
+    } else {
+        "Cancel Upload"
+    };
+    // In a thread, every attachment can go with a different part
+    let parts = store
+        .thread
+        .as_ref()
+        .filter(|e| e.parts.len() > 1 && !e.is_started())
+        .map(|e| (e.media_part(image).min(e.parts.len() - 1), e.parts.len()));
     cx.render(rsx!(
         div {
             HStack { class: "p-2 align-items-center gap-2",
@@ -214,6 +492,20 @@ fn SingleImageView<'a>(
                         class: "label-tertiary me-auto overflow-y-hidden",
                         "{text.read()}"
                     }
+                    { parts.map(|(selected, count)| rsx!(select {
+                        title: "Post with part",
+                        onchange: move |evt| {
+                            let Ok(part) = evt.value.parse::<usize>() else {
+                                return
+                            };
+                            store.send(PostAction::UpdateMediaPart(*index, part));
+                        },
+                        (0..count).map(|part| rsx!(option {
+                            value: "{part}",
+                            selected: "{part == selected}",
+                            "Part {part + 1}"
+                        }))
+                    }))}
                     { is_uploaded.then(|| rsx!(
                         IconButton {
                             icon: crate::icons::ICON_EDIT_CAPTION,
@@ -223,11 +515,24 @@ fn SingleImageView<'a>(
                             }
                         }
                     ))}
-                    { (!is_uploaded).then(|| rsx!(
+                    span {
+                        class: "upload-state upload-state-{state_class}",
+                        "{image.upload_state}"
+                    }
+                    { is_busy.then(|| rsx!(
                         Spinner {
                             class: "mt-1 me-3"
                         }
                     ))}
+                    { is_failed.then(|| rsx!(
+                        IconButton {
+                            icon: crate::icons::ICON_RELOAD,
+                            title: "Retry",
+                            onclick: move |_| {
+                                store.send(PostAction::RetryUpload(*index))
+                            }
+                        }
+                    ))}
                     IconButton {
                         icon: crate::icons::ICON_INFO,
                         title: "Show on Disk",
@@ -237,13 +542,20 @@ fn SingleImageView<'a>(
                     }
                     IconButton {
                         icon: crate::icons::ICON_DELETE,
-                        title: "Remove",
+                        title: remove_title,
                         onclick: move |_| {
                             store.send(PostAction::RemoveImage(*index))
                         }
                     }
                 }}}
             }
+            // What was done to the file before uploading it
+            VStack { class: "attachment-processing px-2",
+                image.processing.iter().map(|step| rsx!(Label {
+                    style: TextStyle::Tertiary,
+                    "{step}"
+                }))
+            }
         }
     ))
 }
@@ -4,8 +4,8 @@ mod view;
 pub use reducer::reduce as profile_reducer;
 pub use reducer::{ProfileAction, ProfileState, ViewStore};
 pub use view::{
-    FollowProfileComponent, ListProfileComponent, ProfileComponent, ProfilePageHeader,
-    SelectedProfileTab,
+    list_menu_items, moderation_menu_items, FollowProfileComponent, ListProfileComponent,
+    ProfileComponent, ProfilePageHeader, SelectedProfileTab,
 };
 
 pub struct ProfilePreviewReducer;
@@ -1,5 +1,6 @@
 use crate::environment::model::Relationship;
 
+use crate::environment::types::AccountModeration;
 use crate::environment::Environment;
 use crate::view_model::AccountViewModel;
 use crate::PublicAction;
@@ -16,6 +17,8 @@ pub enum ProfileAction {
     ToggleFollow,
     ToggleFollowResult(Result<bool, String>),
     ToggleFavourite,
+    Moderate(AccountModeration),
+    ModerateResult(AccountModeration, Result<Option<Relationship>, String>),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
@@ -33,6 +36,10 @@ pub struct ProfileState {
     /// Did we mute them?
     pub muting: bool,
     pub blocking: bool,
+    /// Did we block their domain?
+    pub domain_blocking: bool,
+    /// The `(id, title)` of our lists
+    pub lists: Vec<(String, String)>,
     pub error: Option<String>,
 }
 
@@ -57,6 +64,15 @@ pub fn reduce<'a>(
         ProfileAction::Initial => {
             let id = state.account.id.0.clone();
             state.is_favorite = environment.repository.is_favorite(&id).unwrap_or_default();
+            let mut lists: Vec<_> = environment.storage.with(|s| {
+                s.timelines
+                    .iter()
+                    .filter(|e| !e.0.is_empty())
+                    .map(|(key, value)| (key.clone(), value.title.clone()))
+                    .collect()
+            });
+            lists.sort_by_key(|e| e.1.to_lowercase());
+            state.lists = lists;
             if state.load_initial {
                 return Effect::action(ProfileAction::LoadRelationship);
             }
@@ -78,6 +94,7 @@ pub fn reduce<'a>(
                     state.followed_by = r.followed_by;
                     state.blocking = r.blocking;
                     state.muting = r.muting;
+                    state.domain_blocking = r.domain_blocking;
                 }
                 Err(e) => {
                     state.error = Some(e);
@@ -126,6 +143,46 @@ pub fn reduce<'a>(
                 .toggle_favorite(state.account.id.0.clone());
             state.is_favorite = !state.is_favorite;
         }
+        ProfileAction::Moderate(moderation) => {
+            if let Some(message) = moderation.confirmation() {
+                if !crate::environment::platform::confirm_dialog(&state.account.acct, message) {
+                    return Effect::NONE;
+                }
+            }
+            state.is_loading = true;
+            let id = state.account.id.0.clone();
+            let domain = state.account.domain().map(|e| e.to_string());
+            let model = environment.model.clone();
+            return Effect::future(
+                async move { model.moderate(id, domain, moderation).await },
+                move |result| ProfileAction::ModerateResult(moderation, result),
+            );
+        }
+        ProfileAction::ModerateResult(moderation, result) => {
+            state.is_loading = false;
+            match result {
+                Ok(relationship) => {
+                    if let Some(r) = relationship {
+                        state.following = r.following;
+                        state.blocking = r.blocking;
+                        state.muting = r.muting;
+                    }
+                    match moderation {
+                        AccountModeration::BlockDomain => state.domain_blocking = true,
+                        AccountModeration::UnblockDomain => state.domain_blocking = false,
+                        _ => (),
+                    }
+                    let account = state.account.clone();
+                    environment
+                        .storage
+                        .with_mutation(|mut s| s.apply_moderation(&account, moderation));
+                }
+                Err(e) => {
+                    log::error!("Could not moderate {moderation:?} {e:?}");
+                    state.error = Some(e);
+                }
+            }
+        }
         ProfileAction::Public(action) => {
             context.send_parent(action);
         }
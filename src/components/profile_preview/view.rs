@@ -4,6 +4,7 @@ use super::reducer::{ProfileAction, ViewStore};
 use crate::PublicAction;
 use crate::{
     environment::menu::{self, ViewStoreContextMenu},
+    environment::types::AccountModeration,
     loc,
     view_model::AccountViewModel,
     widgets::*,
@@ -488,6 +489,19 @@ fn ProfileActions<'a>(
                 if !items.is_empty() {
                     menu.push(menu::ContextMenuItem::submenu("Links", items));
                 }
+                menu.push(menu::ContextMenuItem::separator());
+                menu.extend(moderation_menu_items(
+                    account,
+                    (store.muting, store.blocking, store.domain_blocking),
+                    ProfileAction::Moderate,
+                ));
+                menu.extend(list_menu_items(&store.lists, |id, add| {
+                    Public(PublicAction::UpdateListMembership((*account).clone(), id, add))
+                }));
+                menu.push(menu::ContextMenuItem::item(
+                    loc!("Report..."),
+                    Public(PublicAction::Report((*account).clone(), None)),
+                ));
                 store.context_menu(
                     cx,
                     &e.data, menu::ContextMenu::<ProfileAction>::new("Actions", true, menu))
@@ -495,3 +509,86 @@ fn ProfileActions<'a>(
         }
     }
 }
+
+/// The mute / block entries for an account. `state` is whether we're
+/// currently (muting, blocking, domain blocking) the account
+pub fn moderation_menu_items<T: Send + Sync + 'static>(
+    account: &AccountViewModel,
+    state: (bool, bool, bool),
+    action: impl Fn(AccountModeration) -> T,
+) -> Vec<menu::ContextMenuItem> {
+    let (muting, blocking, domain_blocking) = state;
+    let durations = [
+        (loc!("Indefinitely"), None),
+        (loc!("1 Hour"), Some(60 * 60)),
+        (loc!("1 Day"), Some(60 * 60 * 24)),
+        (loc!("7 Days"), Some(60 * 60 * 24 * 7)),
+    ];
+    let mute_menu = |title: &str, notifications: bool| {
+        let items = durations
+            .iter()
+            .map(|(name, duration)| {
+                menu::ContextMenuItem::item(
+                    name,
+                    action(AccountModeration::Mute {
+                        duration: *duration,
+                        notifications,
+                    }),
+                )
+            })
+            .collect();
+        menu::ContextMenuItem::submenu(title, items)
+    };
+
+    let mut items = Vec::new();
+    if muting {
+        items.push(menu::ContextMenuItem::item(
+            loc!("Unmute"),
+            action(AccountModeration::Unmute),
+        ));
+    } else {
+        items.push(mute_menu(loc!("Mute"), false));
+        items.push(mute_menu(loc!("Mute (Hide Notifications)"), true));
+    }
+    items.push(if blocking {
+        menu::ContextMenuItem::item(loc!("Unblock"), action(AccountModeration::Unblock))
+    } else {
+        menu::ContextMenuItem::item(loc!("Block"), action(AccountModeration::Block))
+    });
+    if let Some(domain) = account.domain() {
+        items.push(if domain_blocking {
+            menu::ContextMenuItem::item(
+                format!("Unblock {domain}"),
+                action(AccountModeration::UnblockDomain),
+            )
+        } else {
+            menu::ContextMenuItem::item(
+                format!("Block {domain}"),
+                action(AccountModeration::BlockDomain),
+            )
+        });
+    }
+    items
+}
+
+/// The "Add to List" / "Remove from List" entries for an account.
+/// `lists` are the `(id, title)` of the user's lists
+pub fn list_menu_items<T: Send + Sync + 'static>(
+    lists: &[(String, String)],
+    action: impl Fn(String, bool) -> T,
+) -> Vec<menu::ContextMenuItem> {
+    if lists.is_empty() {
+        return Vec::new();
+    }
+    let submenu = |title: &str, add: bool| {
+        let items = lists
+            .iter()
+            .map(|(id, name)| menu::ContextMenuItem::item(name, action(id.clone(), add)))
+            .collect();
+        menu::ContextMenuItem::submenu(title, items)
+    };
+    vec![
+        submenu(loc!("Add to List"), true),
+        submenu(loc!("Remove from List"), false),
+    ]
+}
@@ -7,16 +7,21 @@ pub use view::ProfilesView;
 pub struct ProfilesReducer;
 use std::cell::RefCell;
 
-use megalodon::entities::List;
 use navicula::reducer::Reducer;
 
 use crate::{
-    environment::{model::Account, Environment},
+    environment::{
+        model::{Account, AccountList},
+        Environment,
+    },
     view_model::{AccountId, AccountViewModel},
 };
 
 use super::status_timeline::TimelineProvider;
 
+/// The identifier of the provider of our follow requests
+pub const FOLLOW_REQUESTS_ID: &str = "follow-requests";
+
 impl Reducer for ProfilesReducer {
     type Message = reducer::ProfilesMessage;
 
@@ -51,7 +56,10 @@ impl Reducer for ProfilesReducer {
 pub enum ProfilesKind {
     Followers(AccountId),
     Following(AccountId),
-    List(AccountId, List),
+    /// The members of a list of the account
+    List(AccountId, AccountList),
+    /// The accounts that asked to follow the (locked) account
+    FollowRequests(AccountId),
 }
 
 impl PartialEq for ProfilesKind {
@@ -59,7 +67,8 @@ impl PartialEq for ProfilesKind {
         match (self, other) {
             (Self::Followers(l0), Self::Followers(r0)) => l0 == r0,
             (Self::Following(l0), Self::Following(r0)) => l0 == r0,
-            (Self::List(l0, _), Self::List(r0, _)) => l0 == r0,
+            (Self::List(l0, l1), Self::List(r0, r1)) => l0 == r0 && l1.id == r1.id,
+            (Self::FollowRequests(l0), Self::FollowRequests(r0)) => l0 == r0,
             _ => false,
         }
     }
@@ -73,6 +82,7 @@ impl ProfilesKind {
             ProfilesKind::Followers(a) => a,
             ProfilesKind::Following(a) => a,
             ProfilesKind::List(a, _) => a,
+            ProfilesKind::FollowRequests(a) => a,
         }
     }
 }
@@ -90,8 +100,13 @@ pub struct FollowersTimelineProvider {
 
 impl FollowersTimelineProvider {
     pub fn new(kind: ProfilesKind, environment: &Environment) -> Self {
+        let id = match &kind {
+            ProfilesKind::List(_, list) => format!("list-{}", list.id),
+            ProfilesKind::FollowRequests(_) => FOLLOW_REQUESTS_ID.to_string(),
+            _ => format!("followers-{}", &kind.id().0),
+        };
         Self {
-            id: format!("followers-{}", &kind.id().0),
+            id,
             kind,
             data: RefCell::default(),
             environment: environment.clone(),
@@ -135,7 +150,13 @@ impl TimelineProvider for FollowersTimelineProvider {
                 let id = a.clone();
                 Box::pin(async move { model.following(id.0, after).await })
             }
-            ProfilesKind::List(_, _) => todo!(),
+            ProfilesKind::List(_, list) => {
+                let id = list.id.clone();
+                Box::pin(async move { model.list_accounts(id, after).await })
+            }
+            ProfilesKind::FollowRequests(_) => {
+                Box::pin(async move { model.follow_requests(after).await })
+            }
         }
     }
 
@@ -145,7 +166,18 @@ impl TimelineProvider for FollowersTimelineProvider {
         _direction: crate::environment::types::TimelineDirection,
         is_reload: bool,
     ) -> bool {
-        let mut converted = updates.iter().map(AccountViewModel::new).collect();
+        let mut converted: Vec<_> = updates.iter().map(AccountViewModel::new).collect();
+        // Follow requests are also answered and counted elsewhere
+        if let ProfilesKind::FollowRequests(_) = self.kind {
+            self.environment.storage.with_mutation(|mut storage| {
+                if is_reload {
+                    storage.follow_requests = converted;
+                } else {
+                    storage.follow_requests.append(&mut converted);
+                }
+            });
+            return false;
+        }
         if is_reload {
             self.data.replace(converted);
         } else {
@@ -158,6 +190,12 @@ impl TimelineProvider for FollowersTimelineProvider {
         &self,
         _direction: crate::environment::types::TimelineDirection,
     ) -> Vec<AccountViewModel> {
+        if let ProfilesKind::FollowRequests(_) = self.kind {
+            return self
+                .environment
+                .storage
+                .with(|storage| storage.follow_requests.clone());
+        }
         self.data.borrow().clone()
     }
 
@@ -166,6 +204,156 @@ impl TimelineProvider for FollowersTimelineProvider {
     }
 }
 
+/// The accounts of a notification group (e.g. everyone who boosted a post)
+#[derive(Debug, Clone)]
+pub struct NotificationGroupProfilesProvider {
+    id: String,
+    group: String,
+    environment: Environment,
+}
+
+impl NotificationGroupProfilesProvider {
+    pub fn new(group: String, environment: &Environment) -> Self {
+        Self {
+            id: format!("notification-group-{group}"),
+            group,
+            environment: environment.clone(),
+        }
+    }
+}
+
+impl TimelineProvider for NotificationGroupProfilesProvider {
+    type Id = AccountId;
+    type Element = Account;
+    type ViewModel = AccountViewModel;
+    fn should_auto_reload(&self) -> bool {
+        false
+    }
+
+    fn identifier(&self) -> &str {
+        &self.id
+    }
+
+    fn reset(&self) {}
+
+    fn forced_direction(&self) -> Option<crate::environment::types::TimelineDirection> {
+        None
+    }
+
+    fn request_data(
+        &self,
+        _after: Option<AccountId>,
+    ) -> std::pin::Pin<Box<dyn futures_util::Future<Output = Result<Vec<Account>, String>> + Send>>
+    {
+        // The accounts are part of the notifications we already loaded
+        Box::pin(async move { Ok(Vec::new()) })
+    }
+
+    fn process_new_data(
+        &self,
+        _updates: &[Account],
+        _direction: crate::environment::types::TimelineDirection,
+        _is_reload: bool,
+    ) -> bool {
+        false
+    }
+
+    fn data(
+        &self,
+        _direction: crate::environment::types::TimelineDirection,
+    ) -> Vec<AccountViewModel> {
+        self.environment
+            .storage
+            .with(|storage| {
+                storage
+                    .notification_groups
+                    .iter()
+                    .find(|g| g.id == self.group)
+                    .map(|g| g.accounts.clone())
+            })
+            .unwrap_or_default()
+    }
+
+    fn scroll_to_item(&self, _updates: &[megalodon::entities::Status]) -> Option<AccountId> {
+        None
+    }
+}
+
+/// The accounts found by a search
+#[derive(Debug, Clone)]
+pub struct SearchProfilesProvider {
+    id: String,
+    term: String,
+    environment: Environment,
+}
+
+impl SearchProfilesProvider {
+    pub fn new(term: String, environment: &Environment) -> Self {
+        Self {
+            id: format!("search-{term}"),
+            term,
+            environment: environment.clone(),
+        }
+    }
+}
+
+impl TimelineProvider for SearchProfilesProvider {
+    type Id = AccountId;
+    type Element = Account;
+    type ViewModel = AccountViewModel;
+    fn should_auto_reload(&self) -> bool {
+        false
+    }
+
+    fn identifier(&self) -> &str {
+        &self.id
+    }
+
+    fn reset(&self) {}
+
+    fn forced_direction(&self) -> Option<crate::environment::types::TimelineDirection> {
+        None
+    }
+
+    fn request_data(
+        &self,
+        _after: Option<AccountId>,
+    ) -> std::pin::Pin<Box<dyn futures_util::Future<Output = Result<Vec<Account>, String>> + Send>>
+    {
+        // The accounts are loaded by the search
+        Box::pin(async move { Ok(Vec::new()) })
+    }
+
+    fn process_new_data(
+        &self,
+        _updates: &[Account],
+        _direction: crate::environment::types::TimelineDirection,
+        _is_reload: bool,
+    ) -> bool {
+        false
+    }
+
+    fn data(
+        &self,
+        _direction: crate::environment::types::TimelineDirection,
+    ) -> Vec<AccountViewModel> {
+        self.environment
+            .storage
+            .with(|storage| {
+                storage
+                    .search_results
+                    .as_ref()
+                    .filter(|r| r.term == self.term)
+                    .map(|r| r.accounts.clone())
+            })
+            .unwrap_or_default()
+    }
+
+    fn scroll_to_item(&self, _updates: &[megalodon::entities::Status]) -> Option<AccountId> {
+        None
+    }
+}
+
 use std::{
     collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
@@ -20,12 +20,15 @@ pub struct ProfilesState {
     pub error: Option<String>,
     pub can_load_more: bool,
     pub next_profile_id: Option<String>,
+    /// Show accept / reject buttons instead of opening the profile
+    pub is_follow_requests: bool,
     // need this to open more providers from within. not optimal
     pub environment: Option<Environment>,
 }
 
 impl ProfilesState {
     pub fn new(provider: super::AnyProfilesTimelineProvider, is_nested: bool) -> Self {
+        let is_follow_requests = provider.identifier() == super::FOLLOW_REQUESTS_ID;
         Self {
             is_nested,
             ui_settings: UiConfig::default(),
@@ -36,6 +39,7 @@ impl ProfilesState {
             error: None,
             can_load_more: true,
             next_profile_id: None,
+            is_follow_requests,
             environment: None,
         }
     }
@@ -63,6 +67,10 @@ pub enum ProfilesAction {
     Select(Option<AccountViewModel>),
     AppEvent(AppEvent),
     TimelineAction(crate::PublicAction),
+    /// Accept (`true`) or reject a follow request
+    AnswerFollowRequest(AccountViewModel, bool),
+    AnsweredFollowRequest(AccountId, Result<(), String>),
+    ClearError,
 }
 
 impl std::fmt::Debug for ProfilesAction {
@@ -77,6 +85,17 @@ impl std::fmt::Debug for ProfilesAction {
             Self::Select(arg0) => f.debug_tuple("Select").field(arg0).finish(),
             Self::AppEvent(arg0) => f.debug_tuple("AppEvent").field(arg0).finish(),
             Self::TimelineAction(arg0) => f.debug_tuple("TimelineAction").field(arg0).finish(),
+            Self::AnswerFollowRequest(arg0, arg1) => f
+                .debug_tuple("AnswerFollowRequest")
+                .field(arg0)
+                .field(arg1)
+                .finish(),
+            Self::AnsweredFollowRequest(arg0, arg1) => f
+                .debug_tuple("AnsweredFollowRequest")
+                .field(arg0)
+                .field(arg1)
+                .finish(),
+            Self::ClearError => write!(f, "ClearError"),
         }
     }
 }
@@ -151,6 +170,24 @@ pub fn reduce<'a>(
         ProfilesAction::TimelineAction(p) => {
             context.send_parent(ProfilesDelegate::TimelineAction(p))
         }
+        ProfilesAction::AnswerFollowRequest(account, accept) => {
+            let model = environment.model.clone();
+            let id = account.id.0.clone();
+            return Effect::future(
+                async move { model.answer_follow_request(id, accept).await },
+                move |result| ProfilesAction::AnsweredFollowRequest(account.id.clone(), result),
+            );
+        }
+        ProfilesAction::AnsweredFollowRequest(id, result) => match result {
+            Ok(_) => {
+                environment
+                    .storage
+                    .with_mutation(|mut storage| storage.remove_follow_request(&id));
+                state.profiles = state.provider.data(TimelineDirection::NewestTop);
+            }
+            Err(e) => state.error = Some(e),
+        },
+        ProfilesAction::ClearError => state.error = None,
         ProfilesAction::AppEvent(a) => {
             use crate::environment::types::MainMenuEvent;
             match a {
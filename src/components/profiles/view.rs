@@ -24,11 +24,24 @@ pub fn ProfilesView<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
             VStack {
                 div {
                     class: "scroll {nested_paddings}",
+                    { store.error.as_ref().map(|error| rsx!(ErrorBox {
+                        content: error.clone(),
+                        onclick: move |_| store.send(ProfilesAction::ClearError)
+                    }))}
+
                     { store.is_loading.then(|| rsx!(div {
                         class: "hstack p-2 m-2 grow align-self-center",
                         Spinner {}
                     }))}
 
+                    (store.is_follow_requests && store.profiles.is_empty() && !store.is_loading).then(|| rsx!(div {
+                        class: "p-2",
+                        Label {
+                            style: TextStyle::Tertiary,
+                            loc!("No follow requests")
+                        }
+                    }))
+
                     for profile in &store.profiles {
                         ProfileView {
                             key: "a{profile.id.0}",
@@ -74,13 +87,45 @@ pub fn ProfileView<'a>(
                 *profile,
                 |account| ProfileState::new(account, true),
             ),
-            button {
+            store.is_follow_requests.then(|| rsx!(FollowRequestButtons {
+                store: store,
+                profile: profile,
+            }))
+            (!store.is_follow_requests).then(|| rsx!(button {
                 class:  "button highlighted ms-auto",
                 title: loc!("Open Profile"),
                 onclick: move |_| {
                     store.send(ProfilesAction::Select(Some((*profile).clone())));
                 },
                 loc!("Open")
+            }))
+        }
+    }
+}
+
+#[inline_props]
+fn FollowRequestButtons<'a>(
+    cx: Scope<'a>,
+    store: &'a ViewStore<'a>,
+    profile: &'a AccountViewModel,
+) -> Element<'a> {
+    render! {
+        HStack { class: "ms-auto gap-2",
+            button {
+                class: "button",
+                title: loc!("Reject Follow Request"),
+                onclick: move |_| {
+                    store.send(ProfilesAction::AnswerFollowRequest((*profile).clone(), false));
+                },
+                loc!("Reject")
+            }
+            button {
+                class: "button highlighted",
+                title: loc!("Accept Follow Request"),
+                onclick: move |_| {
+                    store.send(ProfilesAction::AnswerFollowRequest((*profile).clone(), true));
+                },
+                loc!("Accept")
             }
         }
     }
@@ -0,0 +1,37 @@
+mod reducer;
+mod view;
+
+pub use reducer::{reduce, ReportAction, ReportState, ViewStore};
+pub use view::ReportComponent;
+
+pub struct ReportReducer;
+use navicula::reducer::Reducer;
+
+impl Reducer for ReportReducer {
+    type Message = ();
+
+    type DelegateMessage = ();
+
+    type Action = reducer::ReportAction;
+
+    type State = reducer::ReportState;
+
+    type Environment = crate::environment::Environment;
+
+    fn reduce<'a, 'b>(
+        context: &'a impl navicula::types::MessageContext<
+            Self::Action,
+            Self::DelegateMessage,
+            Self::Message,
+        >,
+        action: Self::Action,
+        state: &'a mut Self::State,
+        environment: &'a Self::Environment,
+    ) -> navicula::effect::Effect<'b, Self::Action> {
+        reducer::reduce(context, action, state, environment)
+    }
+
+    fn initial_action() -> Option<Self::Action> {
+        Some(ReportAction::Load)
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::HashSet;
+
+use crate::environment::model::{InstanceRule, Status};
+use crate::environment::types::{AccountModeration, ReportCategory, ReportInput};
+use crate::environment::Environment;
+use crate::view_model::{AccountViewModel, StatusId, StatusViewModel};
+use navicula::Effect;
+
+pub type ViewStore<'a> = navicula::ViewStore<'a, super::ReportReducer>;
+
+#[derive(Clone, Debug)]
+pub enum ReportAction {
+    Load,
+    Loaded(Result<(Vec<Status>, Vec<InstanceRule>), String>),
+    ToggleStatus(StatusId),
+    SelectCategory(ReportCategory),
+    ToggleRule(String),
+    UpdateComment(String),
+    UpdateForward(bool),
+    SelectModeration(Option<AccountModeration>),
+    Send,
+    Sent(Result<(), String>),
+    Moderated(Result<(), String>),
+    Close,
+    ClearError,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct ReportState {
+    pub account: AccountViewModel,
+    /// Recent posts of the account that can be attached to the report
+    pub statuses: Vec<StatusViewModel>,
+    pub selected: HashSet<StatusId>,
+    pub category: ReportCategory,
+    pub rules: Vec<InstanceRule>,
+    pub selected_rules: HashSet<String>,
+    pub comment: String,
+    pub forward: bool,
+    /// Mute or block the account once the report was sent
+    pub moderation: Option<AccountModeration>,
+    pub is_loading: bool,
+    pub is_sending: bool,
+    /// The report was sent, only muting or blocking is left
+    pub is_reported: bool,
+    pub is_done: bool,
+    pub error: Option<String>,
+}
+
+impl ReportState {
+    pub fn new(account: AccountViewModel, status: Option<StatusViewModel>) -> Self {
+        let mut selected = HashSet::new();
+        let mut statuses = Vec::new();
+        if let Some(status) = status {
+            selected.insert(status.id.clone());
+            statuses.push(status);
+        }
+        Self {
+            account,
+            statuses,
+            selected,
+            ..Default::default()
+        }
+    }
+
+    /// Reports for remote accounts can be forwarded to their instance
+    pub fn is_remote(&self) -> bool {
+        self.account.domain().is_some()
+    }
+
+    /// Rule violations can only be reported if the instance has rules
+    pub fn categories(&self) -> Vec<ReportCategory> {
+        ReportCategory::all()
+            .into_iter()
+            .filter(|c| *c != ReportCategory::Violation || !self.rules.is_empty())
+            .collect()
+    }
+
+    pub fn can_send(&self) -> bool {
+        !self.is_sending
+            && !self.is_done
+            && (self.is_reported
+                || self.category != ReportCategory::Violation
+                || !self.selected_rules.is_empty())
+    }
+}
+
+pub fn reduce<'a>(
+    context: &'a impl navicula::types::MessageContext<ReportAction, (), ()>,
+    action: ReportAction,
+    state: &'a mut ReportState,
+    environment: &'a Environment,
+) -> Effect<'static, ReportAction> {
+    log::trace!("{action:?}");
+    let window = context.window();
+    let model = environment.model.clone();
+    match action {
+        ReportAction::Load => {
+            state.is_loading = true;
+            let id = state.account.id.0.clone();
+            return Effect::future(
+                async move {
+                    futures_util::future::try_join(
+                        model.user_timeline(id, None, None, Some(20)),
+                        model.instance_rules(),
+                    )
+                    .await
+                },
+                ReportAction::Loaded,
+            );
+        }
+        ReportAction::Loaded(result) => {
+            state.is_loading = false;
+            match result {
+                Ok((statuses, rules)) => {
+                    // Keep the reported status at the top, even if it is older
+                    for status in statuses.iter().filter(|s| s.reblog.is_none()) {
+                        let status = StatusViewModel::new(status);
+                        if !state.statuses.iter().any(|s| s.id == status.id) {
+                            state.statuses.push(status);
+                        }
+                    }
+                    state.rules = rules;
+                    if !state.categories().contains(&state.category) {
+                        state.category = ReportCategory::default();
+                    }
+                }
+                Err(e) => state.error = Some(e),
+            }
+        }
+        ReportAction::ToggleStatus(id) => {
+            if !state.selected.remove(&id) {
+                state.selected.insert(id);
+            }
+        }
+        ReportAction::SelectCategory(category) => state.category = category,
+        ReportAction::ToggleRule(id) => {
+            if !state.selected_rules.remove(&id) {
+                state.selected_rules.insert(id);
+            }
+        }
+        ReportAction::UpdateComment(comment) => state.comment = comment,
+        ReportAction::UpdateForward(forward) => state.forward = forward,
+        ReportAction::SelectModeration(moderation) => state.moderation = moderation,
+        ReportAction::Send => {
+            if !state.can_send() {
+                return Effect::NONE;
+            }
+            state.is_sending = true;
+            // Only the moderation is tried again, the report is not sent twice
+            if state.is_reported {
+                return moderate(state, environment);
+            }
+            let report = ReportInput {
+                account_id: state.account.id.0.clone(),
+                status_ids: state
+                    .statuses
+                    .iter()
+                    .filter(|s| state.selected.contains(&s.id))
+                    .map(|s| s.id.0.clone())
+                    .collect(),
+                comment: state.comment.clone(),
+                forward: state.forward && state.is_remote(),
+                category: state.category,
+                rule_ids: match state.category {
+                    ReportCategory::Violation => state.selected_rules.iter().cloned().collect(),
+                    _ => Vec::new(),
+                },
+            };
+            return Effect::future(
+                async move { model.report(report).await },
+                ReportAction::Sent,
+            );
+        }
+        ReportAction::Sent(result) => match result {
+            Ok(_) => {
+                state.is_reported = true;
+                return moderate(state, environment);
+            }
+            Err(e) => {
+                state.is_sending = false;
+                state.error = Some(e);
+            }
+        },
+        ReportAction::Moderated(result) => {
+            state.is_sending = false;
+            match result {
+                Ok(_) => {
+                    state.is_done = true;
+                    if let Some(moderation) = state.moderation {
+                        let account = state.account.clone();
+                        environment
+                            .storage
+                            .with_mutation(|mut s| s.apply_moderation(&account, moderation));
+                    }
+                    return Effect::timer(
+                        std::time::Duration::from_secs(2),
+                        ReportAction::Close,
+                        "close-report",
+                    );
+                }
+                Err(e) => {
+                    let message = crate::loc!(
+                        "The report was sent, but the account could not be muted or blocked:"
+                    );
+                    state.error = Some(format!("{message} {e}"));
+                }
+            }
+        }
+        ReportAction::Close => window.close(),
+        ReportAction::ClearError => state.error = None,
+    }
+    Effect::NONE
+}
+
+/// Mute or block the account once it was reported
+fn moderate(state: &ReportState, environment: &Environment) -> Effect<'static, ReportAction> {
+    let Some(moderation) = state.moderation else {
+        return Effect::action(ReportAction::Moderated(Ok(())));
+    };
+    let model = environment.model.clone();
+    let id = state.account.id.0.clone();
+    let domain = state.account.domain().map(|e| e.to_string());
+    Effect::future(
+        async move { model.moderate(id, domain, moderation).await.map(|_| ()) },
+        ReportAction::Moderated,
+    )
+}
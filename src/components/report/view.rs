@@ -0,0 +1,224 @@
+use dioxus::prelude::*;
+
+use super::reducer::{ReportAction, ViewStore};
+use crate::environment::types::{AccountModeration, ReportCategory};
+use crate::loc;
+use crate::view_model::StatusViewModel;
+use crate::widgets::*;
+
+#[inline_props]
+pub fn ReportComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
+    let account = &store.account;
+    let placeholder = loc!("Additional comments for the moderators");
+    let can_send = store.can_send();
+    // Only muting or blocking is tried again once the report was sent
+    let send_title = match store.is_reported {
+        true => loc!("Try Again"),
+        false => loc!("Report"),
+    };
+    let moderations = [
+        (loc!("Don't mute or block"), None),
+        (
+            loc!("Also mute this account"),
+            Some(AccountModeration::Mute {
+                duration: None,
+                notifications: true,
+            }),
+        ),
+        (loc!("Also block this account"), Some(AccountModeration::Block)),
+    ];
+    render! {
+        div {
+            class: "report-component",
+            VStack {
+                class: "gap-3 p-3",
+                HStack { class: "align-items-center gap-2",
+                    img {
+                        class: "image-author-small",
+                        src: "{account.image}",
+                        alt: "{account.username}",
+                    }
+                    Label {
+                        class: "me-auto",
+                        "{account.acct}"
+                    }
+                }
+
+                { store.error.as_ref().map(|error| rsx!(ErrorBox {
+                    content: error.clone(),
+                    onclick: move |_| store.send(ReportAction::ClearError)
+                }))}
+
+                if store.is_done {
+                    rsx!(Label {
+                        style: TextStyle::Secondary,
+                        loc!("Thanks, the report was sent to the moderators.")
+                    })
+                } else {
+                    rsx! {
+                        HStack {
+                            class: "justify-content-between align-items-center",
+                            Label {
+                                style: TextStyle::Secondary,
+                                loc!("Reason")
+                            }
+                            select {
+                                onchange: move |evt| {
+                                    let Some(category) = evt.value.parse::<usize>().ok()
+                                        .and_then(|i| store.categories().get(i).copied()) else {
+                                        return
+                                    };
+                                    store.send(ReportAction::SelectCategory(category));
+                                },
+                                for (index, category) in store.categories().into_iter().enumerate() {
+                                    option {
+                                        value: "{index}",
+                                        selected: "{store.category == category}",
+                                        "{category}"
+                                    }
+                                }
+                            }
+                        }
+
+                        (store.category == ReportCategory::Violation).then(|| rsx!(VStack {
+                            class: "gap-1",
+                            for rule in &store.rules {
+                                CheckboxRow {
+                                    key: "{rule.id}",
+                                    id: "rule-{rule.id}",
+                                    title: &rule.text,
+                                    checked: store.selected_rules.contains(&rule.id),
+                                    onchange: move |_| store.send(ReportAction::ToggleRule(rule.id.clone())),
+                                }
+                            }
+                        }))
+
+                        Label {
+                            style: TextStyle::Secondary,
+                            loc!("Posts to include")
+                        }
+                        div {
+                            class: "report-statuses scroll",
+                            { store.is_loading.then(|| rsx!(div {
+                                class: "hstack p-2 m-2 grow align-self-center",
+                                Spinner {}
+                            }))}
+                            for status in &store.statuses {
+                                ReportStatusRow {
+                                    key: "{status.id.0}",
+                                    status: status,
+                                    checked: store.selected.contains(&status.id),
+                                    onchange: move |_| store.send(ReportAction::ToggleStatus(status.id.clone())),
+                                }
+                            }
+                        }
+
+                        textarea {
+                            class: "report-comment",
+                            placeholder: "{placeholder}",
+                            oninput: move |evt| store.send(ReportAction::UpdateComment(evt.value.clone())),
+                            "{store.comment}"
+                        }
+
+                        store.is_remote().then(|| rsx!(CheckboxRow {
+                            id: "report-forward",
+                            title: loc!("Also send the report to the server of this account"),
+                            checked: store.forward,
+                            onchange: move |v| store.send(ReportAction::UpdateForward(v)),
+                        }))
+
+                        select {
+                            onchange: move |evt| {
+                                let Some((_, moderation)) = evt.value.parse::<usize>().ok()
+                                    .and_then(|i| moderations.get(i)) else {
+                                    return
+                                };
+                                store.send(ReportAction::SelectModeration(*moderation));
+                            },
+                            for (index, (title, moderation)) in moderations.iter().enumerate() {
+                                option {
+                                    value: "{index}",
+                                    selected: "{store.moderation == *moderation}",
+                                    "{title}"
+                                }
+                            }
+                        }
+
+                        HStack { class: "align-items-center gap-2",
+                            span { class: "me-auto" }
+                            store.is_sending.then(|| rsx!(Spinner {}))
+                            button {
+                                class: "button",
+                                onclick: move |_| store.send(ReportAction::Close),
+                                loc!("Cancel")
+                            }
+                            button {
+                                class: "button",
+                                disabled: "{!can_send}",
+                                onclick: move |_| store.send(ReportAction::Send),
+                                "{send_title}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn ReportStatusRow<'a>(
+    cx: Scope<'a>,
+    status: &'a StatusViewModel,
+    checked: bool,
+    onchange: EventHandler<'a, bool>,
+) -> Element<'a> {
+    let id = format!("report-status-{}", status.id.0);
+    render! {
+        HStack {
+            class: "report-status align-items-center gap-2 p-2",
+            input {
+                r#type: "checkbox",
+                id: "{id}",
+                checked: "{checked}",
+                oninput: move |evt| onchange.call(evt.value.parse().unwrap_or_default()),
+            }
+            label {
+                r#for: "{id}",
+                Paragraph {
+                    "{status.text}"
+                }
+                Label {
+                    style: TextStyle::Tertiary,
+                    "{status.created_full}"
+                }
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn CheckboxRow<'a>(
+    cx: Scope<'a>,
+    id: &'a str,
+    title: &'a str,
+    checked: bool,
+    onchange: EventHandler<'a, bool>,
+) -> Element<'a> {
+    render! {
+        HStack {
+            class: "align-items-center gap-2",
+            input {
+                r#type: "checkbox",
+                id: "{id}",
+                checked: "{checked}",
+                oninput: move |evt| onchange.call(evt.value.parse().unwrap_or_default()),
+            }
+            label {
+                class: "label-secondary",
+                r#for: "{id}",
+                "{title}"
+            }
+        }
+    }
+}
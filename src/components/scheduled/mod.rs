@@ -0,0 +1,37 @@
+mod reducer;
+mod view;
+
+pub use reducer::{reduce, ScheduledAction, ScheduledState, ViewStore};
+pub use view::ScheduledComponent;
+
+pub struct ScheduledReducer;
+use navicula::reducer::Reducer;
+
+impl Reducer for ScheduledReducer {
+    type Message = ();
+
+    type DelegateMessage = ();
+
+    type Action = reducer::ScheduledAction;
+
+    type State = reducer::ScheduledState;
+
+    type Environment = crate::environment::Environment;
+
+    fn reduce<'a, 'b>(
+        context: &'a impl navicula::types::MessageContext<
+            Self::Action,
+            Self::DelegateMessage,
+            Self::Message,
+        >,
+        action: Self::Action,
+        state: &'a mut Self::State,
+        environment: &'a Self::Environment,
+    ) -> navicula::effect::Effect<'b, Self::Action> {
+        reducer::reduce(context, action, state, environment)
+    }
+
+    fn initial_action() -> Option<Self::Action> {
+        Some(ScheduledAction::Load)
+    }
+}
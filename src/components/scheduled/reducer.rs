@@ -0,0 +1,98 @@
+use crate::environment::model::ScheduledStatus;
+use crate::environment::Environment;
+use crate::view_model::ScheduledStatusViewModel;
+use navicula::Effect;
+
+pub type ViewStore<'a> = navicula::ViewStore<'a, super::ScheduledReducer>;
+
+#[derive(Clone, Debug)]
+pub enum ScheduledAction {
+    Load,
+    Loaded(Result<Vec<ScheduledStatus>, String>),
+    Reschedule(String, String),
+    Rescheduled(Result<ScheduledStatus, String>),
+    Cancel(String),
+    Cancelled(String, Result<(), String>),
+    ClearError,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct ScheduledState {
+    pub statuses: Vec<ScheduledStatusViewModel>,
+    pub is_loading: bool,
+    pub error: Option<String>,
+}
+
+pub fn reduce<'a>(
+    _context: &'a impl navicula::types::MessageContext<ScheduledAction, (), ()>,
+    action: ScheduledAction,
+    state: &'a mut ScheduledState,
+    environment: &'a Environment,
+) -> Effect<'static, ScheduledAction> {
+    log::trace!("{action:?}");
+    match action {
+        ScheduledAction::Load => {
+            state.is_loading = true;
+            let model = environment.model.clone();
+            return Effect::future(
+                async move { model.scheduled_statuses().await },
+                ScheduledAction::Loaded,
+            );
+        }
+        ScheduledAction::Loaded(result) => {
+            state.is_loading = false;
+            match result {
+                Ok(statuses) => {
+                    let mut statuses: Vec<_> =
+                        statuses.iter().map(ScheduledStatusViewModel::new).collect();
+                    statuses.sort_by_key(|e| e.scheduled_at);
+                    state.statuses = statuses;
+                }
+                Err(e) => state.error = Some(e),
+            }
+        }
+        ScheduledAction::Reschedule(id, value) => {
+            let Some(date) = crate::helper::parse_local_datetime(&value) else {
+                state.error = Some(format!("Invalid Date: {value:?}"));
+                return Effect::NONE
+            };
+            state.is_loading = true;
+            let model = environment.model.clone();
+            return Effect::future(
+                async move { model.reschedule_status(id, date).await },
+                ScheduledAction::Rescheduled,
+            );
+        }
+        ScheduledAction::Rescheduled(result) => {
+            state.is_loading = false;
+            match result {
+                Ok(status) => {
+                    let updated = ScheduledStatusViewModel::new(&status);
+                    if let Some(entry) = state.statuses.iter_mut().find(|e| e.id == updated.id) {
+                        *entry = updated;
+                    }
+                    state.statuses.sort_by_key(|e| e.scheduled_at);
+                }
+                Err(e) => state.error = Some(e),
+            }
+        }
+        ScheduledAction::Cancel(id) => {
+            state.is_loading = true;
+            let model = environment.model.clone();
+            let cloned_id = id.clone();
+            return Effect::future(
+                async move { model.cancel_scheduled_status(cloned_id).await },
+                move |result| ScheduledAction::Cancelled(id, result),
+            );
+        }
+        ScheduledAction::Cancelled(id, result) => {
+            state.is_loading = false;
+            match result {
+                Ok(_) => state.statuses.retain(|e| e.id != id),
+                Err(e) => state.error = Some(e),
+            }
+        }
+        ScheduledAction::ClearError => state.error = None,
+    }
+    Effect::NONE
+}
@@ -0,0 +1,95 @@
+use dioxus::prelude::*;
+
+use super::reducer::{ScheduledAction, ViewStore};
+use crate::loc;
+use crate::view_model::ScheduledStatusViewModel;
+use crate::widgets::*;
+
+#[inline_props]
+pub fn ScheduledComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
+    let is_empty = store.statuses.is_empty() && !store.is_loading;
+    render! {
+        div {
+            class: "scheduled-list-component",
+            VStack {
+                div {
+                    class: "scroll content-cell-container",
+                    { store.error.as_ref().map(|error| rsx!(ErrorBox {
+                        content: error.clone(),
+                        onclick: move |_| store.send(ScheduledAction::ClearError)
+                    }))}
+
+                    { store.is_loading.then(|| rsx!(div {
+                        class: "hstack p-2 m-2 grow align-self-center",
+                        Spinner {}
+                    }))}
+
+                    is_empty.then(|| rsx!(div {
+                        class: "p-3",
+                        Label {
+                            style: TextStyle::Secondary,
+                            loc!("No Scheduled Posts")
+                        }
+                    }))
+
+                    for status in &store.statuses {
+                        ScheduledStatusView {
+                            key: "{status.id}",
+                            store: store,
+                            status: status,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn ScheduledStatusView<'a>(
+    cx: Scope<'a>,
+    store: &'a ViewStore<'a>,
+    status: &'a ScheduledStatusViewModel,
+) -> Element<'a> {
+    let value = use_state(cx, || {
+        crate::helper::format_local_datetime(&status.scheduled_at)
+    });
+    let media = match status.media_count {
+        0 => String::new(),
+        count => format!(" · {count} Attachments"),
+    };
+    render! {
+        VStack { class: "scheduled-status p-2 gap-2",
+            Paragraph {
+                "{status.text}"
+            }
+            Label {
+                style: TextStyle::Tertiary,
+                "{status.scheduled_full}{media}"
+            }
+            HStack { class: "align-items-center gap-2",
+                input {
+                    r#type: "datetime-local",
+                    value: "{value}",
+                    onchange: move |evt| value.set(evt.value.clone()),
+                }
+                button {
+                    class: "button",
+                    disabled: "{store.is_loading}",
+                    onclick: move |_| {
+                        let id = status.id.clone();
+                        store.send(ScheduledAction::Reschedule(id, value.get().clone()))
+                    },
+                    loc!("Reschedule")
+                }
+                span { class: "me-auto" }
+                button {
+                    class: "button",
+                    disabled: "{store.is_loading}",
+                    onclick: move |_| store.send(ScheduledAction::Cancel(status.id.clone())),
+                    loc!("Cancel Post")
+                }
+            }
+        }
+    }
+}
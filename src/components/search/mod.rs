@@ -0,0 +1,37 @@
+mod reducer;
+mod view;
+
+pub use reducer::{reduce, SearchAction, SearchState, ViewStore};
+pub use view::SearchComponent;
+
+pub struct SearchReducer;
+use navicula::reducer::Reducer;
+
+impl Reducer for SearchReducer {
+    type Message = crate::environment::types::AppEvent;
+
+    type DelegateMessage = crate::PublicAction;
+
+    type Action = reducer::SearchAction;
+
+    type State = reducer::SearchState;
+
+    type Environment = crate::environment::Environment;
+
+    fn reduce<'a, 'b>(
+        context: &'a impl navicula::types::MessageContext<
+            Self::Action,
+            Self::DelegateMessage,
+            Self::Message,
+        >,
+        action: Self::Action,
+        state: &'a mut Self::State,
+        environment: &'a Self::Environment,
+    ) -> navicula::effect::Effect<'b, Self::Action> {
+        reducer::reduce(context, action, state, environment)
+    }
+
+    fn initial_action() -> Option<Self::Action> {
+        Some(SearchAction::Initial)
+    }
+}
@@ -0,0 +1,173 @@
+use crate::environment::model::SearchResults;
+use crate::environment::types::{AppEvent, MainMenuEvent};
+use crate::environment::Environment;
+use crate::view_model::{AccountViewModel, SearchResultsViewModel, StatusId, TagViewModel};
+use crate::PublicAction;
+use navicula::Effect;
+
+pub type ViewStore<'a> = navicula::ViewStore<'a, super::SearchReducer>;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum SearchSection {
+    #[default]
+    Accounts,
+    Statuses,
+    Hashtags,
+}
+
+/// What a pasted url resolved to
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Resolved {
+    Account(AccountViewModel),
+    Status(StatusId),
+}
+
+#[derive(Clone, Debug)]
+pub enum SearchAction {
+    Initial,
+    DataChanged,
+    UpdateTerm(String),
+    /// Search for a term (or resolve a pasted url)
+    Open(String),
+    Search(String),
+    Results(String, Result<SearchResults, String>),
+    SelectSection(SearchSection),
+    OpenTag(String),
+    AppEvent(AppEvent),
+    Public(PublicAction),
+    ClearError,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SearchState {
+    pub account: AccountViewModel,
+    /// The contents of the search field
+    pub input: String,
+    /// The term that is currently searched
+    pub requested: Option<String>,
+    /// The term of the current results
+    pub term: Option<String>,
+    pub section: SearchSection,
+    pub resolved: Option<Resolved>,
+    pub account_count: usize,
+    pub status_count: usize,
+    pub hashtags: Vec<TagViewModel>,
+    pub is_searching: bool,
+    pub error: Option<String>,
+}
+
+impl SearchState {
+    pub fn new(account: AccountViewModel) -> Self {
+        Self {
+            account,
+            ..Default::default()
+        }
+    }
+}
+
+pub fn reduce<'a>(
+    context: &'a impl navicula::types::MessageContext<SearchAction, PublicAction, AppEvent>,
+    action: SearchAction,
+    state: &'a mut SearchState,
+    environment: &'a Environment,
+) -> Effect<'static, SearchAction> {
+    log::trace!("{action:?}");
+    match action {
+        SearchAction::Initial => {
+            return Effect::merge2(
+                environment
+                    .storage
+                    .subscribe("search_reducer", context, |_| SearchAction::DataChanged),
+                Effect::action(SearchAction::DataChanged),
+            );
+        }
+        SearchAction::DataChanged => {
+            // A search can also be requested from the sidebar
+            let requested = environment.storage.with(|data| data.search_term.clone());
+            let Some(term) = requested.filter(|t| state.requested.as_ref() != Some(t)) else {
+                return Effect::NONE
+            };
+            state.input = term.clone();
+            return Effect::action(SearchAction::Search(term));
+        }
+        SearchAction::UpdateTerm(term) => state.input = term,
+        SearchAction::Open(term) => {
+            let term = term.trim().to_string();
+            if term.is_empty() {
+                return Effect::NONE;
+            }
+            environment
+                .storage
+                .with_mutation(|mut storage| storage.search_term = Some(term.clone()));
+            // Searching the same term again should still reload
+            if state.requested.as_ref() == Some(&term) {
+                return Effect::action(SearchAction::Search(term));
+            }
+        }
+        SearchAction::Search(term) => {
+            state.requested = Some(term.clone());
+            state.is_searching = true;
+            let model = environment.model.clone();
+            let cloned = term.clone();
+            return Effect::future(
+                async move { model.search(cloned, true).await },
+                move |result| SearchAction::Results(term, result),
+            );
+        }
+        SearchAction::Results(term, result) => {
+            // Ignore results of outdated searches
+            if state.requested.as_ref() != Some(&term) {
+                return Effect::NONE;
+            }
+            state.is_searching = false;
+            let results = match result {
+                Ok(n) => SearchResultsViewModel::new(term.clone(), &n),
+                Err(e) => {
+                    state.error = Some(e);
+                    return Effect::NONE;
+                }
+            };
+            state.resolved = resolve_url(&term, &results);
+            state.section = match state.resolved {
+                Some(Resolved::Account(_)) => SearchSection::Accounts,
+                Some(Resolved::Status(_)) => SearchSection::Statuses,
+                None if !results.accounts.is_empty() => SearchSection::Accounts,
+                None if !results.statuses.is_empty() => SearchSection::Statuses,
+                None if !results.hashtags.is_empty() => SearchSection::Hashtags,
+                None => state.section,
+            };
+            state.account_count = results.accounts.len();
+            state.status_count = results.statuses.len();
+            state.hashtags = results.hashtags.clone();
+            state.term = Some(term);
+            environment
+                .storage
+                .with_mutation(|mut storage| storage.search_results = Some(results));
+        }
+        SearchAction::SelectSection(section) => state.section = section,
+        SearchAction::OpenTag(name) => context.send_parent(PublicAction::OpenTag(name)),
+        SearchAction::AppEvent(event) => {
+            let is_reload = matches!(event, AppEvent::MenuEvent(MainMenuEvent::Reload));
+            context.send_children(event);
+            if let Some(term) = state.requested.clone().filter(|_| is_reload) {
+                return Effect::action(SearchAction::Search(term));
+            }
+        }
+        SearchAction::Public(action) => context.send_parent(action),
+        SearchAction::ClearError => state.error = None,
+    }
+    Effect::NONE
+}
+
+/// If the search term was a (pasted) url of a single account or status,
+/// we directly open it
+fn resolve_url(term: &str, results: &SearchResultsViewModel) -> Option<Resolved> {
+    if !(term.starts_with("https://") || term.starts_with("http://")) {
+        return None;
+    }
+    match (results.accounts.as_slice(), results.statuses.as_slice()) {
+        (_, [status]) => Some(Resolved::Status(status.id.clone())),
+        ([account], []) => Some(Resolved::Account(account.clone())),
+        _ => None,
+    }
+}
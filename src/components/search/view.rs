@@ -0,0 +1,191 @@
+use dioxus::prelude::*;
+use navicula::reducer::ChildReducer;
+
+use super::reducer::{Resolved, SearchAction, SearchSection, ViewStore};
+use super::SearchReducer;
+use crate::components::component_stack::{RootTimelineKind, StackReducer};
+use crate::components::hashtags::TagCellComponent;
+use crate::loc;
+use crate::widgets::*;
+
+impl std::fmt::Display for SearchSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchSection::Accounts => write!(f, loc!("Accounts")),
+            SearchSection::Statuses => write!(f, loc!("Posts")),
+            SearchSection::Hashtags => write!(f, loc!("Hashtags")),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+struct SearchTab {
+    kind: SearchSection,
+    count: usize,
+    selected: bool,
+}
+
+impl SearchTab {
+    fn new(kind: SearchSection, count: usize, selected: &SearchSection) -> Self {
+        SearchTab {
+            kind,
+            count,
+            selected: &kind == selected,
+        }
+    }
+}
+
+impl Segment for SearchTab {
+    fn id(&self) -> u64 {
+        self.kind as u64
+    }
+
+    fn label(&self) -> String {
+        format!("{} {}", self.count, self.kind)
+    }
+
+    fn selected(&self) -> bool {
+        self.selected
+    }
+
+    fn dot(&self) -> bool {
+        false
+    }
+}
+
+#[inline_props]
+pub fn SearchComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
+    let items = vec![
+        SearchTab::new(SearchSection::Accounts, store.account_count, &store.section),
+        SearchTab::new(SearchSection::Statuses, store.status_count, &store.section),
+        SearchTab::new(
+            SearchSection::Hashtags,
+            store.hashtags.len(),
+            &store.section,
+        ),
+    ];
+    let placeholder = loc!("Search or paste a link");
+    render! {
+        div {
+            class: "search-component",
+            VStack { class: "search-header p-3 gap-2",
+                HStack { class: "gap-2 align-items-center",
+                    input {
+                        class: "grow",
+                        r#type: "text",
+                        value: "{store.input}",
+                        placeholder: "{placeholder}",
+                        autocomplete: "off",
+                        spellcheck: "false",
+                        oninput: move |evt| store.send(SearchAction::UpdateTerm(evt.value.clone())),
+                    }
+                    button {
+                        class: "button",
+                        disabled: "{store.input.trim().is_empty()}",
+                        onclick: move |_| store.send(SearchAction::Open(store.input.clone())),
+                        loc!("Search")
+                    }
+                }
+                { store.error.as_ref().map(|error| rsx!(ErrorBox {
+                    content: error.clone(),
+                    onclick: move |_| store.send(SearchAction::ClearError)
+                }))}
+                store.term.is_some().then(|| rsx!(SegmentedControl {
+                    items: items,
+                    onclick: move |i: SearchTab| store.send(SearchAction::SelectSection(i.kind))
+                }))
+            }
+
+            { store.is_searching.then(|| rsx!(div {
+                class: "hstack p-2 m-2 align-self-center",
+                Spinner {}
+            }))}
+
+            SearchResultsComponent {
+                store: store
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn SearchResultsComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
+    let Some(term) = store.term.as_ref() else {
+        return render!(div {})
+    };
+    let section = store.section;
+    let account = store.account.clone();
+    let account2 = store.account.clone();
+    let resolved = store.resolved.clone();
+    let resolved2 = store.resolved.clone();
+    use crate::components::component_stack::{Stack, State};
+    render! {
+        HideableView {
+            class: "search-results",
+            hidden: section != SearchSection::Accounts,
+            Stack {
+                store: store.host_with(cx, term, |t| {
+                    let state = State::new(RootTimelineKind::SearchAccounts(account.clone(), t));
+                    match resolved.clone() {
+                        Some(Resolved::Account(profile)) => state.with_profile(profile),
+                        _ => state,
+                    }
+                })
+            }
+        }
+        HideableView {
+            class: "search-results",
+            hidden: section != SearchSection::Statuses,
+            Stack {
+                store: store.host_with(cx, term, |t| {
+                    let state = State::new(RootTimelineKind::SearchStatuses(account2.clone(), t));
+                    match resolved2.clone() {
+                        Some(Resolved::Status(id)) => state.with_conversation(id),
+                        _ => state,
+                    }
+                })
+            }
+        }
+        HideableView {
+            class: "search-results scroll",
+            hidden: section != SearchSection::Hashtags,
+            VStack { class: "p-3 gap-2",
+                store.hashtags.is_empty().then(|| rsx!(Label {
+                    style: TextStyle::Secondary,
+                    loc!("No Hashtags found")
+                }))
+                for tag in &store.hashtags {
+                    TagCellComponent {
+                        key: "{tag.name}",
+                        tag: tag,
+                        selected: false,
+                        onclick: move |name| store.send(SearchAction::OpenTag(name))
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ChildReducer<SearchReducer> for StackReducer {
+    fn to_child(
+        message: <SearchReducer as navicula::Reducer>::Message,
+    ) -> Option<<Self as navicula::Reducer>::Action> {
+        Some(crate::components::component_stack::Action::AppEvent(
+            message,
+        ))
+    }
+
+    fn from_child(
+        message: <Self as navicula::Reducer>::DelegateMessage,
+    ) -> Option<<SearchReducer as navicula::Reducer>::Action> {
+        match message {
+            crate::components::component_stack::DelegateMessage::PublicAction(a) => {
+                Some(SearchAction::Public(a))
+            }
+            crate::components::component_stack::DelegateMessage::ConversationAction(a) => {
+                Some(SearchAction::Public(a))
+            }
+        }
+    }
+}
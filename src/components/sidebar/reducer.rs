@@ -1,15 +1,24 @@
 use std::collections::HashSet;
+use std::sync::Arc;
 use std::time::Duration;
 
 use im::{HashMap, Vector};
 use itertools::Itertools;
 
-use crate::components::loggedin::Action;
-use crate::environment::model::{Account, Notification, Status};
-use crate::environment::storage::UiTab;
-use crate::environment::types::{AppEvent, MainMenuEvent};
+use crate::components::loggedin::{
+    mark_conversations_read, subscribe_stream, Action, StreamSubscription,
+};
+use crate::environment::model::{
+    cmp_ids, Account, DirectConversation, Notification, ServerMarker, Status,
+};
+use crate::environment::storage::{ListRequest, UiTab};
+use crate::environment::types::{AppEvent, MainMenuEvent, StreamKind};
 use crate::environment::Environment;
-use crate::view_model::{AccountUpdateViewModel, AccountViewModel};
+use crate::view_model::{
+    AccountUpdateViewModel, AccountViewModel, DirectConversationViewModel,
+    NotificationGroupViewModel, NotificationKind,
+};
+use enumset::EnumSet;
 use navicula::{Debouncer, Effect};
 
 pub type ViewStore<'a> = navicula::ViewStore<'a, super::SidebarReducer>;
@@ -18,6 +27,8 @@ pub type ViewStore<'a> = navicula::ViewStore<'a, super::SidebarReducer>;
 pub enum SidebarDelegateAction {
     SelectAccount(AccountViewModel),
     SelectedNotifications(AccountViewModel),
+    SelectedNotificationGroup(String),
+    SelectedDirectConversation(String),
     Root(Action),
     AppEvent(AppEvent),
     SelectMore(MoreSelection),
@@ -36,6 +47,12 @@ pub enum SidebarAction {
     LoadNotifications,
     LoadedNotifications(Result<Vec<Notification>, String>),
     SelectedNotifications(AccountViewModel),
+    SelectedNotificationGroup(String),
+    ToggleNotificationKind(NotificationKind),
+
+    LoadMessages,
+    LoadedMessages(Result<(Vec<DirectConversation>, Option<String>), String>),
+    SelectedDirectConversation(String),
 
     AppEvent(AppEvent),
     Reload(bool),
@@ -44,18 +61,29 @@ pub enum SidebarAction {
 
     Search(String),
     SearchResults(Option<Result<Vec<Account>, String>>),
+    /// Search statuses, hashtags and accounts in the more section
+    SearchEverywhere,
 
     LoadLists,
     LoadedLists(Result<Vec<(String, String)>, String>),
     SelectList(String),
     LoadList(String),
     LoadListData(Result<Vec<Status>, String>, String),
+    /// Create (`None`) or edit a list in the more section
+    EditList(Option<String>),
+    DeleteList(String),
+    DeletedList(String, Result<(), String>),
+
+    LoadFollowRequests,
+    LoadedFollowRequests(Result<Vec<Account>, String>),
 
     // This is a bit hackish. Move needed types into delegate
     Root(Action),
 
     // Switch within the more section
     MoreSelection(MoreSelection),
+    // Show a more section that was selected from somewhere else
+    ShowMore(MoreSelection),
 }
 
 impl std::fmt::Debug for SidebarAction {
@@ -72,19 +100,43 @@ impl std::fmt::Debug for SidebarAction {
             Self::SelectedNotifications(arg0) => {
                 f.debug_tuple("SelectedNotifications").field(arg0).finish()
             }
+            Self::SelectedNotificationGroup(arg0) => f
+                .debug_tuple("SelectedNotificationGroup")
+                .field(arg0)
+                .finish(),
+            Self::ToggleNotificationKind(arg0) => {
+                f.debug_tuple("ToggleNotificationKind").field(arg0).finish()
+            }
+            Self::LoadMessages => write!(f, "LoadMessages"),
+            Self::LoadedMessages(_arg0) => f.debug_tuple("LoadedMessages").finish(),
+            Self::SelectedDirectConversation(arg0) => f
+                .debug_tuple("SelectedDirectConversation")
+                .field(arg0)
+                .finish(),
             Self::AppEvent(arg0) => f.debug_tuple("AppEvent").field(arg0).finish(),
             Self::Reload(arg0) => f.debug_tuple("Reload").field(arg0).finish(),
             Self::DataChanged => write!(f, "DataChanged"),
             Self::Root(arg0) => f.debug_tuple("Root").field(arg0).finish(),
             Self::Search(arg0) => f.debug_tuple("Search").field(arg0).finish(),
             Self::SearchResults(a) => write!(f, "SearchResults: {}", a.is_some()),
+            Self::SearchEverywhere => write!(f, "SearchEverywhere"),
             Self::FavoritesChanged => write!(f, "FavoritesChanged"),
             Self::LoadLists => write!(f, "LoadLists"),
             Self::LoadedLists(_) => write!(f, "LoadedLists"),
             Self::SelectList(entry) => f.debug_tuple("SelectList").field(entry).finish(),
             Self::LoadList(id) => f.debug_tuple("LoadList").field(id).finish(),
             Self::LoadListData(_entry, id) => f.debug_tuple("LoadListData").field(id).finish(),
+            Self::EditList(id) => f.debug_tuple("EditList").field(id).finish(),
+            Self::DeleteList(id) => f.debug_tuple("DeleteList").field(id).finish(),
+            Self::DeletedList(id, result) => f
+                .debug_tuple("DeletedList")
+                .field(id)
+                .field(result)
+                .finish(),
+            Self::LoadFollowRequests => write!(f, "LoadFollowRequests"),
+            Self::LoadedFollowRequests(_) => write!(f, "LoadedFollowRequests"),
             Self::MoreSelection(id) => f.debug_tuple("MoreSelection").field(id).finish(),
+            Self::ShowMore(id) => f.debug_tuple("ShowMore").field(id).finish(),
         }
     }
 }
@@ -98,11 +150,17 @@ pub enum MoreSelection {
     Local,
     Federated,
     Posts,
+    Search,
     Hashtags,
     Followers,
     Following,
+    FollowRequests,
     Bookmarks,
     Favorites,
+    Scheduled,
+    Moderation,
+    Filters,
+    Lists,
 }
 
 #[derive(Clone, Default)]
@@ -113,6 +171,10 @@ pub struct SidebarState {
     pub selected_account: Option<AccountViewModel>,
     pub selected_notifications: Option<AccountViewModel>,
     pub notification_accounts: Vector<AccountUpdateViewModel>,
+    pub notification_groups: Vector<NotificationGroupViewModel>,
+    pub selected_notification_group: Option<String>,
+    /// The notification kinds the user doesn't want to see
+    pub hidden_notification_kinds: EnumSet<NotificationKind>,
     pub user_account: Option<Account>,
     pub notification_posts_empty: bool,
     pub posts_empty: bool,
@@ -123,6 +185,17 @@ pub struct SidebarState {
     pub loading_content: bool,
     pub loading_notifications: bool,
     pub last_notification_id: Option<String>,
+    /// The newest notification, which is the read position once
+    /// the mentions are shown
+    pub newest_notification_id: Option<String>,
+
+    pub direct_conversations: Vector<DirectConversationViewModel>,
+    pub selected_direct_conversation: Option<String>,
+    pub has_new_messages: bool,
+    pub loading_messages: bool,
+    /// The `max_id` of the next page of conversations
+    pub next_messages_page: Option<String>,
+    pub no_more_messages: bool,
 
     last_search_debounce: Option<Debouncer>,
     pub search_results: Vec<Account>,
@@ -131,11 +204,17 @@ pub struct SidebarState {
     pub favorites: HashSet<String>,
     // The currently selected list (or none for timeline)
     pub selected_list: Option<String>,
+    // The stream of the selected list, it is closed when the list is deselected
+    list_stream: Option<Arc<StreamSubscription>>,
     // for each timeline we support, note whether a "load more" (e.g. older)
     // data returned an empty result. in that case hide the button
     pub no_more_load_more: HashSet<String>,
     // The current sidebare selection if we're in the More section
     pub more_selection: MoreSelection,
+    // The other accounts we're logged in with (id, name)
+    pub other_accounts: Vec<(String, String)>,
+    // The number of pending follow requests
+    pub follow_requests: usize,
 }
 
 impl SidebarState {
@@ -165,6 +244,26 @@ pub fn reduce<'a>(
             if let Some(n) = environment.repository.favorites() {
                 state.favorites = n;
             }
+            state.hidden_notification_kinds = environment
+                .repository
+                .config()
+                .map(|c| c.hidden_notification_kinds)
+                .unwrap_or_default();
+            let active = environment.accounts.active();
+            state.other_accounts = environment
+                .repository
+                .users()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|user| Some(&user.id) != active.as_ref())
+                .map(|user| {
+                    let host = url::Url::parse(&user.instance_url)
+                        .ok()
+                        .and_then(|u| u.host_str().map(str::to_string))
+                        .unwrap_or(user.instance_url);
+                    (user.id, format!("{} ({host})", user.name))
+                })
+                .collect();
             Effect::merge6(
                 environment
                     .storage
@@ -178,8 +277,14 @@ pub fn reduce<'a>(
                         SidebarAction::FavoritesChanged
                     }),
                 Effect::action(SidebarAction::LoadTimeline),
-                Effect::action(SidebarAction::LoadNotifications),
-                Effect::action(SidebarAction::LoadLists),
+                Effect::merge2(
+                    Effect::action(SidebarAction::LoadNotifications),
+                    Effect::action(SidebarAction::LoadMessages),
+                ),
+                Effect::merge2(
+                    Effect::action(SidebarAction::LoadLists),
+                    Effect::action(SidebarAction::LoadFollowRequests),
+                ),
                 Effect::timer(
                     Duration::from_secs(85),
                     SidebarAction::Reload(false),
@@ -209,9 +314,32 @@ pub fn reduce<'a>(
                 state.selected_account = d.selected_account.clone();
                 state.posts_empty = d.posts().is_empty();
                 state.selected_notifications = d.selected_notifications.clone();
-                state.notification_accounts = d.notification_accounts.clone();
+                state.selected_notification_group = d.selected_notification_group.clone();
+                // Only show accounts and groups with notifications the user wants to see
+                let hidden = state.hidden_notification_kinds;
+                state.notification_accounts = d
+                    .notification_accounts
+                    .iter()
+                    .filter(|a| {
+                        d.notification_posts
+                            .get(&a.id)
+                            .map(|p| p.iter().any(|n| !hidden.contains(n.kind)))
+                            .unwrap_or_default()
+                    })
+                    .cloned()
+                    .collect();
+                state.notification_groups = d
+                    .notification_groups
+                    .iter()
+                    .filter(|g| !hidden.contains(g.kind))
+                    .cloned()
+                    .collect();
                 state.notification_posts_empty = d.notification_posts.is_empty();
+                state.direct_conversations = d.direct_conversations.clone();
+                state.selected_direct_conversation = d.selected_direct_conversation.clone();
+                state.has_new_messages = d.has_unread_direct_messages();
                 state.user_account = d.user_account.clone();
+                state.follow_requests = d.follow_requests.len();
             });
             // check if we have search results, in that case update apprioripately,
             // because a reload might have loaded an account that was prior to that not
@@ -271,9 +399,12 @@ pub fn reduce<'a>(
                 .ok()
                 .map(|e| e.direction)
                 .unwrap_or_default();
+            // The accounts might already be filled from the cache
+            let is_initial = !state.last_timeline_id.contains_key("");
             environment.storage.with_mutation(|mut storage| {
-                let is_initial = storage.accounts().is_empty();
                 state.loading_content = false;
+                // Keep showing the cached content if the server can't be reached
+                storage.is_offline = data.is_err();
                 let Ok(batch) = data else {
                     return Effect::NONE
                 };
@@ -320,6 +451,7 @@ pub fn reduce<'a>(
                     return Effect::NONE;
                 }
                 let old = storage.notification_posts.clone();
+                let old_groups = storage.notification_groups.clone();
                 storage.update_notifications(&n);
 
                 // FIXME: Notifications really need to be in their own reducer..
@@ -341,10 +473,26 @@ pub fn reduce<'a>(
                     } else {
                         state.has_new_notifications = false;
                     }
-                } else if old != storage.notification_posts {
+                } else if old != storage.notification_posts
+                    || old_groups != storage.notification_groups
+                {
                     state.has_new_notifications = true;
                 }
 
+                let newest = n
+                    .iter()
+                    .map(|e| e.id.clone())
+                    .chain(state.newest_notification_id.clone())
+                    .max_by(|a, b| cmp_ids(a, b));
+                // They might have been read on another client already
+                let marker = storage.markers.notifications.as_ref();
+                if let (Some(marker), Some(newest)) = (marker, &newest) {
+                    if marker.covers(newest) {
+                        state.has_new_notifications = false;
+                    }
+                }
+                state.newest_notification_id = newest;
+
                 let (avt, active_tab) = (storage.user_account.clone(), storage.active_tab);
 
                 // Only, if we're still logged in
@@ -367,6 +515,56 @@ pub fn reduce<'a>(
             context.send_parent(SidebarDelegateAction::SelectedNotifications(account));
             Effect::NONE
         }
+        SidebarAction::SelectedNotificationGroup(id) => {
+            context.send_parent(SidebarDelegateAction::SelectedNotificationGroup(id));
+            Effect::NONE
+        }
+        SidebarAction::ToggleNotificationKind(kind) => {
+            let hidden = environment.repository.map_config(|c| {
+                if !c.hidden_notification_kinds.remove(kind) {
+                    c.hidden_notification_kinds.insert(kind);
+                }
+                c.hidden_notification_kinds
+            });
+            if let Ok(hidden) = hidden {
+                state.hidden_notification_kinds = hidden;
+            }
+            // Notify everyone showing notifications
+            environment.storage.with_mutation(|_| ());
+            Effect::NONE
+        }
+        SidebarAction::LoadMessages => {
+            if state.no_more_messages {
+                return Effect::NONE;
+            }
+            let page = state.next_messages_page.clone();
+            state.loading_messages = true;
+            Effect::future(
+                async move { model.conversations(page).await },
+                SidebarAction::LoadedMessages,
+            )
+        }
+        SidebarAction::LoadedMessages(result) => {
+            state.loading_messages = false;
+            let (conversations, next) = match result {
+                Ok(n) => n,
+                Err(e) => {
+                    log::error!("Could not load conversations: {e}");
+                    return Effect::NONE;
+                }
+            };
+            // Only older messages are loaded, new ones arrive via the stream
+            state.no_more_messages = next.is_none();
+            state.next_messages_page = next;
+            environment.storage.with_mutation(|mut storage| {
+                storage.update_direct_conversations(&conversations);
+            });
+            Effect::NONE
+        }
+        SidebarAction::SelectedDirectConversation(id) => {
+            context.send_parent(SidebarDelegateAction::SelectedDirectConversation(id));
+            Effect::NONE
+        }
         SidebarAction::ChangeTab(tab) => {
             let avatar = environment.storage.with_mutation(|mut storage| {
                 storage.active_tab = tab;
@@ -404,7 +602,36 @@ pub fn reduce<'a>(
                     {
                         log::error!("Could not update notification {e:?}");
                     }
-                })
+                });
+
+                // and share the read position with other clients
+                if let Some(id) = state.newest_notification_id.clone() {
+                    let is_read = environment.storage.with_mutation(|mut storage| {
+                        let marker = storage.markers.notifications.as_ref();
+                        if marker.map(|m| m.covers(&id)).unwrap_or_default() {
+                            return true;
+                        }
+                        storage.markers.notifications = Some(ServerMarker::new(id.clone()));
+                        false
+                    });
+                    if !is_read {
+                        return Effect::fire_forget(async move {
+                            if let Err(e) = model.save_markers(None, Some(id)).await {
+                                log::error!("Could not save notifications marker: {e}");
+                            }
+                        });
+                    }
+                }
+            }
+
+            // The open conversation is read once it is visible
+            if tab == UiTab::Messages {
+                if let Some(id) = state.selected_direct_conversation.clone() {
+                    let unread_ids = environment
+                        .storage
+                        .with_mutation(|mut storage| storage.mark_direct_conversation_read(&id));
+                    return mark_conversations_read(environment, unread_ids);
+                }
             }
             Effect::NONE
         }
@@ -442,7 +669,13 @@ pub fn reduce<'a>(
                 environment.storage.with_mutation(|mut s| s.clear_reload());
                 state.last_timeline_id = HashMap::new();
                 state.last_notification_id = None;
+                state.next_messages_page = None;
+                state.no_more_messages = false;
                 state.no_more_load_more = HashSet::new();
+                return Effect::merge2(
+                    Effect::action(SidebarAction::LoadTimeline),
+                    Effect::action(SidebarAction::LoadFollowRequests),
+                );
             }
             Effect::action(SidebarAction::LoadTimeline)
         }
@@ -477,6 +710,14 @@ pub fn reduce<'a>(
             }
             Effect::NONE
         }
+        SidebarAction::SearchEverywhere => {
+            let term = std::mem::take(&mut state.search_term);
+            state.search_results = Vec::new();
+            environment
+                .storage
+                .with_mutation(|mut storage| storage.search_term = Some(term));
+            Effect::action(SidebarAction::MoreSelection(MoreSelection::Search))
+        }
         SidebarAction::LoadLists => Effect::future(
             async move {
                 Ok(match model.lists().await {
@@ -505,19 +746,24 @@ pub fn reduce<'a>(
             state.search_term = String::new();
             if entry.is_empty() {
                 state.selected_list = None;
+                state.list_stream = None;
                 Effect::NONE
             } else {
                 let Some(list) = environment.storage.with(|s| s.timelines.get(&entry).cloned()) else {
                     state.selected_list = None;
+                    state.list_stream = None;
                     return Effect::NONE
                 };
                 state.selected_list = Some(entry.clone());
+                // The selected list is updated live
+                let (subscription, stream) =
+                    subscribe_stream(environment, StreamKind::List(entry.clone()));
+                state.list_stream = Some(subscription);
                 if list.entries.is_empty() {
                     // if we don't have any data for this list, load data
-                    Effect::action(SidebarAction::LoadList(entry))
+                    Effect::merge2(Effect::action(SidebarAction::LoadList(entry)), stream)
                 } else {
-                    // otherwise, do nothing
-                    Effect::NONE
+                    stream
                 }
             }
         }
@@ -552,11 +798,78 @@ pub fn reduce<'a>(
             });
             Effect::NONE
         }
+        SidebarAction::EditList(id) => {
+            let request = id.map(ListRequest::Edit).unwrap_or(ListRequest::New);
+            environment
+                .storage
+                .with_mutation(|mut storage| storage.list_request = Some(request));
+            Effect::action(SidebarAction::Root(Action::SelectMore(
+                MoreSelection::Lists,
+            )))
+        }
+        SidebarAction::DeleteList(id) => {
+            let Some(title) = environment
+                .storage
+                .with(|s| s.timelines.get(&id).map(|e| e.title.clone())) else {
+                return Effect::NONE
+            };
+            if !crate::environment::platform::confirm_dialog(
+                &title,
+                crate::loc!("Do you really want to delete this list?"),
+            ) {
+                return Effect::NONE;
+            }
+            let model = environment.model.clone();
+            let cloned_id = id.clone();
+            Effect::future(
+                async move { model.delete_list(cloned_id).await },
+                move |r| SidebarAction::DeletedList(id, r),
+            )
+        }
+        SidebarAction::DeletedList(id, result) => {
+            if let Err(e) = result {
+                log::error!("Could not delete list {id}: {e}");
+                return Effect::NONE;
+            }
+            if state.selected_list.as_ref() == Some(&id) {
+                state.selected_list = None;
+                state.list_stream = None;
+            }
+            Effect::action(SidebarAction::LoadLists)
+        }
+        SidebarAction::LoadFollowRequests => {
+            let model = environment.model.clone();
+            Effect::future(
+                async move { model.follow_requests(None).await },
+                SidebarAction::LoadedFollowRequests,
+            )
+        }
+        SidebarAction::LoadedFollowRequests(result) => {
+            let accounts = match result {
+                Ok(n) => n,
+                Err(e) => {
+                    log::error!("Could not load follow requests: {e}");
+                    return Effect::NONE;
+                }
+            };
+            environment.storage.with_mutation(|mut storage| {
+                storage.follow_requests = accounts.iter().map(AccountViewModel::new).collect();
+            });
+            Effect::NONE
+        }
         SidebarAction::MoreSelection(s) => {
             state.more_selection = s;
             context.send_parent(SidebarDelegateAction::SelectMore(s));
             Effect::NONE
         }
+        SidebarAction::ShowMore(s) => {
+            state.more_selection = s;
+            if state.active_tab != UiTab::More {
+                Effect::action(SidebarAction::ChangeTab(UiTab::More))
+            } else {
+                Effect::NONE
+            }
+        }
     }
 }
 
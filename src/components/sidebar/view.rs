@@ -2,15 +2,17 @@ use std::cmp::Ordering;
 
 use super::reducer::{MoreSelection, SidebarAction, ViewStore};
 use crate::{
-    components::post::PostKind,
     environment::menu::{self, ViewStoreContextMenu},
     environment::{
         model::Account,
         storage::UiTab,
-        types::{AppEvent, MainMenuEvent},
+        types::{AppEvent, MainMenuEvent, PostKind},
     },
     loc,
-    view_model::{AccountUpdateViewModel, AccountViewModel},
+    view_model::{
+        AccountUpdateViewModel, AccountViewModel, DirectConversationViewModel,
+        NotificationGroupViewModel, NotificationKind,
+    },
     widgets::*,
 };
 use dioxus::prelude::*;
@@ -23,7 +25,8 @@ use crate::PublicAction;
 pub fn SidebarComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a> {
     log::trace!("Rerender SidebarComponent");
     let has_notifications = store.has_new_notifications;
-    let has_messages = false; // FIXME
+    let has_messages = store.has_new_messages;
+    let has_follow_requests = store.follow_requests > 0;
     let tab = store.active_tab;
     let tabs = vec![
         TabBarItem::new(
@@ -44,7 +47,12 @@ pub fn SidebarComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a>
             tab.is_messages(),
             has_messages,
         ),
-        TabBarItem::new(UiTab::More, loc!("More").to_string(), tab.is_more(), false),
+        TabBarItem::new(
+            UiTab::More,
+            loc!("More").to_string(),
+            tab.is_more(),
+            has_follow_requests,
+        ),
     ];
 
     #[cfg(target_os = "macos")]
@@ -77,9 +85,8 @@ pub fn SidebarComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a>
                         // search_state: search_state
                     })
                 } else if tab == tabs[2].id {
-                    rsx!(div {
-                        class: "hstack p-3 m-3 grow align-self-center label-secondary",
-                        "Not implemented yet"
+                    rsx!(SidebarMessagesComponent {
+                        store: store
                     })
                 } else if tab == tabs[3].id {
                     rsx!(SidebarMoreComponent {
@@ -161,6 +168,21 @@ fn SidebarAccountsComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Elem
                     })
                 }),
 
+                {
+                    (!search_term.is_empty()).then(|| rsx!(div {
+                        class: "hstack justify-content-center mt-2",
+                        IconTextButton {
+                            icon: crate::icons::ICON_SEARCH,
+                            text: loc!("Search Everywhere"),
+                            title: loc!("Search posts, hashtags and accounts or open a link"),
+                            class: "mb-3",
+                            onclick: move |_| {
+                                store.send(SidebarAction::SearchEverywhere);
+                            },
+                        },
+                    }))
+                },
+
                 {
                     (search_term.is_empty() && !is_loading && !store.posts_empty && can_load_more)
                     .then(|| rsx!(div {
@@ -202,15 +224,21 @@ fn SidebarMoreComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<
             More(Classic),
             More(Local),
             More(Federated),
-            //Title(loc!("Explore")),
+            Title(loc!("Explore")),
             //More(Posts),
-            //More(Hashtags),
+            More(Search),
+            More(Hashtags),
             Title(loc!("Account")),
             More(Yours),
             More(Followers),
             More(Following),
+            More(FollowRequests),
             More(Bookmarks),
             More(Favorites),
+            More(Scheduled),
+            More(Moderation),
+            More(Filters),
+            More(Lists),
         ]
     };
 
@@ -230,6 +258,7 @@ fn SidebarMoreComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<
                                 icon: m.content(),
                                 text: m.title(),
                                 selected: selection == *m,
+                                badge: (*m == MoreSelection::FollowRequests).then_some(store.follow_requests).unwrap_or_default(),
                                 onclick: move |_| store.send(SidebarAction::MoreSelection(*m))
                             })
                         }
@@ -240,28 +269,80 @@ fn SidebarMoreComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<
     })
 }
 
+enum SidebarNotificationEntry<'a> {
+    Account(&'a AccountUpdateViewModel),
+    Group(&'a NotificationGroupViewModel),
+}
+
+impl SidebarNotificationEntry<'_> {
+    fn date(&self) -> chrono::DateTime<chrono::Utc> {
+        match self {
+            Self::Account(a) => a.last_updated,
+            Self::Group(g) => g.date,
+        }
+    }
+}
+
 #[inline_props]
 fn SidebarNotificationsComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
     let selection = &store.selected_notifications;
+    let group_selection = &store.selected_notification_group;
     let is_loading = store.loading_notifications;
 
+    // Accounts (mentions) and groups (boosts, follows, ...) sorted by date
+    let entries = store
+        .notification_accounts
+        .iter()
+        .map(SidebarNotificationEntry::Account)
+        .merge_by(
+            store
+                .notification_groups
+                .iter()
+                .map(SidebarNotificationEntry::Group),
+            |a, b| a.date() >= b.date(),
+        );
+
     cx.render(rsx! {
+        HStack { class: "justify-content-end p-2 pe-3",
+            IconTextButton {
+                icon: crate::icons::ICON_OPTIONS,
+                text: loc!("Filter"),
+                title: loc!("Choose which notifications to show"),
+                onclick: move |evt: MouseEvent| {
+                    let items = enumset::EnumSet::<NotificationKind>::all()
+                        .iter()
+                        .map(|kind| {
+                            menu::ContextMenuItem::checkbox(
+                                kind.title(),
+                                !store.hidden_notification_kinds.contains(kind),
+                                SidebarAction::ToggleNotificationKind(kind),
+                            )
+                        })
+                        .collect();
+                    store.context_menu(
+                        cx,
+                        &evt.data,
+                        menu::ContextMenu::<SidebarAction>::new("Notifications", true, items),
+                    )
+                },
+            }
+        }
         div { class: "scroll",
             div { class: "scroll-margin-fix",
-                store.notification_accounts.iter()
-                    // .filter(|account| if search_term.is_empty() {
-                    //     true
-                    // } else {
-                    //     account.display_name.contains(search_term) ||
-                    //     account.username.contains(search_term)
-                    // })
-                    .map(move |model| rsx!(CellComponent {
+                entries.map(move |entry| match entry {
+                    SidebarNotificationEntry::Account(model) => rsx!(CellComponent {
                         model: model.clone(),
                         store: store,
                         selected: selection.as_ref() == Some(&model.account),
                         onclick: move |_| store.send(SidebarAction::SelectedNotifications(model.account.clone())),
                         favorited: false
-                    })),
+                    }),
+                    SidebarNotificationEntry::Group(group) => rsx!(NotificationGroupCellComponent {
+                        group: group,
+                        selected: group_selection.as_ref() == Some(&group.id),
+                        onclick: move |_| store.send(SidebarAction::SelectedNotificationGroup(group.id.clone())),
+                    }),
+                }),
 
                 {
                     (/*search_term.is_empty() &&*/ !is_loading && !store.notification_posts_empty)
@@ -288,6 +369,47 @@ fn SidebarNotificationsComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) ->
     })
 }
 
+#[inline_props]
+fn SidebarMessagesComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
+    let selection = &store.selected_direct_conversation;
+    let is_loading = store.loading_messages;
+
+    cx.render(rsx! {
+        div { class: "scroll",
+            div { class: "scroll-margin-fix",
+                store.direct_conversations.iter()
+                    .map(move |model| rsx!(DirectConversationCellComponent {
+                        model: model.clone(),
+                        store: store,
+                        selected: selection.as_ref() == Some(&model.id),
+                        onclick: move |_| store.send(SidebarAction::SelectedDirectConversation(model.id.clone())),
+                    })),
+
+                {
+                    (!is_loading && !store.no_more_messages && !store.direct_conversations.is_empty())
+                    .then(|| rsx!(div {
+                        class: "hstack justify-content-center mt-2",
+                        IconTextButton {
+                            icon: crate::icons::ICON_LOAD_OLDER_TIMELINE,
+                            text: "More",
+                            title: "Load more direct messages",
+                            class: "mb-3",
+                            onclick: move |_| {
+                                store.send(SidebarAction::LoadMessages);
+                            },
+                        },
+                    }))
+                }
+            }
+        }
+
+        { is_loading.then(|| rsx!(div {
+            class: "hstack p-2 m-2 grow align-self-center",
+            Spinner {}
+        }))}
+    })
+}
+
 #[inline_props]
 fn MenuComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
     let img = store
@@ -296,32 +418,47 @@ fn MenuComponent<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
         .map(|i| {
             rsx!(img {
                 onclick: move |evt| {
+                    let accounts: Vec<_> = store
+                        .other_accounts
+                        .iter()
+                        .map(|(id, name)| {
+                            menu::ContextMenuItem::item(
+                                name,
+                                SidebarAction::Root(Action::SwitchAccount(id.clone())),
+                            )
+                        })
+                        .collect();
+                    let mut items = vec![
+                        menu::ContextMenuItem::item(
+                            "Open in Browser",
+                            SidebarAction::Root(Action::Public(PublicAction::OpenLink(
+                                i.url.clone(),
+                            ))),
+                        ),
+                        menu::ContextMenuItem::item(
+                            "Copy URL",
+                            SidebarAction::Root(Action::Public(PublicAction::Copy(
+                                i.url.clone(),
+                            ))),
+                        ),
+                        menu::ContextMenuItem::separator(),
+                    ];
+                    if !accounts.is_empty() {
+                        items.push(menu::ContextMenuItem::submenu("Switch Account", accounts));
+                    }
+                    items.push(menu::ContextMenuItem::item(
+                        "Add Account…",
+                        SidebarAction::Root(Action::AddAccount),
+                    ));
+                    items.push(menu::ContextMenuItem::separator());
+                    items.push(menu::ContextMenuItem::item(
+                        "Logout",
+                        SidebarAction::Root(Action::Logout),
+                    ));
                     store.context_menu(
                         cx,
                         &evt,
-                        menu::ContextMenu::<SidebarAction>::new(
-                            "Account Options",
-                            true,
-                            vec![
-                                menu::ContextMenuItem::item(
-                                    "Open in Browser",
-                                    SidebarAction::Root(Action::Public(PublicAction::OpenLink(
-                                        i.url.clone(),
-                                    ))),
-                                ),
-                                menu::ContextMenuItem::item(
-                                    "Copy URL",
-                                    SidebarAction::Root(Action::Public(PublicAction::Copy(
-                                        i.url.clone(),
-                                    ))),
-                                ),
-                                menu::ContextMenuItem::separator(),
-                                menu::ContextMenuItem::item(
-                                    "Logout",
-                                    SidebarAction::Root(Action::Logout),
-                                ),
-                            ],
-                        ),
+                        menu::ContextMenu::<SidebarAction>::new("Account Options", true, items),
                     )
                 },
                 class: "image-author-small",
@@ -392,6 +529,91 @@ fn CellComponent<'a>(
 ) -> Element {
     // let window = AppWindow::retrieve(cx);
     let class = selected.then(|| "cell selected").unwrap_or("cell");
+    cx.render(rsx!(
+        div {
+            class: "{class} grow",
+            onclick: move |_| onclick.call(()),
+            prevent_default: "oncontextmenu",
+            oncontextmenu: move |e| {
+                let mut items = vec![
+                    menu::ContextMenuItem::item(
+                        "Open in Browser", 
+                        Action::Public(PublicAction::OpenLink(model.account.url.clone()))
+                    ),
+                    menu::ContextMenuItem::item(
+                        "Copy URL",
+                        Action::Public(PublicAction::Copy(model.account.url.clone()))
+                    ),
+                    menu::ContextMenuItem::item(
+                        "Copy Account Name", 
+                        Action::Public(PublicAction::Copy(model.account.acct.clone()))
+                    ),
+                    menu::ContextMenuItem::separator(),
+                ];
+                items.extend(crate::components::profile_preview::moderation_menu_items(
+                    &model.account,
+                    (false, false, false),
+                    |m| SidebarAction::Root(Action::Public(PublicAction::Moderate(model.account.clone(), m))),
+                ));
+                items.extend(crate::components::profile_preview::list_menu_items(
+                    &store.list_names,
+                    |id, add| SidebarAction::Root(Action::Public(PublicAction::UpdateListMembership(model.account.clone(), id, add))),
+                ));
+                store.context_menu(
+                    cx,
+                    &e.data,
+                    menu::ContextMenu::<SidebarAction>::new("Account", true, items),
+                )
+            },
+            HStack { class: "gap-2 grow",
+                VStack { class: "align-items-center no-shrink noclip",
+                    img {
+                        class: "image-author",
+                        src: "{model.account.image}",
+                        alt: "{model.account.display_name}",
+                        width: 42,
+                        height: 42
+                    }
+                    favorited.then(|| rsx!(div {
+                    class: "favorite-icon",
+                    Icon {
+                        icon: crate::icons::ICON_LIKE_ACCOUNT,
+                        title: loc!("Favorite Account. Always at the top"),
+                }}))
+                }
+                VStack { class: "gap-1 grow account-preview-fields",
+                    HStack { class: "justify-content-between force-single-line",
+                        Label {
+                            onclick: move |_| onclick.call(()),
+                            style: TextStyle::Primary,
+                            class: "me-auto",
+                            pointer_style: PointerStyle::Pointer,
+                            "{model.account.username}"
+                        }
+                        Label { style: TextStyle::Secondary, pointer_style: PointerStyle::Pointer, "{model.last_updated_human}" }
+                    }
+                    Paragraph {
+                        class: "status-content",
+                        style: TextStyle::Tertiary,
+                        pointer_style: PointerStyle::Pointer,
+                        "{model.content}"
+                    }
+                }
+            }
+        }
+    ))
+}
+
+#[inline_props]
+fn DirectConversationCellComponent<'a>(
+    cx: Scope<'a>,
+    model: DirectConversationViewModel,
+    selected: bool,
+    store: &'a ViewStore<'a>,
+    onclick: EventHandler<'a, ()>,
+) -> Element {
+    let class = selected.then(|| "cell selected").unwrap_or("cell");
+    let unread_title = loc!("Unread messages");
     cx.render(rsx!(
         div {
             class: "{class} grow",
@@ -402,20 +624,16 @@ fn CellComponent<'a>(
                     cx,
                     &e.data,
                     menu::ContextMenu::<SidebarAction>::new(
-                        "Account",
+                        "Conversation",
                         true,
                         vec![
                             menu::ContextMenuItem::item(
-                                "Open in Browser", 
+                                "Open Profile in Browser",
                                 Action::Public(PublicAction::OpenLink(model.account.url.clone()))
                             ),
                             menu::ContextMenuItem::item(
-                                "Copy URL",
-                                Action::Public(PublicAction::Copy(model.account.url.clone()))
-                            ),
-                            menu::ContextMenuItem::item(
-                                "Copy Account Name", 
-                                Action::Public(PublicAction::Copy(model.account.acct.clone()))
+                                "Copy Participants",
+                                Action::Public(PublicAction::Copy(model.participants.clone()))
                             ),
                         ],
                     ),
@@ -430,12 +648,10 @@ fn CellComponent<'a>(
                         width: 42,
                         height: 42
                     }
-                    favorited.then(|| rsx!(div {
-                    class: "favorite-icon",
-                    Icon {
-                        icon: crate::icons::ICON_LIKE_ACCOUNT,
-                        title: loc!("Favorite Account. Always at the top"),
-                }}))
+                    model.unread.then(|| rsx!(div {
+                        class: "unread-dot",
+                        title: "{unread_title}",
+                    }))
                 }
                 VStack { class: "gap-1 grow account-preview-fields",
                     HStack { class: "justify-content-between force-single-line",
@@ -444,7 +660,7 @@ fn CellComponent<'a>(
                             style: TextStyle::Primary,
                             class: "me-auto",
                             pointer_style: PointerStyle::Pointer,
-                            "{model.account.username}"
+                            "{model.participants}"
                         }
                         Label { style: TextStyle::Secondary, pointer_style: PointerStyle::Pointer, "{model.last_updated_human}" }
                     }
@@ -460,6 +676,54 @@ fn CellComponent<'a>(
     ))
 }
 
+#[inline_props]
+fn NotificationGroupCellComponent<'a>(
+    cx: Scope<'a>,
+    group: &'a NotificationGroupViewModel,
+    selected: bool,
+    onclick: EventHandler<'a, ()>,
+) -> Element<'a> {
+    let class = selected.then(|| "cell selected").unwrap_or("cell");
+    let image = group
+        .accounts
+        .first()
+        .map(|a| a.image.clone())
+        .unwrap_or_default();
+    cx.render(rsx!(
+        div {
+            class: "{class} grow",
+            onclick: move |_| onclick.call(()),
+            HStack { class: "gap-2 grow",
+                VStack { class: "align-items-center no-shrink noclip",
+                    img {
+                        class: "image-author",
+                        src: "{image}",
+                        width: 42,
+                        height: 42
+                    }
+                }
+                VStack { class: "gap-1 grow account-preview-fields",
+                    HStack { class: "justify-content-between force-single-line",
+                        Label {
+                            style: TextStyle::Primary,
+                            class: "me-auto",
+                            pointer_style: PointerStyle::Pointer,
+                            "{group.message}"
+                        }
+                        Label { style: TextStyle::Secondary, pointer_style: PointerStyle::Pointer, "{group.date_human}" }
+                    }
+                    Paragraph {
+                        class: "status-content",
+                        style: TextStyle::Tertiary,
+                        pointer_style: PointerStyle::Pointer,
+                        "{group.content}"
+                    }
+                }
+            }
+        }
+    ))
+}
+
 #[inline_props]
 fn AccountCellComponent<'a>(
     cx: Scope<'a>,
@@ -597,6 +861,33 @@ fn ListSelector<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
             onchange: move |evt| {
                 store.send(SidebarAction::SelectList(evt.value.clone()));
             },
+            prevent_default: "oncontextmenu",
+            oncontextmenu: move |e| {
+                let mut items = vec![menu::ContextMenuItem::item(
+                    loc!("New List..."),
+                    SidebarAction::EditList(None),
+                )];
+                if let Some(id) = store.selected_list.as_ref() {
+                    items.push(menu::ContextMenuItem::item(
+                        loc!("Edit List..."),
+                        SidebarAction::EditList(Some(id.clone())),
+                    ));
+                    items.push(menu::ContextMenuItem::item(
+                        loc!("Delete List"),
+                        SidebarAction::DeleteList(id.clone()),
+                    ));
+                }
+                items.push(menu::ContextMenuItem::separator());
+                items.push(menu::ContextMenuItem::item(
+                    loc!("Manage Lists..."),
+                    SidebarAction::Root(Action::SelectMore(MoreSelection::Lists)),
+                ));
+                store.context_menu(
+                    cx,
+                    &e.data,
+                    menu::ContextMenu::<SidebarAction>::new("Lists", true, items),
+                )
+            },
             option { value: "", loc!("Timeline") }
             for (id , name) in store.list_names.iter() {
                 option { value: "{id}", "{name}" }
@@ -608,16 +899,22 @@ fn ListSelector<'a>(cx: Scope<'a>, store: &'a ViewStore<'a>) -> Element<'a> {
 impl MoreSelection {
     fn content(&self) -> &str {
         match self {
-            Self::Classic => "􀭞",   // square.fill.text.grid.1x2
-            Self::Yours => "􀈎",     // square.and.pencil
-            Self::Local => "􀝋",     // person.3.fill
-            Self::Federated => "􀆪", // globe
-            Self::Posts => "􀌪",     // bubble.left
-            Self::Hashtags => "􀋡",  // tag
-            Self::Followers => "􀉬", // person.2.fill
-            Self::Following => "􀉫", // person.2
-            Self::Bookmarks => "􀼺", // bookmark.square.fill
-            Self::Favorites => "􀠨", // star.square.fill
+            Self::Classic => "􀭞",    // square.fill.text.grid.1x2
+            Self::Yours => "􀈎",      // square.and.pencil
+            Self::Local => "􀝋",      // person.3.fill
+            Self::Federated => "􀆪",  // globe
+            Self::Posts => "􀌪",      // bubble.left
+            Self::Search => "􀊫",     // magnifyingglass
+            Self::Hashtags => "􀋡",   // tag
+            Self::Followers => "􀉬",  // person.2.fill
+            Self::Following => "􀉫",  // person.2
+            Self::FollowRequests => "􀜗", // person.badge.plus
+            Self::Bookmarks => "􀼺",  // bookmark.square.fill
+            Self::Favorites => "􀠨",  // star.square.fill
+            Self::Scheduled => "􀐫",  // clock
+            Self::Moderation => "􀇿", // exclamationmark.octagon
+            Self::Filters => "􀌈",    // line.3.horizontal.decrease.circle
+            Self::Lists => "􀋲",      // list.bullet
         }
     }
 
@@ -628,11 +925,17 @@ impl MoreSelection {
             MoreSelection::Local => loc!("Local"),
             MoreSelection::Federated => loc!("Federated"),
             MoreSelection::Posts => loc!("Posts"),
+            MoreSelection::Search => loc!("Search"),
             MoreSelection::Hashtags => loc!("Hashtags"),
             MoreSelection::Followers => loc!("Followers"),
             MoreSelection::Following => loc!("Following"),
+            MoreSelection::FollowRequests => loc!("Follow Requests"),
             MoreSelection::Bookmarks => loc!("Bookmarks"),
             MoreSelection::Favorites => loc!("Favorites"),
+            MoreSelection::Scheduled => loc!("Scheduled Posts"),
+            MoreSelection::Moderation => loc!("Muted & Blocked"),
+            MoreSelection::Filters => loc!("Filters"),
+            MoreSelection::Lists => loc!("Lists"),
         }
     }
 }
@@ -16,21 +16,30 @@ pub use profile::UserProfileTimelineProvider;
 mod notifications;
 pub use notifications::NotificationsTimelineProvider;
 
+mod direct_messages;
+pub use direct_messages::DirectMessagesTimelineProvider;
+
 mod favorites;
 pub use favorites::FavoritesTimelineProvider;
 
+mod hashtag;
+pub use hashtag::HashtagTimelineProvider;
+
 mod local;
 pub use local::LocalTimelineProvider;
 
 mod public;
 pub use public::PublicTimelineProvider;
 
+mod search;
+pub use search::SearchTimelineProvider;
+
 use std::pin::Pin;
 
 use futures_util::Future;
 use megalodon::entities::Status;
 
-use crate::environment::types::TimelineDirection;
+use crate::environment::types::{StreamKind, TimelineDirection};
 
 pub trait TimelineProvider: std::fmt::Debug {
     type Id;
@@ -55,4 +64,16 @@ pub trait TimelineProvider: std::fmt::Debug {
     ) -> bool;
     fn data(&self, direction: TimelineDirection) -> Vec<Self::ViewModel>;
     fn scroll_to_item(&self, updates: &[Status]) -> Option<Self::Id>;
+    // The newest item the user already read, for jumping to it
+    fn last_read(&self) -> Option<Self::Id> {
+        None
+    }
+    // Scrolling through this timeline moves the read position of the home timeline
+    fn moves_home_marker(&self) -> bool {
+        false
+    }
+    // The stream that updates this timeline live, instead of reloading it
+    fn stream(&self) -> Option<StreamKind> {
+        None
+    }
 }
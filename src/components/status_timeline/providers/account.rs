@@ -76,4 +76,15 @@ impl TimelineProvider for AccountTimelineProvider {
             .storage
             .with(|storage| storage.classic_timeline.clone())
     }
+
+    fn last_read(&self) -> Option<StatusId> {
+        self.environment.storage.with(|storage| {
+            let marker = storage.markers.home.as_ref()?;
+            Some(StatusId(marker.last_read_id.clone()))
+        })
+    }
+
+    fn moves_home_marker(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,101 @@
+use super::TimelineProvider;
+use crate::{
+    environment::{types::TimelineDirection, Environment},
+    view_model::{StatusId, StatusViewModel},
+};
+use futures_util::Future;
+use megalodon::entities::Status;
+use std::pin::Pin;
+
+/// A provider that shows the direct messages of one conversation
+/// as a thread
+pub struct DirectMessagesTimelineProvider {
+    environment: Environment,
+    conversation: String,
+}
+
+impl DirectMessagesTimelineProvider {
+    pub fn new(environment: Environment, conversation: String) -> Self {
+        Self {
+            environment,
+            conversation,
+        }
+    }
+}
+
+impl std::fmt::Debug for DirectMessagesTimelineProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DirectMessagesTimelineProvider")
+            .field("conversation", &self.conversation)
+            .finish()
+    }
+}
+
+impl TimelineProvider for DirectMessagesTimelineProvider {
+    type Id = StatusId;
+    type Element = Status;
+    type ViewModel = StatusViewModel;
+    fn should_auto_reload(&self) -> bool {
+        false
+    }
+
+    fn identifier(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    fn forced_direction(&self) -> Option<TimelineDirection> {
+        Some(TimelineDirection::NewestTop)
+    }
+
+    fn reset(&self) {}
+
+    fn scroll_to_item(&self, _updates: &[Status]) -> Option<StatusId> {
+        None
+    }
+
+    /// The thread of the newest message, which includes the older
+    /// messages it replies to. It is loaded at once, so there are no pages
+    fn request_data(
+        &self,
+        after: Option<StatusId>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Status>, String>> + Send>> {
+        let newest = self.environment.storage.with(|storage| {
+            storage
+                .direct_posts
+                .get(&self.conversation)
+                .and_then(|posts| posts.first())
+                .map(|post| post.id.0.clone())
+        });
+        let (Some(newest), None) = (newest, after) else {
+            return Box::pin(async move { Ok(Vec::new()) });
+        };
+        let model = self.environment.model.clone();
+        Box::pin(async move {
+            let context = model.status_context(newest).await?;
+            Ok(context
+                .ancestors
+                .into_iter()
+                .chain(context.descendants)
+                .collect())
+        })
+    }
+
+    fn process_new_data(
+        &self,
+        updates: &[Status],
+        _direction: TimelineDirection,
+        _is_reload: bool,
+    ) -> bool {
+        self.environment
+            .storage
+            .with_mutation(|mut storage| storage.update_direct_thread(&self.conversation, updates));
+        false
+    }
+
+    fn data(&self, _direction: TimelineDirection) -> Vec<StatusViewModel> {
+        self.environment
+            .storage
+            .with(|storage| storage.direct_posts.get(&self.conversation).cloned())
+            .unwrap_or_default()
+    }
+}
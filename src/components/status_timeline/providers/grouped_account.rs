@@ -1,9 +1,9 @@
 use super::TimelineProvider;
 use crate::{
-    environment::{types::TimelineDirection, Environment, Model},
+    environment::{model::cmp_ids, types::TimelineDirection, Environment, Model},
     view_model::{AccountId, StatusId, StatusViewModel},
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use futures_util::Future;
 use megalodon::entities::Status;
 use std::{cell::Cell, pin::Pin};
@@ -39,6 +39,27 @@ impl std::fmt::Debug for GroupedAccountTimelineProvider {
     }
 }
 
+impl GroupedAccountTimelineProvider {
+    /// The local marker for this account, or the home marker from the
+    /// server if the timeline was read further on another client
+    fn marker(&self) -> Option<(String, DateTime<Utc>)> {
+        let local = self
+            .environment
+            .repository
+            .get_timeline_marker(&self.account.0);
+        let home = self
+            .environment
+            .storage
+            .with(|storage| storage.markers.home.clone())
+            .map(|marker| (marker.last_read_id, marker.updated_at));
+        match (local, home) {
+            (Some(local), Some(home)) if cmp_ids(&home.0, &local.0).is_gt() => Some(home),
+            (Some(local), _) => Some(local),
+            (None, home) => home,
+        }
+    }
+}
+
 impl TimelineProvider for GroupedAccountTimelineProvider {
     type Id = StatusId;
     type Element = Status;
@@ -89,9 +110,7 @@ impl TimelineProvider for GroupedAccountTimelineProvider {
             .with(|m| m.posts().get(&self.account).cloned());
 
         // check if we have a marker for this account
-        let (after_id, since_id, post_id) = if let Some((marker, last_used)) =
-            self.environment.repository.get_timeline_marker(&account.0)
-        {
+        let (after_id, since_id, post_id) = if let Some((marker, last_used)) = self.marker() {
             // If we have posts we selected an account with updates in the sidebar
             if let Some(post) = act.and_then(|e| e.last().cloned()) {
                 log::trace!("Found marker {marker:?}");
@@ -161,8 +180,7 @@ impl TimelineProvider for GroupedAccountTimelineProvider {
                             }
                             stati.append(&mut next);
                             let Some(last) = stati.last() else { return Ok(stati) };
-                            let Some(cmp) = cmp_id_str_gt(&last.id, &post_id) else { return Ok(stati) };
-                            if !cmp {
+                            if cmp_ids(&last.id, &post_id).is_lt() {
                                 log::trace!("smp not done yet: {} >= {}", &last.id, &post_id);
                                 // not done yet
                                 cloned_since = last.id.clone();
@@ -211,13 +229,12 @@ impl TimelineProvider for GroupedAccountTimelineProvider {
                 .unwrap_or_default()
         })
     }
-}
 
-/// check if a is >= b (converted to nr)
-fn cmp_id_str_gt(a: &str, b: &str) -> Option<bool> {
-    let a = a.parse::<u128>().ok()?;
-    let b = b.parse::<u128>().ok()?;
-    Some(a >= b)
+    // The posts of one account don't tell how far the home timeline was read,
+    // so this doesn't move the home marker
+    fn last_read(&self) -> Option<StatusId> {
+        self.marker().map(|(id, _)| StatusId(id))
+    }
 }
 
 fn newer_than<'a>(
@@ -0,0 +1,101 @@
+use super::TimelineProvider;
+use crate::{
+    environment::{
+        types::{StreamKind, TimelineDirection},
+        Environment,
+    },
+    view_model::{StatusId, StatusViewModel},
+};
+use futures_util::Future;
+use megalodon::entities::Status;
+use std::pin::Pin;
+
+/// A provider that loads the public timeline of a hashtag
+pub struct HashtagTimelineProvider {
+    environment: Environment,
+    tag: String,
+    identifier: String,
+}
+
+impl HashtagTimelineProvider {
+    pub fn new(environment: Environment, tag: String) -> Self {
+        let identifier = format!("HashtagTimelineProvider-{tag}");
+        Self {
+            environment,
+            tag,
+            identifier,
+        }
+    }
+}
+
+impl std::fmt::Debug for HashtagTimelineProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HashtagTimelineProvider")
+            .field("tag", &self.tag)
+            .finish()
+    }
+}
+
+impl TimelineProvider for HashtagTimelineProvider {
+    type Id = StatusId;
+    type Element = Status;
+    type ViewModel = StatusViewModel;
+    fn should_auto_reload(&self) -> bool {
+        true
+    }
+
+    fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    fn forced_direction(&self) -> Option<TimelineDirection> {
+        Some(TimelineDirection::NewestTop)
+    }
+
+    fn reset(&self) {
+        self.environment.storage.with_mutation(|mut storage| {
+            storage.tag_timelines.remove(&self.tag);
+        })
+    }
+
+    fn scroll_to_item(&self, _updates: &[Status]) -> Option<StatusId> {
+        None
+    }
+
+    fn request_data(
+        &self,
+        after: Option<StatusId>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Status>, String>> + Send>> {
+        let after = after.map(|e| e.0);
+        let model = self.environment.model.clone();
+        let tag = self.tag.clone();
+        Box::pin(async move { model.tag_timeline(tag, after).await })
+    }
+
+    fn process_new_data(
+        &self,
+        updates: &[Status],
+        _direction: TimelineDirection,
+        is_reload: bool,
+    ) -> bool {
+        let can_load_more = !updates.is_empty();
+        self.environment
+            .storage
+            .with_mutation(|mut storage| storage.merge_tag_timeline(updates, &self.tag, is_reload));
+        can_load_more
+    }
+
+    fn data(&self, _direction: TimelineDirection) -> Vec<StatusViewModel> {
+        self.environment.storage.with(|storage| {
+            storage
+                .tag_timelines
+                .get(&self.tag)
+                .cloned()
+                .unwrap_or_default()
+        })
+    }
+
+    fn stream(&self) -> Option<StreamKind> {
+        Some(StreamKind::Hashtag(self.tag.clone()))
+    }
+}
@@ -1,6 +1,9 @@
 use super::TimelineProvider;
 use crate::{
-    environment::{types::TimelineDirection, Environment},
+    environment::{
+        types::{StreamKind, TimelineDirection},
+        Environment,
+    },
     view_model::{StatusId, StatusViewModel},
 };
 use futures_util::Future;
@@ -77,4 +80,8 @@ impl TimelineProvider for LocalTimelineProvider {
             .storage
             .with(|storage| storage.local_timeline.clone())
     }
+
+    fn stream(&self) -> Option<StreamKind> {
+        Some(StreamKind::Local)
+    }
 }
@@ -69,11 +69,18 @@ impl TimelineProvider for NotificationsTimelineProvider {
     }
 
     fn data(&self, _direction: TimelineDirection) -> Vec<StatusViewModel> {
+        let hidden = self
+            .environment
+            .repository
+            .config()
+            .map(|c| c.hidden_notification_kinds)
+            .unwrap_or_default();
         self.environment
             .storage
             .with(|storage| storage.notification_posts.get(&self.account).cloned())
             .unwrap_or_default()
             .iter()
+            .filter(|e| !hidden.contains(e.kind))
             .map(|e| e.status.clone())
             .collect()
     }
@@ -0,0 +1,86 @@
+use super::TimelineProvider;
+use crate::{
+    environment::{types::TimelineDirection, Environment},
+    view_model::{StatusId, StatusViewModel},
+};
+use futures_util::Future;
+use megalodon::entities::Status;
+use std::pin::Pin;
+
+/// A provider that shows the statuses of a search which
+/// were already loaded
+pub struct SearchTimelineProvider {
+    environment: Environment,
+    term: String,
+    identifier: String,
+}
+
+impl SearchTimelineProvider {
+    pub fn new(environment: Environment, term: String) -> Self {
+        let identifier = format!("SearchTimelineProvider-{term}");
+        Self {
+            environment,
+            term,
+            identifier,
+        }
+    }
+}
+
+impl std::fmt::Debug for SearchTimelineProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchTimelineProvider")
+            .field("term", &self.term)
+            .finish()
+    }
+}
+
+impl TimelineProvider for SearchTimelineProvider {
+    type Id = StatusId;
+    type Element = Status;
+    type ViewModel = StatusViewModel;
+    fn should_auto_reload(&self) -> bool {
+        false
+    }
+
+    fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    fn forced_direction(&self) -> Option<TimelineDirection> {
+        Some(TimelineDirection::NewestTop)
+    }
+
+    fn reset(&self) {}
+
+    fn scroll_to_item(&self, _updates: &[Status]) -> Option<StatusId> {
+        None
+    }
+
+    fn request_data(
+        &self,
+        _after: Option<StatusId>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Status>, String>> + Send>> {
+        // The statuses are loaded by the search
+        Box::pin(async move { Ok(Vec::new()) })
+    }
+
+    fn process_new_data(
+        &self,
+        _updates: &[Status],
+        _direction: TimelineDirection,
+        _is_reload: bool,
+    ) -> bool {
+        false
+    }
+
+    fn data(&self, _direction: TimelineDirection) -> Vec<StatusViewModel> {
+        self.environment.storage.with(|storage| {
+            storage
+                .search_results
+                .as_ref()
+                .filter(|r| r.term == self.term)
+                .map(|r| r.statuses.clone())
+                .unwrap_or_default()
+        })
+    }
+}
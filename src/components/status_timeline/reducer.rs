@@ -1,9 +1,12 @@
-use std::time::Duration;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use enumset::EnumSet;
 
 use super::providers::AnyTimelineProvider;
-use crate::environment::model::Status;
+use crate::components::loggedin::{subscribe_stream, StreamSubscription};
+use crate::environment::model::{cmp_ids, Status};
 use crate::environment::types::{AppEvent, MainMenuEvent, TimelineDirection, UiConfig};
 use crate::environment::Environment;
 use crate::view_model::{
@@ -12,6 +15,11 @@ use crate::view_model::{
 use crate::PublicAction;
 use navicula::Effect;
 
+/// How far back we go to find the read position
+const MAX_JUMP_PAGES: usize = 5;
+/// Scrolling fires a lot of events, the read position is checked at most this often
+const READ_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Clone, Debug)]
 pub struct State {
     pub is_loading: bool,
@@ -28,6 +36,16 @@ pub struct State {
     pub forced_direction: Option<TimelineDirection>,
     pub identifier: String,
     pub known_conversations: Vec<StatusId>,
+    /// The read position when the timeline was loaded
+    pub last_read: Option<StatusId>,
+    /// How many more pages may be loaded to find the read position
+    pub jump_pages: usize,
+    /// The quoting posts whose quoted status was requested
+    pub loading_quotes: HashSet<StatusId>,
+    /// When the visible posts were last checked for the read position
+    pub read_checked: Option<Instant>,
+    /// The stream that updates this timeline, it is closed with the timeline
+    pub stream: Option<Arc<StreamSubscription>>,
 }
 
 pub type ViewStore<'a> = navicula::ViewStore<'a, super::TimelineReducer>;
@@ -42,6 +60,11 @@ pub enum Action {
     ShouldReloadSoft,
     ReloadSoft(bool),
     DataChanged,
+    LoadedQuotes(Vec<(StatusId, Option<Status>)>),
+    JumpToLastRead,
+    FindLastRead,
+    Scrolled,
+    ReadUpTo(StatusId),
     AccountVisibility(AccountId, AccountVisibility),
     Public(PublicAction),
     AppEvent(AppEvent),
@@ -58,6 +81,11 @@ impl std::fmt::Debug for Action {
             Self::ShouldReloadSoft => write!(f, "ShouldReloadSoft"),
             Self::ReloadSoft(arg0) => f.debug_tuple("ReloadSoft").field(arg0).finish(),
             Self::DataChanged => write!(f, "DataChanged"),
+            Self::LoadedQuotes(arg0) => f.debug_tuple("LoadedQuotes").field(&arg0.len()).finish(),
+            Self::JumpToLastRead => write!(f, "JumpToLastRead"),
+            Self::FindLastRead => write!(f, "FindLastRead"),
+            Self::Scrolled => write!(f, "Scrolled"),
+            Self::ReadUpTo(arg0) => f.debug_tuple("ReadUpTo").field(arg0).finish(),
             Self::AccountVisibility(arg0, arg1) => f
                 .debug_tuple("AccountVisibility")
                 .field(arg0)
@@ -89,6 +117,11 @@ impl State {
             forced_direction,
             identifier: Default::default(),
             known_conversations: Vec::new(),
+            last_read: None,
+            jump_pages: 0,
+            loading_quotes: HashSet::new(),
+            read_checked: None,
+            stream: None,
         }
     }
 
@@ -113,14 +146,19 @@ pub fn reduce<'a>(
                 .replace(' ', "-");
             state.identifier = identifier;
 
-            let reload_action = if state.provider.should_auto_reload() {
-                Effect::timer(
+            // Streamed timelines update live, the others are reloaded regularly
+            let reload_action = match state.provider.stream() {
+                Some(kind) if environment.model.supports_streaming() => {
+                    let (subscription, effect) = subscribe_stream(environment, kind);
+                    state.stream = Some(subscription);
+                    effect
+                }
+                _ if state.provider.should_auto_reload() => Effect::timer(
                     Duration::from_secs(45),
                     Action::ShouldReloadSoft,
                     &state.identifier,
-                )
-            } else {
-                Effect::NONE
+                ),
+                _ => Effect::NONE,
             };
 
             Effect::merge3(
@@ -156,6 +194,8 @@ pub fn reduce<'a>(
         }
         Action::LoadData => {
             state.is_loading = true;
+            // Loading moves the markers, so remember where we were
+            state.last_read = state.provider.last_read();
             let ft = state.provider.request_data(None);
             Effect::future(ft, |d| Action::LoadedData(d, false))
         }
@@ -194,34 +234,37 @@ pub fn reduce<'a>(
             state.can_load_more = state
                 .provider
                 .process_new_data(&updates, direction, was_reload);
-            state.posts = state.provider.data(direction);
+            let load_quotes = update_posts(state, environment);
 
             environment.platform.update_menu(window, |config| {
                 config.enable_scroll = true;
             });
 
             if was_reload {
-                return Effect::NONE;
+                return load_quotes;
             }
 
             // if we're supposed to scroll to the newest, have to do some more work
             // this is based on whether we have a scroll id *and* whether the direction
             // is down
             if direction == TimelineDirection::NewestTop {
-                return Effect::NONE;
+                return load_quotes;
             }
             let Some(scroll_id) = possible_scroll else {
-                return Effect::NONE
+                return load_quotes
             };
 
             let dom_id = scroll_id.dom_id();
-            return Effect::ui(format!(
-                r#"
+            return Effect::merge2(
+                load_quotes,
+                Effect::ui(format!(
+                    r#"
                 setTimeout(() => {{
                     document.getElementById("{dom_id}").scrollIntoView({{ behavior: "auto", block: "end" }});
                     }}, 100);
                 "#
-            ));
+                )),
+            );
         }
         Action::LoadedMoreData(result) => {
             state.is_loading_more = false;
@@ -240,17 +283,96 @@ pub fn reduce<'a>(
 
             let direction = state.direction();
             state.can_load_more = state.provider.process_new_data(&batch, direction, false);
-            state.posts = state.provider.data(direction);
+            let load_quotes = update_posts(state, environment);
             environment
                 .storage
                 .with_mutation(|mut s| s.update_account_historical_data(&batch, &direction));
-            Effect::NONE
+            if state.jump_pages > 0 {
+                return Effect::merge2(load_quotes, Effect::action(Action::FindLastRead));
+            }
+            load_quotes
+        }
+        Action::JumpToLastRead => {
+            state.jump_pages = MAX_JUMP_PAGES;
+            Effect::action(Action::FindLastRead)
+        }
+        Action::FindLastRead => {
+            let last_read = state
+                .last_read
+                .clone()
+                .or_else(|| state.provider.last_read());
+            let Some(last_read) = last_read else {
+                state.jump_pages = 0;
+                return Effect::NONE
+            };
+            // The newest post that was already read
+            let found = state
+                .posts
+                .iter()
+                .filter(|p| cmp_ids(&p.id.0, &last_read.0).is_le())
+                .max_by(|a, b| cmp_ids(&a.id.0, &b.id.0));
+            let Some(post) = found else {
+                // Not loaded yet, load older posts until we find it
+                if state.jump_pages == 0 || !state.can_load_more {
+                    state.jump_pages = 0;
+                    return Effect::NONE;
+                }
+                state.jump_pages -= 1;
+                let oldest = state.posts.last().map(|e| e.id.clone());
+                return Effect::action(Action::LoadMoreData(oldest));
+            };
+            state.jump_pages = 0;
+            let id = post.id.dom_id();
+            Effect::ui(format!(
+                "document.getElementById(\"{id}\").scrollIntoView({{ behavior: \"smooth\", block: \"center\" }});"
+            ))
+        }
+        Action::Scrolled => {
+            if !state.provider.moves_home_marker() {
+                return Effect::NONE;
+            }
+            let now = Instant::now();
+            if let Some(checked) = state.read_checked {
+                if now.duration_since(checked) < READ_CHECK_INTERVAL {
+                    return Effect::NONE;
+                }
+            }
+            state.read_checked = Some(now);
+            // The posts that are visible or were scrolled past have been read.
+            // Older posts are above in one direction, newer in the other, either
+            // way the newest of them is the read position
+            let id = &state.identifier;
+            let js = format!(
+                r#"
+                const container = document.getElementById('{id}');
+                if (!container) {{ return []; }}
+                const bottom = container.getBoundingClientRect().bottom;
+                return Array.from(container.querySelectorAll('[id^="status-"]'))
+                    .filter(e => e.getBoundingClientRect().top < bottom)
+                    .map(e => e.id.substring(7));
+                "#
+            );
+            Effect::ui_future(js, |v| {
+                let ids = v.as_array()?;
+                ids.iter()
+                    .filter_map(|id| id.as_str())
+                    .max_by(|a, b| cmp_ids(a, b))
+                    .map(|id| Action::ReadUpTo(StatusId(id.to_string())))
+            })
         }
+        Action::ReadUpTo(id) => move_home_marker(id.0, environment),
         Action::DataChanged => {
-            state.posts = state.provider.data(state.direction());
+            let load_quotes = update_posts(state, environment);
             environment.storage.with(|data| {
                 state.known_conversations = data.conversations.keys().cloned().collect();
             });
+            load_quotes
+        }
+        Action::LoadedQuotes(quotes) => {
+            // Storing them triggers `DataChanged`, which attaches them
+            environment
+                .storage
+                .with_mutation(|mut s| s.add_quotes(&quotes));
             Effect::NONE
         }
         Action::Public(action) => {
@@ -292,6 +414,7 @@ pub fn reduce<'a>(
                     "document.getElementById(\"{id}\").scrollIntoView({{ behavior: \"smooth\", block: \"{direction}\" }});"
                 ));
             }
+            MainMenuEvent::JumpToLastRead => Effect::action(Action::JumpToLastRead),
             MainMenuEvent::Reload => {
                 state.provider.reset();
                 state.posts = Vec::new();
@@ -302,3 +425,85 @@ pub fn reduce<'a>(
         Action::AppEvent(_) => Effect::NONE,
     }
 }
+
+/// Take the posts from the provider and attach the quoted statuses.
+/// The quoted statuses came with the timeline, the others are requested at once
+fn update_posts(state: &mut State, environment: &Environment) -> Effect<'static, Action> {
+    let mut posts = state.provider.data(state.direction());
+    let missing = environment.storage.with(|data| {
+        data.attach_quotes(&mut posts);
+        data.missing_quotes(&posts)
+    });
+    state.posts = posts;
+    // A quote that failed to load is not requested again
+    let missing: Vec<_> = missing
+        .into_iter()
+        .filter(|id| state.loading_quotes.insert(id.clone()))
+        .collect();
+    if missing.is_empty() {
+        return Effect::NONE;
+    }
+    let ids: Vec<_> = missing.iter().map(|id| id.0.clone()).collect();
+    let into_quotes = |quotes: Vec<(String, Option<Status>)>| -> Vec<(StatusId, Option<Status>)> {
+        quotes
+            .into_iter()
+            .map(|(id, quote)| (StatusId(id), quote))
+            .collect()
+    };
+    let found = environment.model.take_quotes(&ids);
+    let rest: Vec<_> = ids
+        .into_iter()
+        .filter(|id| !found.iter().any(|(quoting, _)| quoting == id))
+        .collect();
+    let found = into_quotes(found);
+    if rest.is_empty() {
+        return Effect::action(Action::LoadedQuotes(found));
+    }
+    let model = environment.model.clone();
+    let load = Effect::future(
+        async move {
+            match model.quoted_statuses(rest).await {
+                Ok(quotes) => quotes,
+                Err(e) => {
+                    log::error!("Could not load quotes: {e}");
+                    Vec::new()
+                }
+            }
+        },
+        move |quotes| Action::LoadedQuotes(into_quotes(quotes)),
+    );
+    if found.is_empty() {
+        return load;
+    }
+    Effect::merge2(Effect::action(Action::LoadedQuotes(found)), load)
+}
+
+/// Send the newest post the user has seen as the read position of the home timeline
+fn move_home_marker(newest: String, environment: &Environment) -> Effect<'static, Action> {
+    let moved = environment.storage.with_mutation(|mut storage| {
+        // Never move the marker back, even if another client read further
+        let remote = storage
+            .markers
+            .home
+            .as_ref()
+            .map(|m| m.last_read_id.as_str());
+        let is_read = [storage.home_read_id.as_deref(), remote]
+            .into_iter()
+            .flatten()
+            .any(|id| cmp_ids(&newest, id).is_le());
+        if is_read {
+            return false;
+        }
+        storage.home_read_id = Some(newest.clone());
+        true
+    });
+    if !moved {
+        return Effect::NONE;
+    }
+    let model = environment.model.clone();
+    Effect::fire_forget(async move {
+        if let Err(e) = model.save_markers(Some(newest), None).await {
+            log::error!("Could not save home marker: {e}");
+        }
+    })
+}
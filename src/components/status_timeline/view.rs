@@ -44,6 +44,7 @@ pub fn TimelineComponent<'a>(cx: Scope<'a>, store: ViewStore<'a>) -> Element<'a>
                 div {
                     id: "{store.identifier}",
                     class: "scroll content-cell-container",
+                    onscroll: move |_| store.send(Action::Scrolled),
                     TimelineContents {
                         store: store.clone(),
                         account_settings: account_settings,
@@ -180,6 +181,9 @@ fn AccountProfileHeader<'a>(
                         menu::ContextMenuItem::item(loc!("Open Profile in Browser"), Action::Public(PublicAction::OpenLink(account.url.clone()))),
                         menu::ContextMenuItem::item(loc!("Copy Profile URL"), Action::Public(PublicAction::Copy(account.url.clone()))),
                     ];
+                    if store.last_read.is_some() {
+                        items.push(menu::ContextMenuItem::item(loc!("Jump to Last Read"), Action::JumpToLastRead));
+                    }
                     if let Some(Some(view_settings)) = view_settings {
                         items.push(menu::ContextMenuItem::separator());
                         items.push(menu::ContextMenuItem::submenu(loc!("Show"), vec![
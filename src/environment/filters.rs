@@ -0,0 +1,155 @@
+use chrono::Utc;
+use regex::{Regex, RegexBuilder};
+
+use super::model::Filter;
+use super::types::{FilterAction, FilterContext};
+use crate::view_model::StatusViewModel;
+
+/// The server side filters and local keyword mutes of the active account,
+/// with their keywords compiled into regexes
+#[derive(Clone, Default)]
+pub struct ContentFilter {
+    filters: Vec<(Filter, Regex)>,
+    keyword_mutes: Vec<Regex>,
+}
+
+/// What should happen to a post in a given context
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FilterResult {
+    Show,
+    /// Show behind a warning with the title of the filter
+    Warn(String),
+    Hide,
+}
+
+/// Local keyword mutes are case insensitive regular expressions.
+/// Invalid ones are skipped, the filter editor shows the error
+pub fn keyword_mute_regex(mute: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(mute).case_insensitive(true).build()
+}
+
+/// The pattern of a filter keyword. Like on the server, whole words only
+/// need a word boundary on the sides that end in a word character, so
+/// that e.g. `#tag`, `@user` or `c++` still match
+fn keyword_pattern(keyword: &str, whole_word: bool) -> String {
+    let escaped = regex::escape(keyword);
+    if !whole_word {
+        return escaped;
+    }
+    let boundary = |c: Option<char>| match c {
+        Some(c) if c.is_alphanumeric() || c == '_' => r"\b",
+        _ => "",
+    };
+    let start = boundary(keyword.chars().next());
+    let end = boundary(keyword.chars().last());
+    format!("{start}{escaped}{end}")
+}
+
+impl ContentFilter {
+    pub fn new(filters: &[Filter], keyword_mutes: &[String]) -> Self {
+        let filters = filters
+            .iter()
+            .filter_map(|filter| {
+                let pattern = filter
+                    .keywords
+                    .iter()
+                    .map(|k| keyword_pattern(&k.keyword, k.whole_word))
+                    .collect::<Vec<_>>()
+                    .join("|");
+                if pattern.is_empty() {
+                    return None;
+                }
+                let regex = RegexBuilder::new(&pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| log::error!("Invalid filter {}: {e:?}", filter.title))
+                    .ok()?;
+                Some((filter.clone(), regex))
+            })
+            .collect();
+        let keyword_mutes = keyword_mutes
+            .iter()
+            .filter_map(|mute| {
+                keyword_mute_regex(mute)
+                    .map_err(|e| log::error!("Invalid keyword mute {mute}: {e:?}"))
+                    .ok()
+            })
+            .collect();
+        Self {
+            filters,
+            keyword_mutes,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty() && self.keyword_mutes.is_empty()
+    }
+
+    pub fn check(&self, status: &StatusViewModel, context: FilterContext) -> FilterResult {
+        if self.is_empty() {
+            return FilterResult::Show;
+        }
+        // For boosts, the boosted post is what's visible
+        let status = status.reblog_status.as_deref().unwrap_or(status);
+        let texts = [status.text.as_str(), status.spoiler_text.as_str()];
+        let matches = |regex: &Regex| texts.iter().any(|text| regex.is_match(text));
+
+        if self.keyword_mutes.iter().any(|r| matches(r)) {
+            return FilterResult::Hide;
+        }
+
+        let now = Utc::now();
+        let mut result = FilterResult::Show;
+        for (filter, regex) in self.filters.iter() {
+            if !filter.context.contains(&context)
+                || filter.expires_at.map(|e| e <= now).unwrap_or_default()
+                || !matches(regex)
+            {
+                continue;
+            }
+            match filter.filter_action {
+                FilterAction::Hide => return FilterResult::Hide,
+                FilterAction::Warn => result = FilterResult::Warn(filter.title.clone()),
+            }
+        }
+        result
+    }
+
+    /// Mark the post as filtered. Returns `false` if it should be hidden
+    pub fn apply(&self, status: &mut StatusViewModel, context: FilterContext) -> bool {
+        let filtered = match self.check(status, context) {
+            FilterResult::Show => None,
+            FilterResult::Warn(title) => Some(title),
+            FilterResult::Hide => return false,
+        };
+        // Boosts are displayed with the boosted post
+        if let Some(reblog) = status.reblog_status.as_mut() {
+            reblog.filtered = filtered.clone();
+        }
+        status.filtered = filtered;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(keyword: &str, text: &str) -> bool {
+        Regex::new(&keyword_pattern(keyword, true))
+            .unwrap()
+            .is_match(text)
+    }
+
+    #[test]
+    fn whole_words() {
+        assert!(matches("rust", "I like rust."));
+        assert!(!matches("rust", "trusty"));
+        assert!(matches("#tag", "about #tag today"));
+        assert!(!matches("#tag", "about #tags today"));
+        assert!(matches("@user", "hi @user"));
+        assert!(matches("c++", "written in c++"));
+        assert!(matches("c++", "c++, of course"));
+        assert!(!matches("c++", "objc++"));
+    }
+}
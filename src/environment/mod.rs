@@ -1,3 +1,4 @@
+pub mod filters;
 pub mod menu;
 pub mod storage;
 pub mod types;
@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use navicula::publisher::RefPublisher;
+
+use super::model::Model;
+use crate::environment::storage::Data;
+
+/// The model and the data of one logged in account
+#[derive(Clone)]
+pub struct AccountEnvironment {
+    pub model: Model,
+    pub storage: RefPublisher<Data>,
+}
+
+impl AccountEnvironment {
+    fn new(model: Model) -> Self {
+        Self {
+            model,
+            storage: RefPublisher::default(),
+        }
+    }
+}
+
+/// All accounts the user is logged in with: account-id -> environment.
+/// Cloning shares the underlying accounts.
+#[derive(Clone, Default)]
+pub struct Accounts {
+    entries: Rc<RefCell<Vec<(String, AccountEnvironment)>>>,
+    active: Rc<RefCell<Option<String>>>,
+}
+
+impl Accounts {
+    /// Insert the model for the account `id`. If the account already
+    /// exists, the model is replaced but the data is kept
+    pub fn insert(&self, id: &str, model: Model) -> AccountEnvironment {
+        let mut entries = self.entries.borrow_mut();
+        if let Some((_, entry)) = entries.iter_mut().find(|(i, _)| i == id) {
+            entry.model = model;
+            return entry.clone();
+        }
+        let entry = AccountEnvironment::new(model);
+        entries.push((id.to_string(), entry.clone()));
+        entry
+    }
+
+    pub fn remove(&self, id: &str) -> Option<AccountEnvironment> {
+        let mut entries = self.entries.borrow_mut();
+        let position = entries.iter().position(|(i, _)| i == id)?;
+        let (_, entry) = entries.remove(position);
+        if self.active.borrow().as_deref() == Some(id) {
+            self.active.replace(None);
+        }
+        Some(entry)
+    }
+
+    pub fn get(&self, id: &str) -> Option<AccountEnvironment> {
+        self.entries
+            .borrow()
+            .iter()
+            .find(|(i, _)| i == id)
+            .map(|(_, e)| e.clone())
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        self.entries.borrow().iter().map(|(i, _)| i.clone()).collect()
+    }
+
+    pub fn all(&self) -> Vec<(String, AccountEnvironment)> {
+        self.entries.borrow().clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+
+    pub fn active(&self) -> Option<String> {
+        self.active.borrow().clone()
+    }
+
+    pub(super) fn set_active(&self, id: Option<String>) {
+        self.active.replace(id);
+    }
+}
@@ -0,0 +1,302 @@
+//! Storage for the secrets of the logged in users. `users.json` only
+//! contains the public parts of a `User`, the access tokens and client
+//! secrets are kept in a `CredentialStore`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use base64::{engine::general_purpose, Engine as _};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+
+use super::super::types::{CredentialBackend, User};
+
+const CREDENTIALS_PATH: &str = "credentials.enc";
+const KEY_PATH: &str = "credentials.key";
+/// If set, the credentials file key is derived from this instead of the key file
+const PASSPHRASE_VARIABLE: &str = "EBOU_PASSPHRASE";
+const SALT_LENGTH: usize = 16;
+#[cfg(feature = "secret-service")]
+const KEYRING_SERVICE: &str = "com.stylemac.ebou";
+
+/// The secret parts of a `User`
+#[derive(Default, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Credentials {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+impl Credentials {
+    /// Move the secrets out of the user, leaving empty values behind
+    pub fn take(user: &mut User) -> Self {
+        Self {
+            access_token: std::mem::take(&mut user.token_access_token),
+            refresh_token: user.token_refresh_token.take(),
+            client_id: std::mem::take(&mut user.app_client_id),
+            client_secret: std::mem::take(&mut user.app_client_secret),
+        }
+    }
+
+    pub fn apply(self, user: &mut User) {
+        user.token_access_token = self.access_token;
+        user.token_refresh_token = self.refresh_token;
+        user.app_client_id = self.client_id;
+        user.app_client_secret = self.client_secret;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.access_token.is_empty()
+            && self.refresh_token.is_none()
+            && self.client_id.is_empty()
+            && self.client_secret.is_empty()
+    }
+}
+
+pub trait CredentialStore: Send + Sync {
+    fn get(&self, user_id: &str) -> Result<Option<Credentials>, String>;
+    fn set(&self, user_id: &str, credentials: Credentials) -> Result<(), String>;
+    fn remove(&self, user_id: &str) -> Result<(), String>;
+}
+
+/// The configured store. Falls back to the encrypted file if the
+/// Secret Service support was not compiled in
+pub fn store(backend: CredentialBackend, directory: &Path) -> Arc<dyn CredentialStore> {
+    match backend {
+        CredentialBackend::EncryptedFile => Arc::new(EncryptedFileStore::new(directory)),
+        #[cfg(feature = "secret-service")]
+        CredentialBackend::SecretService => Arc::new(SecretServiceStore),
+        #[cfg(not(feature = "secret-service"))]
+        CredentialBackend::SecretService => {
+            log::error!("Secret Service support is not available, using the encrypted file");
+            Arc::new(EncryptedFileStore::new(directory))
+        }
+    }
+}
+
+enum KeySource {
+    Passphrase(String),
+    /// A random key that is generated on first use
+    KeyFile(PathBuf),
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedFile {
+    /// Only used for keys derived from a passphrase
+    salt: Option<String>,
+    nonce: String,
+    data: String,
+}
+
+/// All credentials in one file, encrypted with ChaCha20-Poly1305
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    key_source: KeySource,
+    /// The decrypted file, loaded on first access
+    entries: Mutex<Option<HashMap<String, Credentials>>>,
+}
+
+impl EncryptedFileStore {
+    pub fn new(directory: &Path) -> Self {
+        let key_source = match std::env::var(PASSPHRASE_VARIABLE) {
+            Ok(passphrase) if !passphrase.is_empty() => KeySource::Passphrase(passphrase),
+            _ => KeySource::KeyFile(directory.join(KEY_PATH)),
+        };
+        Self {
+            path: directory.join(CREDENTIALS_PATH),
+            key_source,
+            entries: Mutex::new(None),
+        }
+    }
+
+    fn with_entries<T>(
+        &self,
+        action: impl FnOnce(&mut HashMap<String, Credentials>) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|e| format!("Credentials Data Error: {e:?}"))?;
+        if entries.is_none() {
+            *entries = Some(self.load()?);
+        }
+        let Some(entries) = entries.as_mut() else {
+            return Err("Credentials Data Error".to_string())
+        };
+        action(entries)
+    }
+
+    fn load(&self) -> Result<HashMap<String, Credentials>, String> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+        let data = std::fs::read(&self.path)
+            .map_err(|e| format!("Could not read {}: {e:?}", self.path.display()))?;
+        let file: EncryptedFile = serde_json::from_slice(&data)
+            .map_err(|e| format!("Could not parse {}: {e:?}", self.path.display()))?;
+        let salt = file.salt.as_deref().map(decode).transpose()?;
+        let key = self.key(salt.as_deref())?;
+        let nonce = decode(&file.nonce)?;
+        let decrypted = ChaCha20Poly1305::new(&key)
+            .decrypt(Nonce::from_slice(&nonce), decode(&file.data)?.as_ref())
+            .map_err(|_| {
+                format!(
+                    "Could not decrypt {}. Is {PASSPHRASE_VARIABLE} correct?",
+                    self.path.display()
+                )
+            })?;
+        serde_json::from_slice(&decrypted)
+            .map_err(|e| format!("Could not parse credentials: {e:?}"))
+    }
+
+    fn save(&self, entries: &HashMap<String, Credentials>) -> Result<(), String> {
+        let salt = match self.key_source {
+            KeySource::Passphrase(_) => {
+                let mut salt = [0u8; SALT_LENGTH];
+                OsRng.fill_bytes(&mut salt);
+                Some(salt.to_vec())
+            }
+            KeySource::KeyFile(_) => None,
+        };
+        let key = self.key(salt.as_deref())?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let data = serde_json::to_vec(entries)
+            .map_err(|e| format!("Could not serialize credentials: {e:?}"))?;
+        let encrypted = ChaCha20Poly1305::new(&key)
+            .encrypt(&nonce, data.as_ref())
+            .map_err(|e| format!("Could not encrypt credentials: {e:?}"))?;
+        let file = EncryptedFile {
+            salt: salt.map(|s| general_purpose::STANDARD.encode(s)),
+            nonce: general_purpose::STANDARD.encode(nonce),
+            data: general_purpose::STANDARD.encode(encrypted),
+        };
+        let data = serde_json::to_vec_pretty(&file)
+            .map_err(|e| format!("Could not serialize credentials: {e:?}"))?;
+        write_private(&self.path, &data)
+    }
+
+    fn key(&self, salt: Option<&[u8]>) -> Result<Key, String> {
+        match &self.key_source {
+            KeySource::Passphrase(passphrase) => {
+                let Some(salt) = salt else {
+                    return Err(format!(
+                        "{} was not encrypted with a passphrase",
+                        self.path.display()
+                    ))
+                };
+                let mut key = Key::default();
+                argon2::Argon2::default()
+                    .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                    .map_err(|e| format!("Could not derive key: {e:?}"))?;
+                Ok(key)
+            }
+            KeySource::KeyFile(path) if path.exists() => {
+                let data = std::fs::read(path)
+                    .map_err(|e| format!("Could not read {}: {e:?}", path.display()))?;
+                if data.len() != Key::default().len() {
+                    return Err(format!("Invalid key in {}", path.display()));
+                }
+                Ok(Key::clone_from_slice(&data))
+            }
+            KeySource::KeyFile(path) => {
+                if self.path.exists() {
+                    return Err(format!("Missing key file {}", path.display()));
+                }
+                let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+                write_private(path, &key)?;
+                Ok(key)
+            }
+        }
+    }
+}
+
+impl CredentialStore for EncryptedFileStore {
+    fn get(&self, user_id: &str) -> Result<Option<Credentials>, String> {
+        self.with_entries(|entries| Ok(entries.get(user_id).cloned()))
+    }
+
+    fn set(&self, user_id: &str, credentials: Credentials) -> Result<(), String> {
+        self.with_entries(|entries| {
+            if entries.get(user_id) == Some(&credentials) {
+                return Ok(());
+            }
+            entries.insert(user_id.to_string(), credentials);
+            self.save(entries)
+        })
+    }
+
+    fn remove(&self, user_id: &str) -> Result<(), String> {
+        self.with_entries(|entries| {
+            if entries.remove(user_id).is_none() {
+                return Ok(());
+            }
+            self.save(entries)
+        })
+    }
+}
+
+/// The credentials of each user as one entry in the system keyring
+#[cfg(feature = "secret-service")]
+pub struct SecretServiceStore;
+
+#[cfg(feature = "secret-service")]
+impl SecretServiceStore {
+    fn entry(user_id: &str) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(KEYRING_SERVICE, user_id)
+            .map_err(|e| format!("Could not open keyring: {e:?}"))
+    }
+}
+
+#[cfg(feature = "secret-service")]
+impl CredentialStore for SecretServiceStore {
+    fn get(&self, user_id: &str) -> Result<Option<Credentials>, String> {
+        match Self::entry(user_id)?.get_password() {
+            Ok(data) => serde_json::from_str(&data)
+                .map(Some)
+                .map_err(|e| format!("Could not parse credentials: {e:?}")),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Could not read from keyring: {e:?}")),
+        }
+    }
+
+    fn set(&self, user_id: &str, credentials: Credentials) -> Result<(), String> {
+        let data = serde_json::to_string(&credentials)
+            .map_err(|e| format!("Could not serialize credentials: {e:?}"))?;
+        Self::entry(user_id)?
+            .set_password(&data)
+            .map_err(|e| format!("Could not write to keyring: {e:?}"))
+    }
+
+    fn remove(&self, user_id: &str) -> Result<(), String> {
+        match Self::entry(user_id)?.delete_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Could not remove from keyring: {e:?}")),
+        }
+    }
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, String> {
+    general_purpose::STANDARD
+        .decode(value)
+        .map_err(|e| format!("Could not decode credentials: {e:?}"))
+}
+
+/// Write a file that only the current user can read
+pub fn write_private(path: &Path, data: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(data))
+        .map_err(|e| format!("Could not write to {}: {e:?}", path.display()))
+}
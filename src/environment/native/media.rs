@@ -0,0 +1,434 @@
+//! Images are processed before they are uploaded: The metadata is removed
+//! and they are scaled and converted to fit the limits of the instance.
+//! Only the metadata is removed from GIFs and videos
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageOutputFormat};
+
+use crate::environment::types::MediaLimits;
+
+/// Animations would get lost when re-encoding them
+const UNPROCESSED_TYPES: &[&str] = &["gif"];
+/// MP4 and QuickTime boxes with metadata, e.g. the location in `©xyz`
+const VIDEO_METADATA_BOXES: &[&[u8; 4]] = &[b"udta", b"meta"];
+/// The boxes that contain the metadata boxes
+const VIDEO_CONTAINER_BOXES: &[&[u8; 4]] = &[b"moov", b"trak"];
+/// The `uuid` box that Adobe uses for XMP metadata
+const XMP_UUID: [u8; 16] = [
+    0xBE, 0x7A, 0xCF, 0xCB, 0x97, 0xA9, 0x42, 0xE8, 0x9C, 0x71, 0x99, 0x94, 0x91, 0xE3, 0xAF, 0xAC,
+];
+/// GIF application extensions that are needed for the animation
+const GIF_ANIMATION_EXTENSIONS: &[&[u8]] = &[b"NETSCAPE2.0", b"ANIMEXTS1.0"];
+/// Lower the JPEG quality down to this before scaling down further
+const MIN_JPEG_QUALITY: u8 = 60;
+
+pub struct ProcessedMedia {
+    pub path: PathBuf,
+    /// The processed image, videos have none
+    pub image: Option<DynamicImage>,
+    /// What was done, to show in the attachment list
+    pub steps: Vec<String>,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Format {
+    Jpeg,
+    Png,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Jpeg => "jpg",
+            Format::Png => "png",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Format::Jpeg => "JPEG",
+            Format::Png => "PNG",
+        }
+    }
+
+    fn mime_type(&self) -> &'static str {
+        match self {
+            Format::Jpeg => "image/jpeg",
+            Format::Png => "image/png",
+        }
+    }
+}
+
+/// Process the image at `path` and write the result into `directory`.
+/// Returns `None` for images that are uploaded unchanged
+pub fn process_image(
+    path: &Path,
+    directory: &Path,
+    limits: &MediaLimits,
+) -> Result<Option<ProcessedMedia>, String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    if UNPROCESSED_TYPES.contains(&extension.as_str()) {
+        return process_gif(path, directory);
+    }
+    let stem = path
+        .file_stem()
+        .and_then(|e| e.to_str())
+        .unwrap_or("image")
+        .to_string();
+    let mut steps = Vec::new();
+
+    // The image crate can't read HEIC
+    let (source, extension) = if matches!(extension.as_str(), "heic" | "heif") {
+        let converted = directory.join(format!("{stem}-converted.jpg"));
+        convert_heic(path, &converted)?;
+        steps.push(format!(
+            crate::loc!("Converted {} to JPEG"),
+            extension.to_uppercase()
+        ));
+        (converted, "jpg".to_string())
+    } else {
+        (path.to_path_buf(), extension)
+    };
+
+    let bytes = std::fs::read(&source).map_err(|e| format!("Could not read image: {e}"))?;
+    let mut image =
+        image::load_from_memory(&bytes).map_err(|e| format!("Could not read image: {e}"))?;
+
+    // Re-encoding drops the metadata, but the orientation has to be kept
+    if let Some(exif) = exif_segment(&bytes) {
+        steps.push(crate::loc!("Removed metadata (location, camera)").to_string());
+        if let Some(orientation) = exif_orientation(exif).filter(|e| *e > 1) {
+            image = apply_orientation(image, orientation);
+        }
+    }
+
+    let (width, height) = (image.width(), image.height());
+    let pixels = width as u64 * height as u64;
+    if limits.image_matrix_limit > 0 && pixels > limits.image_matrix_limit {
+        let scale = (limits.image_matrix_limit as f64 / pixels as f64).sqrt();
+        let new_width = ((width as f64 * scale) as u32).max(1);
+        let new_height = ((height as f64 * scale) as u32).max(1);
+        image = image.resize(new_width, new_height, FilterType::Lanczos3);
+        steps.push(format!(
+            crate::loc!("Resized from {}×{} to {}×{}"),
+            width,
+            height,
+            image.width(),
+            image.height()
+        ));
+    }
+
+    // Keep the format if the instance supports it, otherwise convert it.
+    // Transparency needs a PNG
+    let format = match extension.as_str() {
+        "jpg" | "jpeg" if limits.supports("image/jpeg") => Format::Jpeg,
+        "png" if limits.supports("image/png") => Format::Png,
+        _ if image.color().has_alpha() && limits.supports("image/png") => Format::Png,
+        _ => Format::Jpeg,
+    };
+    let is_same_format = match format {
+        Format::Jpeg => matches!(extension.as_str(), "jpg" | "jpeg"),
+        Format::Png => extension == "png",
+    };
+    if !is_same_format {
+        steps.push(format!(
+            crate::loc!("Converted {} to {}"),
+            extension.to_uppercase(),
+            format.name()
+        ));
+    }
+    if !limits.supports(format.mime_type()) {
+        return Err(format!("The instance doesn't support {}", format.name()));
+    }
+
+    let data = encode_within_limit(&mut image, format, limits.image_size_limit, &mut steps)?;
+    let output = directory.join(format!("{stem}.{}", format.extension()));
+    std::fs::write(&output, data).map_err(|e| format!("Could not write image: {e}"))?;
+
+    Ok(Some(ProcessedMedia {
+        path: output,
+        image: Some(image),
+        steps,
+    }))
+}
+
+/// Remove the comments and the application extensions (e.g. XMP) of a GIF.
+/// Returns `None` if it has none
+fn process_gif(path: &Path, directory: &Path) -> Result<Option<ProcessedMedia>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Could not read image: {e}"))?;
+    let stripped = strip_gif_metadata(&bytes).ok_or("Could not read GIF")?;
+    if stripped.len() == bytes.len() {
+        return Ok(None);
+    }
+    let image =
+        image::load_from_memory(&stripped).map_err(|e| format!("Could not read image: {e}"))?;
+    let output = directory.join(path.file_name().ok_or("Could not read image")?);
+    std::fs::write(&output, stripped).map_err(|e| format!("Could not write image: {e}"))?;
+    Ok(Some(ProcessedMedia {
+        path: output,
+        image: Some(image),
+        steps: vec![crate::loc!("Removed metadata").to_string()],
+    }))
+}
+
+/// Remove the metadata boxes of the MP4 or QuickTime video at `path` and
+/// write the result into `directory`. Returns `None` if it has none
+pub fn process_video(path: &Path, directory: &Path) -> Result<Option<ProcessedMedia>, String> {
+    let mut bytes = std::fs::read(path).map_err(|e| format!("Could not read video: {e}"))?;
+    let length = bytes.len();
+    if !free_video_metadata(&mut bytes, 0, length).ok_or("Could not read video")? {
+        return Ok(None);
+    }
+    let output = directory.join(path.file_name().ok_or("Could not read video")?);
+    std::fs::write(&output, bytes).map_err(|e| format!("Could not write video: {e}"))?;
+    Ok(Some(ProcessedMedia {
+        path: output,
+        image: None,
+        steps: vec![crate::loc!("Removed metadata (location, camera)").to_string()],
+    }))
+}
+
+/// Turn the metadata boxes between `offset` and `end` into `free` boxes,
+/// which players skip. Returns whether there were any
+fn free_video_metadata(bytes: &mut [u8], mut offset: usize, end: usize) -> Option<bool> {
+    let mut found = false;
+    while offset + 8 <= end {
+        let size = u32::from_be_bytes(bytes[offset..offset + 4].try_into().ok()?);
+        let (size, header) = match size {
+            // The box extends to the end of the file
+            0 => (end - offset, 8),
+            1 => {
+                let size = bytes.get(offset + 8..offset + 16)?.try_into().ok()?;
+                (usize::try_from(u64::from_be_bytes(size)).ok()?, 16)
+            }
+            size => (size as usize, 8),
+        };
+        if size < header || offset.checked_add(size)? > end {
+            return None;
+        }
+        let kind = &bytes[offset + 4..offset + 8];
+        let is_xmp = kind == b"uuid"
+            && bytes.get(offset + header..offset + header + 16) == Some(&XMP_UUID[..]);
+        if is_xmp || VIDEO_METADATA_BOXES.iter().any(|k| kind == &k[..]) {
+            bytes[offset + 4..offset + 8].copy_from_slice(b"free");
+            found = true;
+        } else if VIDEO_CONTAINER_BOXES.iter().any(|k| kind == &k[..]) {
+            found |= free_video_metadata(bytes, offset + header, offset + size)?;
+        }
+        offset += size;
+    }
+    Some(found)
+}
+
+/// The GIF without comments and without the application extensions
+/// that aren't needed for the animation
+fn strip_gif_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    if !bytes.starts_with(b"GIF") {
+        return None;
+    }
+    let color_table = |flags: u8| match flags & 0x80 {
+        0 => 0,
+        _ => 3 * (1 << ((flags & 0x07) + 1)),
+    };
+    // Header, logical screen descriptor and global color table
+    let mut offset = 13 + color_table(*bytes.get(10)?);
+    let mut stripped = bytes.get(..offset)?.to_vec();
+    loop {
+        let start = offset;
+        match *bytes.get(offset)? {
+            // Extension
+            0x21 => {
+                let label = *bytes.get(offset + 1)?;
+                offset = skip_gif_sub_blocks(bytes, offset + 2)?;
+                let is_metadata = match label {
+                    0xFE => true,
+                    0xFF => {
+                        let identifier = bytes.get(start + 3..start + 14)?;
+                        !GIF_ANIMATION_EXTENSIONS.contains(&identifier)
+                    }
+                    _ => false,
+                };
+                if !is_metadata {
+                    stripped.extend_from_slice(&bytes[start..offset]);
+                }
+            }
+            // Image descriptor, local color table, LZW code size and data
+            0x2C => {
+                offset += 10 + color_table(*bytes.get(offset + 9)?) + 1;
+                offset = skip_gif_sub_blocks(bytes, offset)?;
+                stripped.extend_from_slice(bytes.get(start..offset)?);
+            }
+            // Trailer
+            0x3B => {
+                stripped.push(0x3B);
+                return Some(stripped);
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// The offset after the sub-blocks starting at `offset`
+fn skip_gif_sub_blocks(bytes: &[u8], mut offset: usize) -> Option<usize> {
+    loop {
+        let length = *bytes.get(offset)? as usize;
+        offset += 1 + length;
+        if length == 0 {
+            return Some(offset);
+        }
+    }
+}
+
+/// Encode the image. If it is too large, the quality is lowered
+/// and then the image is scaled down until it fits
+fn encode_within_limit(
+    image: &mut DynamicImage,
+    format: Format,
+    size_limit: u64,
+    steps: &mut Vec<String>,
+) -> Result<Vec<u8>, String> {
+    let mut quality = 90;
+    let mut was_compressed = false;
+    loop {
+        let data = encode(image, format, quality)?;
+        if size_limit == 0 || data.len() as u64 <= size_limit {
+            if was_compressed {
+                steps.push(format!(
+                    crate::loc!("Compressed to {:.1} MB"),
+                    data.len() as f64 / (1024.0 * 1024.0)
+                ));
+            }
+            return Ok(data);
+        }
+        was_compressed = true;
+        if format == Format::Jpeg && quality > MIN_JPEG_QUALITY {
+            quality -= 10;
+            continue;
+        }
+        if image.width() < 128 || image.height() < 128 {
+            return Err("The image is too large for the instance".to_string());
+        }
+        *image = image.resize(
+            image.width() * 3 / 4,
+            image.height() * 3 / 4,
+            FilterType::Lanczos3,
+        );
+    }
+}
+
+fn encode(image: &DynamicImage, format: Format, quality: u8) -> Result<Vec<u8>, String> {
+    let mut buffer = Cursor::new(Vec::new());
+    let result = match format {
+        // JPEG has no alpha channel
+        Format::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_to(&mut buffer, ImageOutputFormat::Jpeg(quality)),
+        Format::Png => image.write_to(&mut buffer, ImageOutputFormat::Png),
+    };
+    result.map_err(|e| format!("Could not encode image: {e}"))?;
+    Ok(buffer.into_inner())
+}
+
+fn convert_heic(from: &Path, to: &Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("sips");
+        command
+            .args(["-s", "format", "jpeg"])
+            .arg(from)
+            .arg("--out")
+            .arg(to);
+        command
+    };
+
+    // `heif-convert` is part of libheif
+    #[cfg(not(target_os = "macos"))]
+    let mut command = {
+        let mut command = Command::new("heif-convert");
+        command.arg(from).arg(to);
+        command
+    };
+
+    match command.output() {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(format!(
+            "Could not convert HEIC image: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )),
+        Err(e) => Err(format!("Could not convert HEIC image: {e}")),
+    }
+}
+
+/// The TIFF data of the EXIF segment of a JPEG
+fn exif_segment(bytes: &[u8]) -> Option<&[u8]> {
+    if bytes.get(0..2)? != [0xFF, 0xD8] {
+        return None;
+    }
+    let mut offset = 2;
+    while offset + 4 <= bytes.len() {
+        if bytes[offset] != 0xFF {
+            return None;
+        }
+        let marker = bytes[offset + 1];
+        // Start of scan, the image data follows
+        if marker == 0xDA {
+            return None;
+        }
+        let length = u16::from_be_bytes([bytes[offset + 2], bytes[offset + 3]]) as usize;
+        let data = bytes.get(offset + 4..offset + 2 + length)?;
+        if marker == 0xE1 && data.starts_with(b"Exif\0\0") {
+            return Some(&data[6..]);
+        }
+        offset += 2 + length;
+    }
+    None
+}
+
+/// The orientation tag (1-8) of the first IFD
+fn exif_orientation(tiff: &[u8]) -> Option<u16> {
+    let big_endian = match tiff.get(0..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let read16 = |offset: usize| -> Option<u16> {
+        let b = tiff.get(offset..offset + 2)?;
+        Some(match big_endian {
+            true => u16::from_be_bytes([b[0], b[1]]),
+            false => u16::from_le_bytes([b[0], b[1]]),
+        })
+    };
+    let read32 = |offset: usize| -> Option<u32> {
+        let b = tiff.get(offset..offset + 4)?;
+        Some(match big_endian {
+            true => u32::from_be_bytes([b[0], b[1], b[2], b[3]]),
+            false => u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        })
+    };
+    let ifd = read32(4)? as usize;
+    let count = read16(ifd)? as usize;
+    (0..count)
+        .map(|index| ifd + 2 + index * 12)
+        .find(|entry| read16(*entry) == Some(0x0112))
+        .and_then(|entry| read16(entry + 8))
+}
+
+fn apply_orientation(image: DynamicImage, orientation: u16) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
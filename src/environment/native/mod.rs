@@ -1,9 +1,15 @@
+pub mod accounts;
+pub mod credentials;
+pub mod oauth;
 pub mod instances;
 use std::rc::Rc;
 
+pub use accounts::{AccountEnvironment, Accounts};
+
 use flume::{Receiver, Sender};
 pub use instances::Instances;
 
+pub mod media;
 pub mod model;
 pub use model::Model;
 
@@ -30,12 +36,15 @@ use crate::behaviours::{Behaviour, ChangeTextsizeBehaviour};
 
 #[derive(Clone)]
 pub struct Environment {
-    // For multi-account support, have this be a hashmap: account-id -> model
+    /// The model of the active account
     pub model: Model,
     pub repository: Repository,
     pub instances: Instances,
     pub platform: platform::Platform,
+    /// The data of the active account
     pub storage: RefPublisher<Data>,
+    /// All logged in accounts, including the active one
+    pub accounts: Accounts,
 }
 
 impl EnvironmentType for Environment {
@@ -56,6 +65,7 @@ impl Environment {
             instances: Instances::default(),
             platform: platform::Platform::default(),
             storage: RefPublisher::default(),
+            accounts: Accounts::default(),
         }
     }
 
@@ -63,6 +73,46 @@ impl Environment {
         self.model = model;
     }
 
+    /// Add (or replace) the model for an account. This does not
+    /// make the account active
+    pub fn add_account(&mut self, id: &str, model: Model) {
+        self.accounts.insert(id, model);
+    }
+
+    /// Make the account with the given id the active one.
+    /// Returns `false` if there is no such account
+    pub fn switch_account(&mut self, id: &str) -> bool {
+        let Some(account) = self.accounts.get(id) else {
+            return false
+        };
+        self.model = account.model;
+        self.storage = account.storage;
+        self.accounts.set_active(Some(id.to_string()));
+        true
+    }
+
+    /// Remove an account. If it was the active account, the next
+    /// remaining one becomes active. Returns the new active account
+    pub fn remove_account(&mut self, id: &str) -> Option<String> {
+        if let Some(account) = self.accounts.remove(id) {
+            account.model.unsubscribe_streams();
+        }
+        if let Some(active) = self.accounts.active() {
+            return Some(active);
+        }
+        let next = self.accounts.ids().first().cloned();
+        match next {
+            Some(ref next) => {
+                self.switch_account(next);
+            }
+            None => {
+                self.model = Model::default();
+                self.storage = RefPublisher::default();
+            }
+        }
+        next
+    }
+
     pub fn open_url(&self, url: &str) {
         let _ = webbrowser::open(url);
     }
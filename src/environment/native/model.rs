@@ -2,34 +2,69 @@
 pub use megalodon::entities::attachment::*;
 pub use megalodon::entities::{
     notification::NotificationType, Attachment, Card, Context, Emoji, Instance, Notification,
-    Relationship, Status, StatusVisibility, Tag, UploadMedia,
+    Poll, Relationship, ScheduledStatus, Status, StatusVisibility, Tag, UploadMedia,
 };
+pub use megalodon::megalodon::PollOptions;
 use megalodon::megalodon::{
     GetArrayOptions, GetArrayWithSinceOptions, GetListTimelineInputOptions,
-    GetNotificationsInputOptions, GetTimelineOptions, PostStatusOutput, SearchAccountInputOptions,
+    GetNotificationsInputOptions, GetTagTimelineInputOptions, GetTimelineOptions, PostStatusOutput,
+    SaveMarkersInputOptions, SearchAccountInputOptions,
 };
 pub use megalodon::streaming::Message;
-use megalodon::{entities::List, megalodon::AccountFollowersInputOptions};
+use megalodon::megalodon::AccountFollowersInputOptions;
 
 use megalodon::{
     megalodon::{
-        FollowAccountInputOptions, GetAccountStatusesInputOptions, GetTimelineOptionsWithLocal,
-        PostStatusInputOptions, UpdateMediaInputOptions, UploadMediaInputOptions,
+        EditStatusInputOptions, FollowAccountInputOptions, GetAccountStatusesInputOptions,
+        GetTimelineOptionsWithLocal, PostStatusInputOptions, UpdateMediaInputOptions, UploadMediaInputOptions,
     },
     Megalodon,
 };
+use crate::environment::types::{
+    AccountModeration, Backend, FilterAction, FilterContext, FilterInput, ListInput,
+    ListRepliesPolicy, MediaLimits, ReportInput, StreamEvent, StreamKind, StreamState,
+};
 use reqwest::header::HeaderValue;
+use reqwest::Method;
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The delay between reconnect attempts doubles up to this many seconds
+const MAX_STREAM_BACKOFF: u64 = 300;
+/// How long to wait for the health check of the streaming server
+const STREAM_HEALTH_TIMEOUT: u64 = 10;
+/// The quoted statuses that were not shown yet are dropped beyond this
+const MAX_KEPT_QUOTES: usize = 1000;
 
 #[derive(Clone)]
 pub struct Model {
     pub url: String,
+    pub backend: Backend,
     pub has_token: bool,
+    /// Only needed for calls that megalodon doesn't support
+    token: Option<String>,
     client: Arc<Box<dyn Megalodon + Send + Sync>>,
+    /// For the calls that megalodon doesn't support
+    http: reqwest::Client,
     instance: Arc<Mutex<Option<Instance>>>,
+    /// megalodon doesn't know about the media configuration of the instance
+    media_limits: Arc<Mutex<MediaLimits>>,
     is_logged_in: Arc<AtomicBool>,
+    streams: Arc<Mutex<Vec<StreamEntry>>>,
+    /// The statuses quoted by the statuses of the loaded timelines, by the
+    /// id of the quoting status. `None` if the server didn't send it
+    quotes: Arc<Mutex<HashMap<String, Option<Status>>>>,
+}
+
+/// A stream and how many timelines show it
+struct StreamEntry {
+    kind: StreamKind,
+    subscribers: usize,
+    task: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl std::fmt::Debug for Model {
@@ -40,20 +75,41 @@ impl std::fmt::Debug for Model {
 
 impl Default for Model {
     fn default() -> Self {
-        Self::new("http://mastodon.social".to_string(), None)
+        Self::new("http://mastodon.social".to_string(), None, Backend::Mastodon)
     }
 }
 
 impl Model {
-    pub fn new(url: String, token: Option<String>) -> Self {
+    pub fn new(url: String, token: Option<String>, backend: Backend) -> Self {
         let has_token = token.is_some();
-        let client = megalodon::generator(megalodon::SNS::Mastodon, url.clone(), token, None);
+        let sns = match backend {
+            Backend::Pleroma | Backend::Akkoma => megalodon::SNS::Pleroma,
+            _ => megalodon::SNS::Mastodon,
+        };
+        let client = megalodon::generator(sns, url.clone(), token.clone(), None);
         Self {
             url,
+            backend,
             has_token,
+            token,
             client: Arc::new(client),
+            http: reqwest::Client::new(),
             instance: Arc::default(),
+            media_limits: Arc::default(),
             is_logged_in: Arc::new(AtomicBool::new(false)),
+            streams: Arc::default(),
+            quotes: Arc::default(),
+        }
+    }
+
+    /// Detect the server software via nodeinfo. Falls back to Mastodon
+    pub async fn detect_backend(url: &str) -> Backend {
+        match nodeinfo_software(url).await {
+            Ok(software) => Backend::new(&software),
+            Err(e) => {
+                log::error!("Could not detect the server software of {url}: {e}");
+                Backend::Mastodon
+            }
         }
     }
 
@@ -62,7 +118,9 @@ impl Model {
         self.is_logged_in.load(Ordering::SeqCst)
     }
 
-    pub async fn register(&self) -> Result<AppData, String> {
+    /// Register Ebou with the instance. Without a `redirect_uri`, the
+    /// instance displays the code for the user to copy
+    pub async fn register(&self, redirect_uri: Option<String>) -> Result<AppData, String> {
         let scopes = "read read:accounts read:bookmarks read:favourites read:statuses write write:bookmarks write:favourites write:media write:statuses follow".split(' ')
         .map(|e| e.to_string())
         .collect();
@@ -71,6 +129,7 @@ impl Model {
         let options = megalodon::megalodon::AppInputOptions {
             scopes: Some(scopes),
             website: Some("https://terhech.de/ebou".to_string()),
+            redirect_uris: redirect_uri,
             ..Default::default()
         };
 
@@ -86,15 +145,11 @@ impl Model {
         client_id: String,
         client_secret: String,
         code: String,
+        redirect_uri: String,
     ) -> Result<TokenData, String> {
         log::trace!("Authenticate");
         self.client
-            .fetch_access_token(
-                client_id,
-                client_secret,
-                code.trim().to_string(),
-                megalodon::default::NO_REDIRECT.to_string(),
-            )
+            .fetch_access_token(client_id, client_secret, code.trim().to_string(), redirect_uri)
             .await
             .map(TokenData::from)
             .string_error("authenticate")
@@ -103,12 +158,18 @@ impl Model {
     pub async fn login(&self) -> Result<Account, String> {
         let a = self.client.verify_account_credentials();
         let b = self.client.get_instance();
-        let (a, b) = tokio::join!(a, b);
+        let c = self.instance_media_limits();
+        let (a, b, c) = tokio::join!(a, b, c);
 
         let instance = b.map(|e| e.json).string_error("login")?;
 
         let _ = self.instance.lock().map(|mut e| e.replace(instance));
 
+        // Without them, the Mastodon defaults are used
+        if let Ok(limits) = c {
+            let _ = self.media_limits.lock().map(|mut e| *e = limits);
+        }
+
         let response = a
             .map(|e| e.json)
             .map(Account::from)
@@ -125,6 +186,13 @@ impl Model {
         self.instance.lock().ok()?.clone()
     }
 
+    pub fn media_limits(&self) -> MediaLimits {
+        self.media_limits
+            .lock()
+            .map(|e| e.clone())
+            .unwrap_or_default()
+    }
+
     pub async fn logout(
         &self,
         client_id: String,
@@ -141,33 +209,184 @@ impl Model {
         Ok(())
     }
 
-    pub async fn subscribe_user_stream(
+    /// Keep a stream open until it is released as often as it was retained
+    pub fn retain_stream(&self, kind: &StreamKind) {
+        let Ok(mut streams) = self.streams.lock() else {
+            return
+        };
+        match streams.iter_mut().find(|entry| &entry.kind == kind) {
+            Some(entry) => entry.subscribers += 1,
+            None => streams.push(StreamEntry {
+                kind: kind.clone(),
+                subscribers: 1,
+                task: None,
+            }),
+        }
+    }
+
+    /// Close the stream once nobody shows it anymore
+    pub fn release_stream(&self, kind: &StreamKind) {
+        let Ok(mut streams) = self.streams.lock() else {
+            return
+        };
+        let Some(index) = streams.iter().position(|entry| &entry.kind == kind) else {
+            return
+        };
+        streams[index].subscribers = streams[index].subscribers.saturating_sub(1);
+        if streams[index].subscribers == 0 {
+            let entry = streams.remove(index);
+            if let Some(task) = entry.task {
+                log::trace!("Unsubscribe {kind:?}");
+                task.abort();
+            }
+        }
+    }
+
+    /// Listen to a retained stream until it is released. If the connection
+    /// drops, it is restarted with an exponential backoff. A stream that is
+    /// already open is shared
+    pub async fn subscribe_stream(
         &self,
-        sender: Arc<dyn Fn(Message) + Send + Sync>,
+        kind: StreamKind,
+        sender: Arc<dyn Fn(StreamEvent) + Send + Sync>,
     ) -> Result<(), String> {
-        log::trace!("Subscribe");
+        log::trace!("Subscribe {kind:?}");
+        if !self.backend.supports_streaming() {
+            return Err(format!("{} doesn't support streaming", self.backend));
+        }
         let streaming_url = self
             .instance
             .lock()
             .map_err(|e| format!("Poison Error {e}"))?
             .as_ref()
             .and_then(|e| e.urls.as_ref().map(|u| u.streaming_api.clone()))
-            .ok_or("Could not connect to user stream: No streaming_api URL")?;
+            .filter(|url| !url.is_empty())
+            .ok_or("Could not connect to stream: No streaming_api URL")?;
 
-        let client = self.client.user_streaming(streaming_url);
+        let mut streams = self
+            .streams
+            .lock()
+            .map_err(|e| format!("Poison Error {e}"))?;
+        // It may have been released before we got here
+        let Some(entry) = streams.iter_mut().find(|entry| entry.kind == kind) else {
+            return Ok(())
+        };
+        if entry.task.is_some() {
+            return Ok(());
+        }
 
-        // this kinda sucks for multiple accounts?
-        tokio::spawn(async move {
-            client
-                .listen(Box::new(move |message| {
-                    sender(message);
-                }))
-                .await;
-        });
+        let (client, http) = (self.client.clone(), self.http.clone());
+        entry.task = Some(tokio::spawn(async move {
+            let mut attempt: u32 = 0;
+            loop {
+                sender(StreamEvent::State(StreamState::Connecting));
+                let url = streaming_url.clone();
+                let streaming = match &kind {
+                    StreamKind::User => client.user_streaming(url),
+                    StreamKind::Public => client.public_streaming(url),
+                    StreamKind::Local => client.local_streaming(url),
+                    StreamKind::Hashtag(tag) => client.tag_streaming(url, tag.clone()),
+                    StreamKind::List(id) => client.list_streaming(url, id.clone()),
+                };
+
+                // megalodon doesn't tell when the connection is open. It is
+                // open once the streaming server is healthy or something
+                // (e.g. a heartbeat) arrives, whichever comes first
+                let is_open = Arc::new(AtomicBool::new(false));
+                let received = Arc::new(AtomicBool::new(false));
+                let is_reconnect = attempt > 0;
+                let opened = {
+                    let (sender, is_open) = (sender.clone(), is_open.clone());
+                    Arc::new(move || {
+                        if !is_open.swap(true, Ordering::Relaxed) {
+                            sender(StreamEvent::State(StreamState::Connected));
+                            if is_reconnect {
+                                sender(StreamEvent::Reconnected);
+                            }
+                        }
+                    })
+                };
+                let listening = {
+                    let (sender, received, opened) =
+                        (sender.clone(), received.clone(), opened.clone());
+                    streaming.listen(Box::new(move |message| {
+                        received.store(true, Ordering::Relaxed);
+                        opened();
+                        sender(StreamEvent::Message(message));
+                    }))
+                };
+                let checking = async {
+                    if is_streaming_healthy(&http, &streaming_url).await {
+                        opened();
+                    }
+                };
+                // `listen` only returns once the connection is gone
+                tokio::join!(listening, checking);
+
+                // Only a stream that received something really connected
+                if received.load(Ordering::Relaxed) {
+                    attempt = 0;
+                }
+                let delay = 2u64.saturating_pow(attempt).min(MAX_STREAM_BACKOFF);
+                attempt = attempt.saturating_add(1);
+                log::error!("Stream {kind:?} disconnected, reconnecting in {delay}s");
+                sender(StreamEvent::State(StreamState::Reconnecting(delay)));
+                tokio::time::sleep(Duration::from_secs(delay)).await;
+            }
+        }));
 
         Ok(())
     }
 
+    /// Timelines of servers without streaming have to be reloaded instead
+    pub fn supports_streaming(&self) -> bool {
+        if !self.backend.supports_streaming() {
+            return false;
+        }
+        self.instance
+            .lock()
+            .ok()
+            .and_then(|instance| {
+                instance
+                    .as_ref()
+                    .and_then(|e| e.urls.as_ref().map(|u| !u.streaming_api.is_empty()))
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn is_streaming(&self, kind: &StreamKind) -> bool {
+        self.streams
+            .lock()
+            .map(|streams| {
+                streams
+                    .iter()
+                    .any(|entry| &entry.kind == kind && entry.task.is_some())
+            })
+            .unwrap_or_default()
+    }
+
+    /// Stop listening to all streams, e.g. when the account is removed
+    pub fn unsubscribe_streams(&self) {
+        let Ok(mut streams) = self.streams.lock() else {
+            return
+        };
+        for task in streams.drain(..).filter_map(|entry| entry.task) {
+            task.abort();
+        }
+    }
+
+    /// The newest posts of the timeline of a stream, to catch up
+    /// after the stream was disconnected
+    pub async fn stream_timeline(&self, kind: StreamKind) -> Result<Vec<Status>, String> {
+        match kind {
+            StreamKind::User => self.timeline(None, 0).await,
+            StreamKind::Public => self.public_timeline(None).await,
+            StreamKind::Local => self.local_timeline(None).await,
+            StreamKind::Hashtag(tag) => self.tag_timeline(tag, None).await,
+            StreamKind::List(id) => self.list_timeline(id, None, 0).await,
+        }
+    }
+
     /// Keeps all `Status` items in the `self.posts` and returns only the new ones
     /// We load multiple pages of data
     pub async fn timeline(
@@ -181,17 +400,17 @@ impl Model {
         let mut last_page = after;
         let mut all_data = Vec::with_capacity(per_page * total_pages);
         for _ in 0..=total_pages {
+            let mut query = vec![("limit", "40".to_string())];
+            query.extend(last_page.clone().map(|id| ("max_id", id)));
             let options = GetTimelineOptionsWithLocal {
                 limit: Some(40),
                 max_id: last_page,
                 ..Default::default()
             };
-            let response = self
-                .client
-                .get_home_timeline(Some(&options))
-                .await
-                .string_error("timeline")?;
-            let mut data = response.json;
+            let fallback = self.client.get_home_timeline(Some(&options));
+            let mut data = self
+                .timeline_statuses("/api/v1/timelines/home", &query, "timeline", fallback)
+                .await?;
             let Some(last) = data.last() else { break };
             last_page = Some(last.id.clone());
             all_data.append(&mut data);
@@ -208,6 +427,13 @@ impl Model {
         limit: Option<u32>,
     ) -> Result<Vec<Status>, String> {
         log::trace!("User Logout");
+        let mut query = vec![
+            ("exclude_replies", "false".to_string()),
+            ("exclude_reblogs", "false".to_string()),
+        ];
+        query.extend(limit.map(|limit| ("limit", limit.to_string())));
+        query.extend(after.clone().map(|id| ("max_id", id)));
+        query.extend(since.clone().map(|id| ("since_id", id)));
         let options = GetAccountStatusesInputOptions {
             limit,
             max_id: after,
@@ -216,12 +442,10 @@ impl Model {
             exclude_reblogs: Some(false),
             ..Default::default()
         };
-        let response = self
-            .client
-            .get_account_statuses(id.to_string(), Some(&options))
+        let fallback = self.client.get_account_statuses(id.clone(), Some(&options));
+        let path = format!("/api/v1/accounts/{id}/statuses");
+        self.timeline_statuses(&path, &query, "user_timeline", fallback)
             .await
-            .string_error("user_timeline")?;
-        Ok(response.json)
     }
 
     pub async fn single_status(&self, id: String) -> Result<Status, String> {
@@ -233,6 +457,102 @@ impl Model {
             .string_error("single_status")
     }
 
+    /// The text of a status as it was written, to edit or redraft it
+    pub async fn status_source(&self, id: String) -> Result<StatusSource, String> {
+        log::trace!("Status Source {id}");
+        let request = self.api_request(Method::GET, &format!("/api/v1/statuses/{id}/source"));
+        send_request(request, "status_source")
+            .await?
+            .json::<StatusSource>()
+            .await
+            .string_error("status_source")
+    }
+
+    /// Statuses of a timeline. Servers with the Mastodon API are asked
+    /// directly, because megalodon can't parse the quoted statuses they send.
+    /// The quoted statuses are kept until they are taken with `take_quotes`
+    async fn timeline_statuses(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        call: &'static str,
+        fallback: impl Future<
+            Output = Result<megalodon::response::Response<Vec<Status>>, megalodon::error::Error>,
+        >,
+    ) -> Result<Vec<Status>, String> {
+        if !self.backend.has_mastodon_api() {
+            return fallback.await.map(|r| r.json).string_error(call);
+        }
+        let request = self.api_request(Method::GET, path).query(query);
+        let values = send_request(request, call)
+            .await?
+            .json::<Vec<serde_json::Value>>()
+            .await
+            .string_error(call)?;
+        Ok(self.parse_statuses(values))
+    }
+
+    /// Parse the statuses and keep the quoted statuses. Statuses that can't
+    /// be parsed are left out instead of failing the whole page
+    fn parse_statuses(&self, values: Vec<serde_json::Value>) -> Vec<Status> {
+        let mut statuses = Vec::with_capacity(values.len());
+        let mut found = Vec::new();
+        for value in values {
+            let id = value["id"].as_str().unwrap_or_default().to_string();
+            match status_with_quotes(self.backend, value) {
+                Ok((status, mut quotes)) => {
+                    statuses.push(status);
+                    found.append(&mut quotes);
+                }
+                Err(e) => log::error!("Skipping invalid status {id}: {e}"),
+            }
+        }
+        if let Ok(mut quotes) = self.quotes.lock() {
+            if quotes.len() > MAX_KEPT_QUOTES {
+                quotes.clear();
+            }
+            quotes.extend(found);
+        }
+        statuses
+    }
+
+    /// The quoted statuses of these quoting statuses that came with their timeline
+    pub fn take_quotes(&self, ids: &[String]) -> Vec<(String, Option<Status>)> {
+        let Ok(mut quotes) = self.quotes.lock() else {
+            return Vec::new()
+        };
+        ids.iter()
+            .filter_map(|id| quotes.remove_entry(id))
+            .collect()
+    }
+
+    /// The quoted statuses of statuses that didn't come with a timeline,
+    /// e.g. from a stream. They are loaded again in one request
+    pub async fn quoted_statuses(
+        &self,
+        ids: Vec<String>,
+    ) -> Result<Vec<(String, Option<Status>)>, String> {
+        log::trace!("Quoted Statuses {ids:?}");
+        let query: Vec<_> = ids.iter().map(|id| ("id[]", id.clone())).collect();
+        let request = self
+            .api_request(Method::GET, "/api/v1/statuses")
+            .query(&query);
+        let values = send_request(request, "quoted_statuses")
+            .await?
+            .json::<Vec<serde_json::Value>>()
+            .await
+            .string_error("quoted_statuses")?;
+        self.parse_statuses(values);
+        // The statuses that the server doesn't know anymore are not requested again
+        let mut quotes = self.take_quotes(&ids);
+        for id in ids {
+            if !quotes.iter().any(|(quoting, _)| quoting == &id) {
+                quotes.push((id, None));
+            }
+        }
+        Ok(quotes)
+    }
+
     pub async fn status_context(&self, id: String) -> Result<Context, String> {
         log::trace!("Status Context");
         self.client
@@ -262,6 +582,262 @@ impl Model {
             .string_error("search_account")
     }
 
+    /// Search accounts, statuses and hashtags (v2 search). With `resolve`
+    /// the server fetches remote accounts and statuses it doesn't know yet
+    /// (e.g. for pasted urls)
+    pub async fn search(&self, term: String, resolve: bool) -> Result<SearchResults, String> {
+        log::trace!("Search {resolve}");
+        // megalodon requires a type, without one the server returns all kinds at once
+        let request = self.api_request(Method::GET, "/api/v2/search").query(&[
+            ("q", term),
+            ("resolve", resolve.to_string()),
+            ("limit", "40".to_string()),
+        ]);
+        let results = send_request(request, "search")
+            .await?
+            .json::<RawSearchResults>()
+            .await
+            .string_error("search")?;
+        let statuses = results
+            .statuses
+            .into_iter()
+            .filter_map(|value| {
+                status_from_value(self.backend, value)
+                    .map_err(|e| log::error!("Skipping invalid search result: {e}"))
+                    .ok()
+            })
+            .collect();
+        Ok(SearchResults {
+            accounts: results.accounts.into_iter().map(Account::from).collect(),
+            statuses,
+            hashtags: results.hashtags,
+        })
+    }
+
+    /// Mute or block an account or its domain. Returns the new relationship
+    /// for the account (domain blocks don't return one)
+    pub async fn moderate(
+        &self,
+        id: String,
+        domain: Option<String>,
+        moderation: AccountModeration,
+    ) -> Result<Option<Relationship>, String> {
+        let domain = || domain.clone().ok_or_else(|| "No domain".to_string());
+        Ok(Some(match moderation {
+            AccountModeration::Mute {
+                duration,
+                notifications,
+            } => self.mute(id, notifications, duration).await?,
+            AccountModeration::Unmute => self.unmute(id).await?,
+            AccountModeration::Block => self.block(id).await?,
+            AccountModeration::Unblock => self.unblock(id).await?,
+            AccountModeration::BlockDomain => {
+                self.block_domain(domain()?, true).await?;
+                return Ok(None);
+            }
+            AccountModeration::UnblockDomain => {
+                self.block_domain(domain()?, false).await?;
+                return Ok(None);
+            }
+        }))
+    }
+
+    /// Mute an account. `duration` is in seconds, `None` mutes indefinitely
+    pub async fn mute(
+        &self,
+        id: String,
+        notifications: bool,
+        duration: Option<u64>,
+    ) -> Result<Relationship, String> {
+        log::trace!("Mute {notifications} {duration:?}");
+        let Some(duration) = duration else {
+            return self
+                .client
+                .mute_account(id, notifications)
+                .await
+                .map(|e| e.json)
+                .string_error("mute");
+        };
+        // megalodon doesn't support the `duration` parameter
+        let request = self
+            .api_request(Method::POST, &format!("/api/v1/accounts/{id}/mute"))
+            .json(&serde_json::json!({
+                "notifications": notifications,
+                "duration": duration,
+            }));
+        send_request(request, "mute")
+            .await?
+            .json::<Relationship>()
+            .await
+            .string_error("mute")
+    }
+
+    pub async fn unmute(&self, id: String) -> Result<Relationship, String> {
+        log::trace!("Unmute");
+        self.client
+            .unmute_account(id)
+            .await
+            .map(|e| e.json)
+            .string_error("unmute")
+    }
+
+    pub async fn block(&self, id: String) -> Result<Relationship, String> {
+        log::trace!("Block");
+        self.client
+            .block_account(id)
+            .await
+            .map(|e| e.json)
+            .string_error("block")
+    }
+
+    pub async fn unblock(&self, id: String) -> Result<Relationship, String> {
+        log::trace!("Unblock");
+        self.client
+            .unblock_account(id)
+            .await
+            .map(|e| e.json)
+            .string_error("unblock")
+    }
+
+    /// Block (`true`) or unblock (`false`) a whole domain
+    pub async fn block_domain(&self, domain: String, block: bool) -> Result<(), String> {
+        log::trace!("Block Domain {block}");
+        if block {
+            self.client.block_domain(domain).await
+        } else {
+            self.client.unblock_domain(domain).await
+        }
+        .map(|_| ())
+        .string_error("block_domain")
+    }
+
+    pub async fn mutes(&self) -> Result<Vec<Account>, String> {
+        log::trace!("Mutes");
+        self.client
+            .get_mutes(None)
+            .await
+            .map(|e| e.json.into_iter().map(Account::from).collect())
+            .string_error("mutes")
+    }
+
+    pub async fn blocks(&self) -> Result<Vec<Account>, String> {
+        log::trace!("Blocks");
+        self.client
+            .get_blocks(None)
+            .await
+            .map(|e| e.json.into_iter().map(Account::from).collect())
+            .string_error("blocks")
+    }
+
+    pub async fn domain_blocks(&self) -> Result<Vec<String>, String> {
+        log::trace!("Domain Blocks");
+        self.client
+            .get_domain_blocks(None)
+            .await
+            .map(|e| e.json)
+            .string_error("domain_blocks")
+    }
+
+    /// Report an account (and optionally some of its posts) to the moderators
+    pub async fn report(&self, report: ReportInput) -> Result<(), String> {
+        log::trace!("Report {}", report.account_id);
+        // megalodon doesn't support report categories and rules
+        let request = self
+            .api_request(Method::POST, "/api/v1/reports")
+            .json(&report);
+        send_request(request, "report").await.map(|_| ())
+    }
+
+    /// The rules of the instance, required for reports of rule violations
+    pub async fn instance_rules(&self) -> Result<Vec<InstanceRule>, String> {
+        log::trace!("Instance Rules");
+        let request = self.api_request(Method::GET, "/api/v1/instance/rules");
+        send_request(request, "instance_rules")
+            .await?
+            .json::<Vec<InstanceRule>>()
+            .await
+            .string_error("instance_rules")
+    }
+
+    /// The (v2) filters of the account
+    pub async fn filters(&self) -> Result<Vec<Filter>, String> {
+        log::trace!("Filters");
+        // megalodon only supports v1 filters
+        let request = self.api_request(Method::GET, "/api/v2/filters");
+        send_request(request, "filters")
+            .await?
+            .json::<Vec<Filter>>()
+            .await
+            .string_error("filters")
+    }
+
+    /// Create a new filter, or update the filter with the given `id`
+    pub async fn save_filter(
+        &self,
+        id: Option<String>,
+        filter: FilterInput,
+    ) -> Result<Filter, String> {
+        log::trace!("Save Filter {id:?}");
+        let request = match id {
+            Some(id) => self.api_request(Method::PUT, &format!("/api/v2/filters/{id}")),
+            None => self.api_request(Method::POST, "/api/v2/filters"),
+        };
+        send_request(request.json(&filter), "save_filter")
+            .await?
+            .json::<Filter>()
+            .await
+            .string_error("save_filter")
+    }
+
+    pub async fn delete_filter(&self, id: String) -> Result<(), String> {
+        log::trace!("Delete Filter {id}");
+        let request = self.api_request(Method::DELETE, &format!("/api/v2/filters/{id}"));
+        send_request(request, "delete_filter").await.map(|_| ())
+    }
+
+    /// The read positions of the home timeline and the notifications
+    pub async fn markers(&self) -> Result<ServerMarkers, String> {
+        log::trace!("Markers");
+        let markers = self
+            .client
+            .get_markers(vec!["home".to_string(), "notifications".to_string()])
+            .await
+            .string_error("markers")?
+            .json;
+        let convert = |last_read_id: &str, updated_at| ServerMarker {
+            last_read_id: last_read_id.to_string(),
+            updated_at,
+        };
+        Ok(ServerMarkers {
+            home: markers.home.map(|m| convert(&m.last_read_id, m.updated_at)),
+            notifications: markers
+                .notifications
+                .map(|m| convert(&m.last_read_id, m.updated_at)),
+        })
+    }
+
+    /// Move the read positions. `None` keeps the current position
+    pub async fn save_markers(
+        &self,
+        home: Option<String>,
+        notifications: Option<String>,
+    ) -> Result<(), String> {
+        log::trace!("Save Markers {home:?} {notifications:?}");
+        if home.is_none() && notifications.is_none() {
+            return Ok(());
+        }
+        let marker = |last_reading_id| megalodon::megalodon::Marker { last_reading_id };
+        let options = SaveMarkersInputOptions {
+            home: home.map(marker),
+            notifications: notifications.map(marker),
+        };
+        self.client
+            .save_markers(Some(&options))
+            .await
+            .map(|_| ())
+            .string_error("save_markers")
+    }
+
     /// Get the relationship for a single user
     pub async fn relationship(&self, id: String) -> Result<Relationship, String> {
         log::trace!("Relationship");
@@ -274,13 +850,90 @@ impl Model {
         data.ok_or("No relationship found".to_string())
     }
 
-    pub async fn lists(&self) -> Result<Vec<List>, String> {
+    pub async fn lists(&self) -> Result<Vec<AccountList>, String> {
         log::trace!("Lists");
+        // megalodon doesn't know the replies policy and exclusive lists
+        let request = self.api_request(Method::GET, "/api/v1/lists");
+        send_request(request, "lists")
+            .await?
+            .json::<Vec<AccountList>>()
+            .await
+            .string_error("lists")
+    }
+
+    /// Create a new list, or update the list with the given `id`
+    pub async fn save_list(
+        &self,
+        id: Option<String>,
+        list: ListInput,
+    ) -> Result<AccountList, String> {
+        log::trace!("Save List {id:?}");
+        let request = match id {
+            Some(id) => self.api_request(Method::PUT, &format!("/api/v1/lists/{id}")),
+            None => self.api_request(Method::POST, "/api/v1/lists"),
+        };
+        send_request(request.json(&list), "save_list")
+            .await?
+            .json::<AccountList>()
+            .await
+            .string_error("save_list")
+    }
+
+    pub async fn delete_list(&self, id: String) -> Result<(), String> {
+        log::trace!("Delete List {id}");
         self.client
-            .get_lists()
+            .delete_list(id)
             .await
-            .map(|e| e.json)
-            .string_error("search_account")
+            .map(|_| ())
+            .string_error("delete_list")
+    }
+
+    /// The members of a list
+    pub async fn list_accounts(
+        &self,
+        id: String,
+        after: Option<String>,
+    ) -> Result<Vec<Account>, String> {
+        log::trace!("List Accounts {id}");
+        let mut query = vec![("limit", "80".to_string())];
+        if let Some(after) = after {
+            query.push(("max_id", after));
+        }
+        let request = self
+            .api_request(Method::GET, &format!("/api/v1/lists/{id}/accounts"))
+            .query(&query);
+        let response = send_request(request, "list_accounts").await?;
+        let next = parse_lheader(response.headers().get("link"));
+        response
+            .json::<Vec<megalodon::entities::Account>>()
+            .await
+            .map(|accounts| {
+                accounts
+                    .into_iter()
+                    .map(|e| Account::new(e, next.clone()))
+                    .collect()
+            })
+            .string_error("list_accounts")
+    }
+
+    /// Add an account to a list or remove it
+    pub async fn update_list_membership(
+        &self,
+        list_id: String,
+        account_id: String,
+        add: bool,
+    ) -> Result<(), String> {
+        log::trace!("Update List {list_id} {account_id} {add}");
+        let result = if add {
+            self.client
+                .add_accounts_to_list(list_id, vec![account_id])
+                .await
+        } else {
+            self.client
+                .delete_accounts_from_list(list_id, vec![account_id])
+                .await
+        };
+        result.map(|_| ()).string_error("update_list_membership")
     }
 
     pub async fn list_timeline(
@@ -295,17 +948,18 @@ impl Model {
         let mut last_page = after;
         let mut all_data = Vec::with_capacity(per_page * total_pages);
         for _ in 0..=total_pages {
+            let mut query = vec![("limit", "40".to_string())];
+            query.extend(last_page.clone().map(|id| ("max_id", id)));
             let options = GetListTimelineInputOptions {
                 limit: Some(40),
                 max_id: last_page,
                 ..Default::default()
             };
-            let response = self
-                .client
-                .get_list_timeline(id.clone(), Some(&options))
-                .await
-                .string_error("timeline")?;
-            let mut data = response.json;
+            let fallback = self.client.get_list_timeline(id.clone(), Some(&options));
+            let path = format!("/api/v1/timelines/list/{id}");
+            let mut data = self
+                .timeline_statuses(&path, &query, "timeline", fallback)
+                .await?;
             let Some(last) = data.last() else { break };
             last_page = Some(last.id.clone());
             all_data.append(&mut data);
@@ -328,15 +982,6 @@ impl Model {
             let options = GetNotificationsInputOptions {
                 limit: Some(40),
                 max_id: last_page,
-                exclude_types: Some(vec![
-                    NotificationType::Follow,
-                    NotificationType::FollowRequest,
-                    NotificationType::Reblog,
-                    NotificationType::Favourite,
-                    NotificationType::PollVote,
-                    NotificationType::PollExpired,
-                    NotificationType::EmojiReaction,
-                ]),
                 ..Default::default()
             };
             let response = match self
@@ -405,6 +1050,15 @@ impl Model {
             .string_error("upload_media")
     }
 
+    /// Has the server finished processing the attachment. Mastodon answers
+    /// with `206 Partial Content` while it is still processing
+    pub async fn is_media_processed(&self, id: String) -> Result<bool, String> {
+        log::trace!("Media {id}");
+        let request = self.api_request(Method::GET, &format!("/api/v1/media/{id}"));
+        let response = send_request(request, "media").await?;
+        Ok(response.status() != reqwest::StatusCode::PARTIAL_CONTENT)
+    }
+
     pub async fn update_media(
         &self,
         id: String,
@@ -428,13 +1082,21 @@ impl Model {
         in_reply_to_id: Option<String>,
         quote_id: Option<String>,
         visibility: Option<StatusVisibility>,
-    ) -> Result<Status, String> {
+        poll: Option<PollOptions>,
+        spoiler_text: Option<String>,
+        sensitive: Option<bool>,
+        scheduled_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<PostedStatus, String> {
         {
             let options = PostStatusInputOptions {
                 media_ids,
                 in_reply_to_id,
                 quote_id,
                 visibility,
+                poll,
+                spoiler_text,
+                sensitive,
+                scheduled_at,
                 ..Default::default()
             };
             let c = self
@@ -444,15 +1106,90 @@ impl Model {
                 .string_error("post_status")?;
 
             match c.json {
-                PostStatusOutput::Status(s) => Ok(s),
-                // Currently, we don't support scheduled statuses
-                PostStatusOutput::ScheduledStatus(_) => {
-                    Err("Scheduled Status not supported".to_string())
-                }
+                PostStatusOutput::Status(s) => Ok(PostedStatus::Status(s)),
+                PostStatusOutput::ScheduledStatus(s) => Ok(PostedStatus::Scheduled(s)),
             }
         }
     }
 
+    pub async fn edit_status(
+        &self,
+        id: String,
+        status: String,
+        media_ids: Option<Vec<String>>,
+        poll: Option<PollOptions>,
+        spoiler_text: Option<String>,
+        sensitive: Option<bool>,
+    ) -> Result<Status, String> {
+        log::trace!("Edit Status");
+        let options = EditStatusInputOptions {
+            status: Some(status),
+            spoiler_text,
+            sensitive,
+            media_ids,
+            poll,
+        };
+        self.client
+            .edit_status(id, &options)
+            .await
+            .map(|e| e.json)
+            .string_error("edit_status")
+    }
+
+    pub async fn delete_status(&self, id: String) -> Result<(), String> {
+        log::trace!("Delete Status");
+        self.client
+            .delete_status(id)
+            .await
+            .map(|_| ())
+            .string_error("delete_status")
+    }
+
+    pub async fn scheduled_statuses(&self) -> Result<Vec<ScheduledStatus>, String> {
+        log::trace!("Scheduled Statuses");
+        self.client
+            .get_scheduled_statuses(None)
+            .await
+            .map(|e| e.json)
+            .string_error("scheduled_statuses")
+    }
+
+    pub async fn reschedule_status(
+        &self,
+        id: String,
+        scheduled_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<ScheduledStatus, String> {
+        log::trace!("Reschedule Status");
+        self.client
+            .schedule_status(id, Some(scheduled_at))
+            .await
+            .map(|e| e.json)
+            .string_error("reschedule_status")
+    }
+
+    pub async fn cancel_scheduled_status(&self, id: String) -> Result<(), String> {
+        log::trace!("Cancel Scheduled Status");
+        self.client
+            .cancel_scheduled_status(id)
+            .await
+            .map(|_| ())
+            .string_error("cancel_scheduled_status")
+    }
+
+    pub async fn vote_poll(
+        &self,
+        poll_id: String,
+        choices: Vec<u32>,
+        status_id: String,
+    ) -> Result<Poll, String> {
+        log::trace!("Vote Poll");
+        self.client
+            .vote_poll(poll_id, choices, Some(status_id))
+            .await
+            .map(|e| e.json)
+            .string_error("vote_poll")
+    }
+
     pub async fn tag(&self, name: String) -> Result<Tag, String> {
         log::trace!("Tag");
         self.client
@@ -462,6 +1199,46 @@ impl Model {
             .string_error("tag")
     }
 
+    pub async fn followed_tags(&self) -> Result<Vec<Tag>, String> {
+        log::trace!("Followed Tags");
+        self.client
+            .get_followed_tags()
+            .await
+            .map(|e| e.json)
+            .string_error("followed_tags")
+    }
+
+    /// Follow (`true`) or unfollow (`false`) a hashtag
+    pub async fn follow_tag(&self, name: String, follow: bool) -> Result<Tag, String> {
+        log::trace!("Follow Tag {follow}");
+        if follow {
+            self.client.follow_tag(name).await
+        } else {
+            self.client.unfollow_tag(name).await
+        }
+        .map(|e| e.json)
+        .string_error("follow_tag")
+    }
+
+    pub async fn tag_timeline(
+        &self,
+        name: String,
+        after: Option<String>,
+    ) -> Result<Vec<Status>, String> {
+        log::trace!("Tag Timeline");
+        let mut query = vec![("limit", "40".to_string())];
+        query.extend(after.clone().map(|id| ("max_id", id)));
+        let options = GetTagTimelineInputOptions {
+            limit: Some(40),
+            max_id: after,
+            ..Default::default()
+        };
+        let fallback = self.client.get_tag_timeline(name.clone(), Some(&options));
+        let path = format!("/api/v1/timelines/tag/{name}");
+        self.timeline_statuses(&path, &query, "tag_timeline", fallback)
+            .await
+    }
+
     /// returns always true in the Result to distinguish from unfollow (false)
     pub async fn follow(&self, userid: String) -> Result<bool, String> {
         let options = FollowAccountInputOptions {
@@ -531,75 +1308,202 @@ impl Model {
             .string_error("following")
     }
 
+    /// The accounts that want to follow our locked account
+    pub async fn follow_requests(&self, after: Option<String>) -> Result<Vec<Account>, String> {
+        log::trace!("Follow Requests");
+        // megalodon can't page through follow requests
+        let mut query = vec![("limit", "80".to_string())];
+        if let Some(after) = after {
+            query.push(("max_id", after));
+        }
+        let request = self
+            .api_request(Method::GET, "/api/v1/follow_requests")
+            .query(&query);
+        let response = send_request(request, "follow_requests").await?;
+        let next = parse_lheader(response.headers().get("link"));
+        response
+            .json::<Vec<megalodon::entities::Account>>()
+            .await
+            .map(|accounts| {
+                accounts
+                    .into_iter()
+                    .map(|e| Account::new(e, next.clone()))
+                    .collect()
+            })
+            .string_error("follow_requests")
+    }
+
+    /// Accept (`true`) or reject a follow request
+    pub async fn answer_follow_request(&self, id: String, accept: bool) -> Result<(), String> {
+        log::trace!("Answer Follow Request {id} {accept}");
+        let result = if accept {
+            self.client.accept_follow_request(id).await
+        } else {
+            self.client.reject_follow_request(id).await
+        };
+        result.map(|_| ()).string_error("answer_follow_request")
+    }
+
     pub async fn bookmarks(&self, after: Option<String>) -> Result<Vec<Status>, String> {
+        let mut query = vec![("limit", "40".to_string())];
+        query.extend(after.clone().map(|id| ("max_id", id)));
         let options = GetArrayWithSinceOptions {
             limit: Some(40),
             max_id: after,
             ..Default::default()
         };
-        self.client
-            .get_bookmarks(Some(&options))
+        let fallback = self.client.get_bookmarks(Some(&options));
+        self.timeline_statuses("/api/v1/bookmarks", &query, "bookmarks", fallback)
             .await
-            .map(|r| r.json)
-            .string_error("bookmarks")
     }
 
     pub async fn favorites(&self, after: Option<String>) -> Result<Vec<Status>, String> {
+        let mut query = vec![("limit", "40".to_string())];
+        query.extend(after.clone().map(|id| ("max_id", id)));
         let options = GetArrayOptions {
             limit: Some(40),
             max_id: after,
             ..Default::default()
         };
-        self.client
-            .get_favourites(Some(&options))
+        let fallback = self.client.get_favourites(Some(&options));
+        self.timeline_statuses("/api/v1/favourites", &query, "favorites", fallback)
             .await
-            .map(|r| r.json)
-            .string_error("favorites")
     }
 
     pub async fn local_timeline(&self, after: Option<String>) -> Result<Vec<Status>, String> {
         log::trace!("Local Timeline");
+        let mut query = vec![("limit", "40".to_string())];
+        query.extend(after.clone().map(|id| ("max_id", id)));
+        query.push(("local", "true".to_string()));
         let options = GetTimelineOptions {
             limit: Some(40),
             max_id: after,
             ..Default::default()
         };
-        self.client
-            .get_local_timeline(Some(&options))
-            .await
-            .map(|r| r.json)
-            .string_error("local_timeline")
+        let fallback = self.client.get_local_timeline(Some(&options));
+        self.timeline_statuses(
+            "/api/v1/timelines/public",
+            &query,
+            "local_timeline",
+            fallback,
+        )
+        .await
     }
 
     pub async fn public_timeline(&self, after: Option<String>) -> Result<Vec<Status>, String> {
         log::trace!("Public Timeline");
+        let mut query = vec![("limit", "40".to_string())];
+        query.extend(after.clone().map(|id| ("max_id", id)));
         let options = GetTimelineOptions {
             limit: Some(40),
             max_id: after,
             ..Default::default()
         };
-        self.client
-            .get_public_timeline(Some(&options))
+        let fallback = self.client.get_public_timeline(Some(&options));
+        self.timeline_statuses(
+            "/api/v1/timelines/public",
+            &query,
+            "public_timeline",
+            fallback,
+        )
+        .await
+    }
+
+    async fn instance_media_limits(&self) -> Result<MediaLimits, String> {
+        let request = self.api_request(Method::GET, "/api/v1/instance");
+        let mut value = send_request(request, "media_limits")
+            .await?
+            .json::<serde_json::Value>()
             .await
-            .map(|r| r.json)
-            .string_error("public_timeline")
+            .string_error("media_limits")?;
+        serde_json::from_value(value["configuration"]["media_attachments"].take())
+            .string_error("media_limits")
     }
 
-    pub async fn dm_timeline(&self, after: Option<String>) -> Result<Vec<Status>, String> {
-        log::trace!("Public Timeline");
-        let options = GetArrayWithSinceOptions {
-            limit: Some(40),
-            max_id: after,
-            ..Default::default()
-        };
-        self.client
-            .get_conversation_timeline(Some(&options))
+    /// A request for an endpoint that megalodon doesn't support
+    fn api_request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}{path}", self.url.trim_end_matches('/'));
+        self.http
+            .request(method, url)
+            .bearer_auth(self.token.clone().unwrap_or_default())
+    }
+
+    /// The direct message conversations and the `max_id` of the next page
+    pub async fn conversations(
+        &self,
+        max_id: Option<String>,
+    ) -> Result<(Vec<DirectConversation>, Option<String>), String> {
+        log::trace!("Conversations");
+        let mut query = vec![("limit", "40".to_string())];
+        if let Some(max_id) = max_id {
+            query.push(("max_id", max_id));
+        }
+        let request = self
+            .api_request(Method::GET, "/api/v1/conversations")
+            .query(&query);
+        let response = send_request(request, "conversations").await?;
+        let next = parse_lheader(response.headers().get("link"));
+        let conversations = response
+            .json::<Vec<RawConversation>>()
             .await
-            .map(|r| r.json)
-            .string_error("dm_timeline")
+            .string_error("conversations")?
+            .into_iter()
+            .filter_map(|raw| {
+                let last_status = status_from_value(self.backend, raw.last_status?)
+                    .map_err(|e| log::error!("Invalid conversation status: {e}"))
+                    .ok()?;
+                Some(DirectConversation {
+                    id: raw.id,
+                    unread: raw.unread,
+                    last_status,
+                })
+            })
+            .collect();
+        Ok((conversations, next))
+    }
+
+    pub async fn mark_conversation_read(&self, id: String) -> Result<(), String> {
+        log::trace!("Mark Conversation Read {id}");
+        let request = self.api_request(Method::POST, &format!("/api/v1/conversations/{id}/read"));
+        send_request(request, "mark_conversation_read")
+            .await
+            .map(|_| ())
     }
 }
 
+#[derive(Debug, Clone, serde::Deserialize)]
+struct RawConversation {
+    id: String,
+    #[serde(default)]
+    unread: bool,
+    last_status: Option<serde_json::Value>,
+}
+
+/// A thread of direct messages, as the server tracks it
+#[derive(Debug, Clone)]
+pub struct DirectConversation {
+    pub id: String,
+    pub unread: bool,
+    pub last_status: Status,
+}
+
+impl DirectConversation {
+    pub fn new(conversation: megalodon::entities::Conversation) -> Option<Self> {
+        Some(Self {
+            id: conversation.id,
+            unread: conversation.unread,
+            last_status: conversation.last_status?,
+        })
+    }
+}
+
+/// A new status is either posted right away or scheduled for later
+#[derive(Debug, Clone)]
+pub enum PostedStatus {
+    Status(Status),
+    Scheduled(ScheduledStatus),
+}
+
 fn parse_lheader(from: Option<&HeaderValue>) -> Option<String> {
     let o = from?.to_str().ok()?;
     // quick hack. get the max_id= until the '>
@@ -611,6 +1515,107 @@ fn parse_lheader(from: Option<&HeaderValue>) -> Option<String> {
     Some(s.0.to_string())
 }
 
+/// A status that wasn't parsed by megalodon. Servers send the quoted
+/// status in `quote`, megalodon expects a `bool` there
+fn status_from_value(backend: Backend, value: serde_json::Value) -> Result<Status, String> {
+    status_with_quotes(backend, value).map(|(status, _)| status)
+}
+
+/// A status that wasn't parsed by megalodon, with the statuses quoted by
+/// it or by the status it reblogs
+fn status_with_quotes(
+    backend: Backend,
+    mut value: serde_json::Value,
+) -> Result<(Status, Vec<(String, Option<Status>)>), String> {
+    let mut quotes = Vec::new();
+    quotes.extend(take_quote(backend, &mut value));
+    if value["reblog"].is_object() {
+        quotes.extend(take_quote(backend, &mut value["reblog"]));
+    }
+    // megalodon's Pleroma status guesses `quote` from the reblog
+    let (is_quote, reblog_is_quote) = (value["quote"] == true, value["reblog"]["quote"] == true);
+    let mut status = parse_status(backend, value)?;
+    status.quote = is_quote;
+    if let Some(reblog) = status.reblog.as_mut() {
+        reblog.quote = reblog_is_quote;
+    }
+    Ok((status, quotes))
+}
+
+/// Pleroma keeps some fields (e.g. the emoji reactions) in its own `pleroma`
+/// object, only megalodon's Pleroma status knows about them. The other
+/// servers send the Mastodon layout, which megalodon's status follows
+fn parse_status(backend: Backend, value: serde_json::Value) -> Result<Status, String> {
+    match backend {
+        Backend::Pleroma | Backend::Akkoma => {
+            serde_json::from_value::<megalodon::pleroma::entities::Status>(value)
+                .map(Into::into)
+                .string_error("status")
+        }
+        _ => serde_json::from_value(value).string_error("status"),
+    }
+}
+
+/// Replace `quote` with whether this is a quote and return the quoted status.
+/// Mastodon wraps the quoted status, Fedibird and Akkoma embed it and Pleroma
+/// embeds it in its `pleroma` object.
+/// Nothing is returned if the server only says that this is a quote
+fn take_quote(backend: Backend, value: &mut serde_json::Value) -> Option<(String, Option<Status>)> {
+    let mut quote = value["quote"].take();
+    if quote.is_null() && value["pleroma"]["quote"].is_object() {
+        quote = value["pleroma"]["quote"].take();
+    }
+    value["quote"] = serde_json::Value::Bool(quote.is_object() || quote == true);
+    let serde_json::Value::Object(mut quote) = quote else {
+        return None
+    };
+    let id = value["id"].as_str()?.to_string();
+    let quoted = match quote.remove("quoted_status") {
+        Some(inner) => inner,
+        // Mastodon leaves out quotes that were removed or not accepted
+        None if quote.contains_key("state") => serde_json::Value::Null,
+        None => serde_json::Value::Object(quote),
+    };
+    if !quoted.is_object() {
+        return Some((id, None));
+    }
+    let quoted = status_from_value(backend, quoted)
+        .map_err(|e| log::error!("Invalid quoted status of {id}: {e}"))
+        .ok();
+    Some((id, quoted))
+}
+
+/// Whether the streaming server reports that it is up. Servers without
+/// the health endpoint (e.g. Pleroma) are only known to be up once a
+/// message arrives
+async fn is_streaming_healthy(http: &reqwest::Client, streaming_url: &str) -> bool {
+    // `wss://` becomes `https://`
+    let url = format!(
+        "{}/api/v1/streaming/health",
+        streaming_url
+            .trim_end_matches('/')
+            .replacen("ws", "http", 1)
+    );
+    http.get(url)
+        .timeout(Duration::from_secs(STREAM_HEALTH_TIMEOUT))
+        .send()
+        .await
+        .map(|response| response.status().is_success())
+        .unwrap_or_default()
+}
+
+/// Send the request, HTTP error statuses are errors too
+async fn send_request(
+    request: reqwest::RequestBuilder,
+    call: &'static str,
+) -> Result<reqwest::Response, String> {
+    request
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .string_error(call)
+}
+
 trait ResultExt {
     type Output;
     fn string_error(self, call: &'static str) -> Result<Self::Output, String>;
@@ -689,6 +1694,145 @@ impl std::ops::Deref for TokenData {
     }
 }
 
+#[derive(Debug, Clone, serde::Deserialize)]
+struct NodeInfoLinks {
+    links: Vec<NodeInfoLink>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct NodeInfoLink {
+    rel: String,
+    href: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct NodeInfo {
+    software: NodeInfoSoftware,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct NodeInfoSoftware {
+    name: String,
+}
+
+/// The `software.name` of the newest nodeinfo schema of the server
+async fn nodeinfo_software(url: &str) -> Result<String, String> {
+    let well_known = format!("{}/.well-known/nodeinfo", url.trim_end_matches('/'));
+    let links = reqwest::get(well_known)
+        .await
+        .and_then(|r| r.error_for_status())
+        .string_error("nodeinfo")?
+        .json::<NodeInfoLinks>()
+        .await
+        .string_error("nodeinfo")?;
+    let link = links
+        .links
+        .iter()
+        .filter(|link| link.rel.starts_with("http://nodeinfo.diaspora.software/ns/schema/"))
+        .max_by(|a, b| a.rel.cmp(&b.rel))
+        .ok_or("No nodeinfo schema")?;
+    reqwest::get(&link.href)
+        .await
+        .and_then(|r| r.error_for_status())
+        .string_error("nodeinfo")?
+        .json::<NodeInfo>()
+        .await
+        .map(|info| info.software.name)
+        .string_error("nodeinfo")
+}
+
+/// A list with the settings that megalodon doesn't know about
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize)]
+pub struct AccountList {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub replies_policy: ListRepliesPolicy,
+    #[serde(default)]
+    pub exclusive: bool,
+}
+
+/// The plain text of a status, before the server rendered it to HTML
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize)]
+pub struct StatusSource {
+    pub text: String,
+    #[serde(default)]
+    pub spoiler_text: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize)]
+pub struct InstanceRule {
+    pub id: String,
+    pub text: String,
+}
+
+/// A server side (v2) filter
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize)]
+pub struct Filter {
+    pub id: String,
+    pub title: String,
+    pub context: Vec<FilterContext>,
+    pub filter_action: FilterAction,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub keywords: Vec<FilterKeyword>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize)]
+pub struct FilterKeyword {
+    pub id: String,
+    pub keyword: String,
+    pub whole_word: bool,
+}
+
+/// The read positions that are synced through the server
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ServerMarkers {
+    pub home: Option<ServerMarker>,
+    pub notifications: Option<ServerMarker>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ServerMarker {
+    pub last_read_id: String,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl ServerMarker {
+    pub fn new(last_read_id: String) -> Self {
+        Self {
+            last_read_id,
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Has the item with this id been read
+    pub fn covers(&self, id: &str) -> bool {
+        cmp_ids(id, &self.last_read_id).is_le()
+    }
+}
+
+/// Ids are sortable by age. Mastodon uses numeric ids, other servers
+/// use fixed length strings, so shorter ids are always older
+pub fn cmp_ids(a: &str, b: &str) -> std::cmp::Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct RawSearchResults {
+    accounts: Vec<megalodon::entities::Account>,
+    statuses: Vec<serde_json::Value>,
+    hashtags: Vec<Tag>,
+}
+
+/// The results of a search, grouped by kind
+#[derive(Debug, Clone, Default)]
+pub struct SearchResults {
+    pub accounts: Vec<Account>,
+    pub statuses: Vec<Status>,
+    pub hashtags: Vec<Tag>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Account {
     d: megalodon::entities::Account,
@@ -819,3 +1963,100 @@ pub mod mock {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(statuses: &[Status]) -> Vec<String> {
+        statuses.iter().map(|status| status.id.clone()).collect()
+    }
+
+    #[test]
+    fn parses_mastodon_statuses() {
+        let model = Model::new(
+            "https://mastodon.social".to_string(),
+            None,
+            Backend::Mastodon,
+        );
+        let values = serde_json::from_str(include_str!("fixtures/mastodon_statuses.json")).unwrap();
+        let statuses = model.parse_statuses(values);
+
+        // The attachment of the last status has no url, only that status is left out
+        let ids = ids(&statuses);
+        assert_eq!(
+            ids,
+            [
+                "113901298352474385",
+                "113901411846113620",
+                "113901502259981904",
+                "113901533906455011"
+            ]
+        );
+        assert!(!statuses[0].quote);
+        assert!(statuses[1].quote);
+        assert!(statuses[2].quote);
+        let reblog = statuses[3].reblog.as_ref().unwrap();
+        assert!(!reblog.quote);
+        assert_eq!(reblog.poll.as_ref().unwrap().voters_count, Some(17));
+
+        let quotes = model.take_quotes(&ids);
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0].0, "113901411846113620");
+        let quoted = quotes[0].1.as_ref().unwrap();
+        assert_eq!(quoted.id, "113901298352474385");
+        // A quote that wasn't accepted yet comes without the quoted status
+        assert_eq!(quotes[1].0, "113901502259981904");
+        assert!(quotes[1].1.is_none());
+        assert!(model.take_quotes(&ids).is_empty());
+    }
+
+    #[test]
+    fn parses_pleroma_statuses() {
+        let model = Model::new(
+            "https://pleroma.example".to_string(),
+            None,
+            Backend::Pleroma,
+        );
+        let values = serde_json::from_str(include_str!("fixtures/pleroma_statuses.json")).unwrap();
+        let statuses = model.parse_statuses(values);
+
+        let ids = ids(&statuses);
+        assert_eq!(
+            ids,
+            [
+                "AR0MmPbJ0ldd8PsbOa",
+                "AR0Mq7z3Fre0Fr7nRo",
+                "AR0N1IeTwXb1Uu8b9E",
+                "AR0N4ObCiaZtXxDbmK"
+            ]
+        );
+        // Only megalodon's Pleroma status reads the `pleroma` object
+        let plain = &statuses[0];
+        assert!(!plain.quote);
+        assert_eq!(
+            plain.plain_content.as_deref(),
+            Some("Pleroma 2.9 is out :pleroma:")
+        );
+        assert_eq!(plain.emoji_reactions.as_ref().map(|e| e.len()), Some(1));
+        assert!(statuses[1].quote);
+        assert!(statuses[2].quote);
+        // A reblog is no quote, even though the reblogged status is one
+        assert!(!statuses[3].quote);
+        assert!(statuses[3].reblog.as_ref().unwrap().quote);
+
+        // Pleroma embeds the quote in `pleroma`, Akkoma in `quote`
+        let quotes = model.take_quotes(&ids);
+        let quoted: Vec<_> = quotes
+            .iter()
+            .map(|(id, quoted)| (id.as_str(), quoted.as_ref().map(|q| q.id.as_str())))
+            .collect();
+        assert_eq!(
+            quoted,
+            [
+                ("AR0Mq7z3Fre0Fr7nRo", Some("AR0MmPbJ0ldd8PsbOa")),
+                ("AR0N1IeTwXb1Uu8b9E", Some("AR0MmPbJ0ldd8PsbOa")),
+            ]
+        );
+    }
+}
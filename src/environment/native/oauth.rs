@@ -0,0 +1,346 @@
+//! Receives the OAuth authorization code via a redirect to a short-lived
+//! HTTP listener on 127.0.0.1, so that users don't have to copy & paste it.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+const CALLBACK_PATH: &str = "/callback";
+/// How long we wait for the user to authorize Ebou in the browser
+const REDIRECT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// Browsers open connections ahead of time that may never send a request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+#[derive(Clone)]
+pub struct RedirectListener {
+    listener: Arc<TcpListener>,
+    port: u16,
+    /// Sent along with the authorization request, the redirect has to
+    /// return it so that other local pages can't inject a code
+    state: String,
+}
+
+impl std::fmt::Debug for RedirectListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedirectListener")
+            .field("port", &self.port)
+            .finish()
+    }
+}
+
+impl PartialEq for RedirectListener {
+    fn eq(&self, other: &Self) -> bool {
+        self.port == other.port
+    }
+}
+
+impl Eq for RedirectListener {}
+
+impl RedirectListener {
+    /// Listen on a random free port
+    pub async fn bind() -> Result<Self, String> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .map_err(|e| format!("Could not bind redirect listener: {e:?}"))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("Could not bind redirect listener: {e:?}"))?
+            .port();
+        let mut state = [0u8; 16];
+        OsRng.fill_bytes(&mut state);
+        Ok(Self {
+            listener: Arc::new(listener),
+            port,
+            state: state.iter().map(|b| format!("{b:02x}")).collect(),
+        })
+    }
+
+    pub fn redirect_uri(&self) -> String {
+        format!("http://127.0.0.1:{}{CALLBACK_PATH}", self.port)
+    }
+
+    /// The authorization url of the instance with our `state` added
+    pub fn authorize_url(&self, url: &str) -> String {
+        let Ok(mut url) = url::Url::parse(url) else {
+            return url.to_string()
+        };
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(key, _)| key != "state")
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        url.query_pairs_mut()
+            .clear()
+            .extend_pairs(pairs)
+            .append_pair("state", &self.state);
+        url.to_string()
+    }
+
+    /// Wait for the browser to be redirected to us and return the code
+    pub async fn code(&self) -> Result<String, String> {
+        tokio::time::timeout(REDIRECT_TIMEOUT, self.accept_code())
+            .await
+            .map_err(|_| "Timed out waiting for the authorization".to_string())?
+    }
+
+    /// Every connection is handled in its own task, so that connections
+    /// that never send anything don't block the redirect
+    async fn accept_code(&self) -> Result<String, String> {
+        let (sender, mut receiver) = mpsc::channel(1);
+        loop {
+            tokio::select! {
+                connection = self.listener.accept() => {
+                    let (stream, _) =
+                        connection.map_err(|e| format!("Redirect listener error: {e:?}"))?;
+                    let (sender, state) = (sender.clone(), self.state.clone());
+                    tokio::spawn(async move {
+                        if let Some(result) = handle_connection(stream, &state).await {
+                            let _ = sender.send(result).await;
+                        }
+                    });
+                }
+                Some(result) = receiver.recv() => return result,
+            }
+        }
+    }
+}
+
+/// Answer a request. Returns the code, or the error of the authorization,
+/// if this was the redirect
+async fn handle_connection(mut stream: TcpStream, state: &str) -> Option<Result<String, String>> {
+    let path = tokio::time::timeout(READ_TIMEOUT, read_request_path(&mut stream))
+        .await
+        .ok()
+        .flatten();
+    let url = path.and_then(|path| url::Url::parse(&format!("http://127.0.0.1{path}")).ok());
+    let Some(url) = url else {
+        respond(&mut stream, "400 Bad Request", "").await;
+        return None
+    };
+    // Browsers also ask for things like the favicon
+    if url.path() != CALLBACK_PATH {
+        respond(&mut stream, "404 Not Found", "").await;
+        return None;
+    }
+    let parameter = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.to_string())
+    };
+    // Not from our authorization request, keep waiting for the real one
+    if parameter("state").as_deref() != Some(state) {
+        respond(
+            &mut stream,
+            "400 Bad Request",
+            crate::loc!("Invalid authorization state"),
+        )
+        .await;
+        return None;
+    }
+    if let Some(code) = parameter("code") {
+        respond(
+            &mut stream,
+            "200 OK",
+            crate::loc!("Ebou has been authorized. You can close this window."),
+        )
+        .await;
+        return Some(Ok(code));
+    }
+    let error = parameter("error_description")
+        .or_else(|| parameter("error"))
+        .unwrap_or_else(|| "Missing authorization code".to_string());
+    respond(&mut stream, "400 Bad Request", &error).await;
+    Some(Err(error))
+}
+
+/// The path of the request line, e.g. `/callback?code=abc`
+async fn read_request_path(stream: &mut TcpStream) -> Option<String> {
+    let mut data = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !data.windows(4).any(|w| w == b"\r\n\r\n") && data.len() < MAX_REQUEST_SIZE {
+        let read = stream.read(&mut buffer).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        data.extend_from_slice(&buffer[..read]);
+    }
+    let request = String::from_utf8_lossy(&data);
+    let mut parts = request.lines().next()?.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+    parts.next().map(str::to_string)
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Ebou</title></head>\
+         <body style=\"font-family: sans-serif; text-align: center; margin-top: 4em;\">\
+         <p>{}</p></body></html>",
+        escape(message)
+    );
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    if let Err(e) = stream.write_all(response.as_bytes()).await {
+        log::error!("Could not answer redirect: {e:?}");
+    }
+    let _ = stream.shutdown().await;
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An authorization server that redirects every request back to the
+    /// `redirect_uri` with a code, like an instance after the user authorized
+    async fn fake_authorization_server(code: &'static str) -> String {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return
+                };
+                let Some(path) = read_request_path(&mut stream).await else {
+                    continue
+                };
+                let url = url::Url::parse(&format!("http://127.0.0.1{path}")).unwrap();
+                let parameter = |name: &str| {
+                    url.query_pairs()
+                        .find(|(key, _)| key == name)
+                        .map(|(_, value)| value.to_string())
+                        .unwrap_or_default()
+                };
+                let mut location = url::Url::parse(&parameter("redirect_uri")).unwrap();
+                location
+                    .query_pairs_mut()
+                    .append_pair("code", code)
+                    .append_pair("state", &parameter("state"));
+                let response = format!(
+                    "HTTP/1.1 302 Found\r\nLocation: {location}\r\n\
+                     Content-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+        format!("http://127.0.0.1:{port}/oauth/authorize")
+    }
+
+    fn authorize_url(server: &str, listener: &RedirectListener) -> String {
+        let mut url = url::Url::parse(server).unwrap();
+        url.query_pairs_mut()
+            .append_pair("client_id", "ebou")
+            .append_pair("redirect_uri", &listener.redirect_uri())
+            .append_pair("response_type", "code");
+        listener.authorize_url(url.as_str())
+    }
+
+    #[tokio::test]
+    async fn receives_code_through_redirect() {
+        let server = fake_authorization_server("abc").await;
+        let listener = RedirectListener::bind().await.unwrap();
+        let url = authorize_url(&server, &listener);
+        let code = tokio::spawn({
+            let listener = listener.clone();
+            async move { listener.code().await }
+        });
+
+        let response = reqwest::get(url).await.unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(code.await.unwrap(), Ok("abc".to_string()));
+    }
+
+    #[tokio::test]
+    async fn idle_connections_do_not_block_the_redirect() {
+        let server = fake_authorization_server("abc").await;
+        let listener = RedirectListener::bind().await.unwrap();
+        let url = authorize_url(&server, &listener);
+        let code = tokio::spawn({
+            let listener = listener.clone();
+            async move { listener.code().await }
+        });
+
+        // Like a speculative preconnect of a browser
+        let _idle = TcpStream::connect(("127.0.0.1", listener.port))
+            .await
+            .unwrap();
+        let response = tokio::time::timeout(Duration::from_secs(5), reqwest::get(url))
+            .await
+            .expect("The redirect was blocked")
+            .unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(code.await.unwrap(), Ok("abc".to_string()));
+    }
+
+    #[tokio::test]
+    async fn rejects_callbacks_with_a_wrong_state() {
+        let server = fake_authorization_server("abc").await;
+        let listener = RedirectListener::bind().await.unwrap();
+        let code = tokio::spawn({
+            let listener = listener.clone();
+            async move { listener.code().await }
+        });
+
+        let injected = format!("{}?code=evil&state=wrong", listener.redirect_uri());
+        let response = reqwest::get(injected).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+        let missing = format!("{}?code=evil", listener.redirect_uri());
+        let response = reqwest::get(missing).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        // Still waiting for the real redirect
+        let response = reqwest::get(authorize_url(&server, &listener))
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(code.await.unwrap(), Ok("abc".to_string()));
+    }
+
+    #[tokio::test]
+    async fn rejects_tokens_instead_of_codes() {
+        let server = fake_authorization_server("abc").await;
+        let listener = RedirectListener::bind().await.unwrap();
+        let code = tokio::spawn({
+            let listener = listener.clone();
+            async move { listener.code().await }
+        });
+
+        // A Misskey style session token is no replacement for the state
+        let injected = format!("{}?token=evil", listener.redirect_uri());
+        let response = reqwest::get(injected).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        let response = reqwest::get(authorize_url(&server, &listener))
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(code.await.unwrap(), Ok("abc".to_string()));
+    }
+
+    #[tokio::test]
+    async fn authorize_url_replaces_state() {
+        let mut listener = RedirectListener::bind().await.unwrap();
+        listener.state = "ours".to_string();
+        let url = listener.authorize_url("https://example.com/oauth/authorize?client_id=a&state=x");
+        assert_eq!(
+            url,
+            "https://example.com/oauth/authorize?client_id=a&state=ours"
+        );
+    }
+}
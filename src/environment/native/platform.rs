@@ -2,12 +2,14 @@ use dioxus::prelude::Scope;
 use dioxus_desktop::tao::menu::MenuId;
 
 use crate::environment::storage::UiTab;
+use crate::environment::types::MediaLimits;
 
 use super::{super::types::MainMenuEvent, toolbar::ToolbarSelection};
 
 use crate::view_model::{self, AttachmentMedia};
 pub use navicula::types::AppWindow;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use strum::IntoEnumIterator;
 
 #[cfg(target_os = "macos")]
@@ -78,6 +80,20 @@ pub fn open_file(path: impl AsRef<std::path::Path>) {
     }
 }
 
+/// A new directory in the temporary directory for each attachment, so that
+/// attachments with the same file name don't overwrite each other
+fn attachment_directory() -> Option<PathBuf> {
+    static ATTACHMENTS: AtomicU64 = AtomicU64::new(0);
+    let number = ATTACHMENTS.fetch_add(1, Ordering::Relaxed);
+    let name = format!("{}-{number}", chrono::Utc::now().timestamp_millis());
+    let path = temporary_directory()?.join(name);
+    if let Err(e) = std::fs::create_dir_all(&path) {
+        log::error!("Could not create directory: {e:?}");
+        return None;
+    }
+    Some(path)
+}
+
 pub fn temporary_directory() -> Option<std::path::PathBuf> {
     let dir = std::env::temp_dir();
     let folder = "com.stylemac.ebou";
@@ -92,19 +108,25 @@ pub fn temporary_directory() -> Option<std::path::PathBuf> {
 }
 
 use rfd::FileDialog;
-pub fn open_file_dialog(directory: &str) -> Option<view_model::AttachmentMedia> {
-    let file = FileDialog::new()
+pub fn open_file_dialog(directory: &str) -> Option<PathBuf> {
+    FileDialog::new()
         .add_filter("image", SUPPORTED_IMAGE_TYPES)
         .add_filter("video", SUPPORTED_VIDEO_TYPES)
         .set_directory(directory)
-        .pick_file();
-
-    let file = file?;
+        .pick_file()
+}
 
-    read_file_to_attachment(&file)
+/// Ask the user to confirm a destructive action
+pub fn confirm_dialog(title: &str, description: &str) -> bool {
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title(title)
+        .set_description(description)
+        .set_buttons(rfd::MessageButtons::OkCancel)
+        .show()
 }
 
-pub const SUPPORTED_IMAGE_TYPES: &[&str] = &["png", "jpg", "jpeg", "gif"];
+pub const SUPPORTED_IMAGE_TYPES: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "heic", "heif"];
 pub const SUPPORTED_VIDEO_TYPES: &[&str] = &["mp4", "mov"];
 
 /// filter out only the supported types
@@ -112,61 +134,87 @@ pub fn supported_file_types(files: &[PathBuf]) -> Vec<PathBuf> {
     let mut collected = Vec::new();
     for f in files {
         let Some(ext) = f.extension().and_then(|e| e.to_str()) else { continue };
-        if SUPPORTED_IMAGE_TYPES.contains(&ext) || SUPPORTED_VIDEO_TYPES.contains(&ext) {
+        let ext = ext.to_lowercase();
+        if SUPPORTED_IMAGE_TYPES.contains(&ext.as_str())
+            || SUPPORTED_VIDEO_TYPES.contains(&ext.as_str())
+        {
             collected.push(f.clone());
         }
     }
     collected
 }
 
-pub fn read_file_to_attachment(path: &PathBuf) -> Option<view_model::AttachmentMedia> {
-    let is_image = if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-        ["png", "jpeg", "jpg", "gif"].contains(&ext)
-    } else {
-        false
-    };
+/// Images are processed to fit the `limits` of the instance, everything
+/// else is copied unchanged. This takes a while for large images, so it
+/// shouldn't run on the main thread
+pub fn read_file_to_attachment(
+    path: &PathBuf,
+    limits: &MediaLimits,
+) -> Result<view_model::AttachmentMedia, String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let is_image = SUPPORTED_IMAGE_TYPES.contains(&extension.as_str());
+    let is_video = SUPPORTED_VIDEO_TYPES.contains(&extension.as_str());
 
     let name = path
         .file_name()
         .and_then(|e| e.to_str().map(|e| e.to_string()))
         .unwrap_or("unknown".to_string());
 
-    let preview = if is_image {
-        if let Ok(img) = image::open(path) {
-            let resized =
-                image::imageops::resize(&img, 64, 64, image::imageops::FilterType::Lanczos3);
-            let mut buffer = std::io::Cursor::new(Vec::new());
-            if resized
-                .write_to(&mut buffer, image::ImageFormat::Png)
-                .is_ok()
-            {
-                let v = buffer.into_inner();
-
-                use base64::{engine::general_purpose, Engine as _};
-                let string: String = general_purpose::STANDARD_NO_PAD.encode(v);
-                Some(format!("data:image/jpeg;base64, {string}"))
-            } else {
-                None
-            }
-        } else {
-            None
+    let base_path = attachment_directory();
+
+    let processed = match (is_image, is_video, base_path.as_ref()) {
+        (true, _, Some(base_path)) => super::media::process_image(path, base_path, limits),
+        (_, true, Some(base_path)) => super::media::process_video(path, base_path),
+        (false, false, _) => Ok(None),
+        (_, _, None) => Err(crate::loc!("No temporary directory").to_string()),
+    };
+
+    let (processed, processing) = match processed {
+        Ok(Some(mut processed)) => {
+            let steps = std::mem::take(&mut processed.steps);
+            (Some(processed), steps)
+        }
+        Ok(None) => (None, Vec::new()),
+        Err(e) => {
+            // The original would give away the location and the camera
+            log::error!("Could not process {name}: {e}");
+            return Err(format!(
+                crate::loc!("{} was not attached, its metadata could not be removed: {}"),
+                name, e
+            ));
         }
-    } else {
-        None
     };
 
-    // copy the actual file to a temporary place
+    let preview = match processed.as_ref() {
+        Some(processed) => processed.image.as_ref().and_then(image_preview),
+        None if is_image => image::open(path).ok().and_then(|img| image_preview(&img)),
+        None => None,
+    };
+
+    // processed media is already in the temporary directory.
+    // Otherwise, copy the actual file to a temporary place
     // if that fails, just use the current path and hope for the best
-    let new_path = if let Some(base_path) = temporary_directory() {
+    let (new_path, name) = if let Some(processed) = processed {
+        let name = processed
+            .path
+            .file_name()
+            .and_then(|e| e.to_str().map(|e| e.to_string()))
+            .unwrap_or(name);
+        (processed.path, name)
+    } else if let Some(base_path) = base_path {
         let new_path = base_path.join(&name);
         if let Err(e) = std::fs::copy(path, &new_path) {
             log::error!("Could not copy file: {e:?}");
-            path.clone()
+            (path.clone(), name)
         } else {
-            new_path
+            (new_path, name)
         }
     } else {
-        path.clone()
+        (path.clone(), name)
     };
 
     // if the data is an image, provide a preview
@@ -178,8 +226,24 @@ pub fn read_file_to_attachment(path: &PathBuf) -> Option<view_model::AttachmentM
         description: None,
         is_uploaded: false,
         server_id: None,
+        processing,
+        ..Default::default()
     };
-    Some(media)
+    Ok(media)
+}
+
+/// A small base64 preview of an attached image
+fn image_preview(img: &image::DynamicImage) -> Option<String> {
+    let resized = image::imageops::resize(img, 64, 64, image::imageops::FilterType::Lanczos3);
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    resized
+        .write_to(&mut buffer, image::ImageFormat::Png)
+        .ok()?;
+    let v = buffer.into_inner();
+
+    use base64::{engine::general_purpose, Engine as _};
+    let string: String = general_purpose::STANDARD_NO_PAD.encode(v);
+    Some(format!("data:image/jpeg;base64, {string}"))
 }
 
 pub fn copy_to_clipboard(content: impl AsRef<str>) {
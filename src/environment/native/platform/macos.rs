@@ -227,6 +227,22 @@ fn mainmenu(config: MainMenuConfig) -> MenuBar {
             .with_enabled(config.logged_in)
             .with_accelerators(&acc),
     );
+    file_menu.add_native_item(MenuItem::Separator);
+    file_menu.add_item(
+        MenuItemAttributes::new(loc!("Add Account…"))
+            .with_enabled(config.logged_in)
+            .with_id(MainMenuEvent::AddAccount.menu_id()),
+    );
+    let acc = Accelerator::new(
+        Some(ModifiersState::SUPER | ModifiersState::SHIFT),
+        KeyCode::KeyA,
+    );
+    file_menu.add_item(
+        MenuItemAttributes::new(loc!("Switch to Next Account"))
+            .with_enabled(config.logged_in)
+            .with_id(MainMenuEvent::NextAccount.menu_id())
+            .with_accelerators(&acc),
+    );
     file_menu.add_item(
         MenuItemAttributes::new(loc!("Logout"))
             .with_enabled(config.logged_in)
@@ -305,6 +321,13 @@ fn mainmenu(config: MainMenuConfig) -> MenuBar {
             .with_enabled(config.enable_scroll && config.logged_in)
             .with_accelerators(&acc),
     );
+    let acc = Accelerator::new(Some(ModifiersState::SUPER), KeyCode::KeyJ);
+    view_menu.add_item(
+        MenuItemAttributes::new(loc!("Jump to Last Read"))
+            .with_id(MainMenuEvent::JumpToLastRead.menu_id())
+            .with_enabled(config.enable_scroll && config.logged_in)
+            .with_accelerators(&acc),
+    );
 
     view_menu.add_native_item(MenuItem::Separator);
 
@@ -1,9 +1,11 @@
+use crate::environment::storage::CachedData;
 use crate::environment::types::UiConfig;
 use chrono::{DateTime, Utc};
 use navicula::publisher::RefPublisher;
 
 // use lazy_static::__Deref;
-use super::super::types::{Marker, User};
+use super::super::types::{Draft, Marker, User};
+use super::credentials::{self, CredentialStore, Credentials};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::{from_slice, to_string_pretty};
@@ -16,6 +18,8 @@ const USERS_PATH: &str = "users.json";
 const MARKERS_PATH: &str = "markers.json";
 const UICONFIG_PATH: &str = "uiconfig.json";
 const FAVORITES_PATH: &str = "favorites.json";
+const DRAFTS_PATH: &str = "drafts.json";
+const CACHE_DIRECTORY: &str = "cache";
 
 #[derive(Clone)]
 pub struct Repository {
@@ -23,34 +27,55 @@ pub struct Repository {
     markers: Arc<Mutex<Markers>>,
     pub favorites: RefPublisher<HashSet<String>>,
     ui_config: Arc<Mutex<UiConfig>>,
+    drafts: Arc<Mutex<Vec<Draft>>>,
+    credentials: Arc<dyn CredentialStore>,
+    /// Why the secrets of some users could not be read on startup
+    credentials_error: Option<String>,
 }
 
 impl Repository {
     pub fn new() -> Self {
-        let users = read(USERS_PATH)
-            .ok()
-            .flatten().unwrap_or_default();
         let markers = read(MARKERS_PATH)
             .ok()
             .flatten().unwrap_or_default();
-        let ui_config = read(UICONFIG_PATH)
+        let ui_config: UiConfig = read(UICONFIG_PATH)
             .ok()
             .flatten().unwrap_or_default();
         let favorites = read(FAVORITES_PATH).ok().flatten().unwrap_or_default();
+        let drafts = read(DRAFTS_PATH).ok().flatten().unwrap_or_default();
 
-        // Try to write the users. Otherwise fail early
-        write(USERS_PATH, &users)
-            .expect("Expect to be able to write access token and logged in users");
+        let credentials = credentials::store(ui_config.credential_backend, &data_directory());
+        let (users, credentials_error) = read_users(credentials.as_ref());
+        if let Some(ref error) = credentials_error {
+            log::error!("{error}");
+        }
+
+        // This moves the secrets of older `users.json` files into the
+        // credential store. If the store can't be written (e.g. a wrong
+        // passphrase), `users.json` is kept as it is so that the app still
+        // starts and the store can be fixed
+        if let Err(e) = write_users(credentials.as_ref(), &users) {
+            log::error!("Could not write the credentials: {e}");
+        }
 
         Self {
-            users,
+            users: Arc::new(Mutex::new(users)),
             markers,
             // FIXME: Make all items in here RefPublishers!
             favorites: RefPublisher::new(favorites),
-            ui_config,
+            ui_config: Arc::new(Mutex::new(ui_config)),
+            drafts,
+            credentials,
+            credentials_error,
         }
     }
 
+    /// Set if the secrets of some users could not be read, e.g. because
+    /// of a wrong passphrase. These users have to log in again
+    pub fn credentials_error(&self) -> Option<String> {
+        self.credentials_error.clone()
+    }
+
     pub fn update_or_insert_user(&self, new_user: User) -> Result<(), String> {
         let mut users = self
             .users
@@ -69,7 +94,7 @@ impl Repository {
             users.push(new_user);
         }
 
-        if let Err(e) = write(USERS_PATH, users.deref()) {
+        if let Err(e) = write_users(self.credentials.as_ref(), users.deref()) {
             log::error!("Could not save users: {e:?}");
         }
 
@@ -81,19 +106,53 @@ impl Repository {
             .users
             .lock()
             .map_err(|e| format!("Accounts Data Error: {e:?}"))?;
-        let Some(id) = users.iter().position(|user| user.id == id) else {
+        let Some(position) = users.iter().position(|user| user.id == id) else {
             return Err(format!("Unknown User {id}"))
         };
 
-        users.remove(id);
+        users.remove(position);
+        self.remove_cache(&id);
+        if let Err(e) = self.credentials.remove(&id) {
+            log::error!("Could not remove credentials: {e:?}");
+        }
+
+        if let Err(e) = write_users(self.credentials.as_ref(), users.deref()) {
+            log::error!("Could not save users: {e:?}");
+        }
+
+        Ok(())
+    }
+
+    /// Mark the user as the most recently used one so that it is
+    /// selected on the next start
+    pub fn set_active_user(&self, id: &str) -> Result<(), String> {
+        let mut users = self
+            .users
+            .lock()
+            .map_err(|e| format!("Accounts Data Error: {e:?}"))?;
+        let Some(user) = users.iter_mut().find(|user| user.id == id) else {
+            return Err(format!("Unknown User {id}"))
+        };
+        user.last_login = Utc::now();
 
-        if let Err(e) = write(USERS_PATH, users.deref()) {
+        if let Err(e) = write_users(self.credentials.as_ref(), users.deref()) {
             log::error!("Could not save users: {e:?}");
         }
 
         Ok(())
     }
 
+    /// The user that was used most recently and can still log in
+    pub fn active_user(&self) -> Option<User> {
+        self.users
+            .lock()
+            .ok()?
+            .iter()
+            .filter(|user| !user.token_access_token.is_empty())
+            .max_by_key(|user| user.last_login)
+            .cloned()
+    }
+
     pub fn users(&self) -> Result<Vec<User>, String> {
         Ok(self
             .users
@@ -180,6 +239,90 @@ impl Repository {
         Ok(self.favorites.with(|favs| favs.contains(id)))
     }
 
+    /// The drafts of an account, newest first
+    pub fn drafts(&self, account_id: &str) -> Vec<Draft> {
+        let Ok(drafts) = self.drafts.lock() else {
+            return Vec::new()
+        };
+        let mut drafts: Vec<_> = drafts
+            .iter()
+            .filter(|e| e.account_id == account_id)
+            .cloned()
+            .collect();
+        drafts.sort_by(|a, b| b.updated.cmp(&a.updated));
+        drafts
+    }
+
+    pub fn draft(&self, id: &str) -> Option<Draft> {
+        self.drafts
+            .lock()
+            .ok()?
+            .iter()
+            .find(|e| e.id == id)
+            .cloned()
+    }
+
+    pub fn save_draft(&self, draft: Draft) -> Result<(), String> {
+        let mut drafts = self
+            .drafts
+            .lock()
+            .map_err(|e| format!("Drafts Data Error: {e:?}"))?;
+        match drafts.iter_mut().find(|e| e.id == draft.id) {
+            Some(existing) => *existing = draft,
+            None => drafts.push(draft),
+        }
+
+        if let Err(e) = write(DRAFTS_PATH, drafts.deref()) {
+            log::error!("Could not save drafts: {e:?}");
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_draft(&self, id: &str) -> Result<(), String> {
+        let mut drafts = self
+            .drafts
+            .lock()
+            .map_err(|e| format!("Drafts Data Error: {e:?}"))?;
+        drafts.retain(|e| e.id != id);
+
+        if let Err(e) = write(DRAFTS_PATH, drafts.deref()) {
+            log::error!("Could not save drafts: {e:?}");
+        }
+
+        Ok(())
+    }
+
+    /// The cached data of an account from a previous launch
+    pub fn cache(&self, account_id: &str) -> Option<CachedData> {
+        read(&cache_path(account_id))
+            .map_err(|e| log::error!("Could not read cache: {e:?}"))
+            .ok()
+            .flatten()
+    }
+
+    pub fn save_cache(&self, account_id: &str, cache: &CachedData) -> Result<(), String> {
+        let directory = data_directory().join(CACHE_DIRECTORY);
+        if !directory.exists() {
+            std::fs::create_dir_all(&directory)
+                .map_err(|e| format!("Could not create {}: {e:?}", directory.display()))?;
+        }
+        // The cache is not encrypted, it only holds posts that the account
+        // can already read, but it should still only be readable by the user
+        let path = data_directory().join(cache_path(account_id));
+        let data = serde_json::to_vec(cache).map_err(|e| format!("Could not parse value:{e:?}"))?;
+        credentials::write_private(&path, &data)
+    }
+
+    pub fn remove_cache(&self, account_id: &str) {
+        let path = data_directory().join(cache_path(account_id));
+        if path.exists() {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log::error!("Could not remove cache {}: {e:?}", path.display());
+            }
+        }
+    }
+
     pub fn map_config<T>(
         &self,
         action: impl FnOnce(&mut MutexGuard<UiConfig>) -> T,
@@ -224,6 +367,55 @@ fn write<T: Serialize>(name: &str, value: &T) -> Result<(), String> {
     Ok(())
 }
 
+/// The users with the secrets from the credential store. Users whose
+/// secrets can't be read are kept without them, so that they aren't
+/// lost, and the reason is returned
+fn read_users(credentials: &dyn CredentialStore) -> (Vec<User>, Option<String>) {
+    let mut users: Vec<User> = read(USERS_PATH).ok().flatten().unwrap_or_default();
+    let mut errors = Vec::new();
+    for user in users.iter_mut() {
+        // Older versions kept the secrets in `users.json`
+        let legacy = Credentials::take(user);
+        if !legacy.is_empty() {
+            legacy.apply(user);
+            continue;
+        }
+        match credentials.get(&user.id) {
+            Ok(Some(stored)) => stored.apply(user),
+            Ok(None) => errors.push(format!("No credentials for {}", user.name)),
+            Err(e) => errors.push(e),
+        }
+    }
+    errors.dedup();
+    let error = (!errors.is_empty()).then(|| {
+        format!(
+            "{} {}",
+            crate::loc!("Could not read the saved logins, please log in again:"),
+            errors.join(", ")
+        )
+    });
+    (users, error)
+}
+
+/// Write the users without their secrets, those go into the credential store.
+/// Nothing is written if a secret can't be stored, so no secret gets lost
+fn write_users(credentials: &dyn CredentialStore, users: &[User]) -> Result<(), String> {
+    let mut public_users = Vec::with_capacity(users.len());
+    for user in users {
+        let mut user = user.clone();
+        let secrets = Credentials::take(&mut user);
+        if !secrets.is_empty() {
+            credentials.set(&user.id, secrets)?;
+        }
+        public_users.push(user);
+    }
+    write(USERS_PATH, &public_users)
+}
+
+fn cache_path(account_id: &str) -> String {
+    format!("{CACHE_DIRECTORY}/{account_id}.json")
+}
+
 fn data_directory() -> PathBuf {
     use directories_next::ProjectDirs;
     if let Some(proj_dirs) = ProjectDirs::from("com", "stylemac", "ebou") {
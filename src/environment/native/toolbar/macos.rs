@@ -75,10 +75,10 @@ impl LoggedInToolbar {
         let image = Image::with_contents_of_url(url);
         let mut account_item = ToolbarItem::new(ACCOUNT_BUTTON);
         account_item.set_image(image);
-        // let cloned = sender.clone();
+        // Tapping the profile cycles through the logged in accounts
+        let cloned = sender.clone();
         account_item.set_action(move |_| {
-            // For now, for profile tap, we go to our toots
-            // (*cloned)(AppEvent::MenuEvent(MainMenuEvent::YourToots));
+            (*cloned)(AppEvent::MenuEvent(MainMenuEvent::NextAccount));
         });
 
         let nicon = if has_notifications {
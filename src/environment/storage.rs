@@ -3,12 +3,17 @@ use std::collections::HashSet;
 use chrono::{DateTime, Utc};
 use im::HashMap;
 use megalodon::streaming::Message;
+use serde::{Deserialize, Serialize};
 
 use crate::components::conversation::Conversation;
-use crate::environment::model::{Account, Notification, Status};
+use crate::environment::model::{
+    Account, DirectConversation, Notification, NotificationType, ServerMarkers, Status,
+};
+use crate::environment::types::Visibility;
 use crate::view_model::*;
 
-use super::types::TimelineDirection;
+use super::filters::ContentFilter;
+use super::types::{AccountModeration, FilterContext, StreamKind, StreamState, TimelineDirection};
 
 const LOCAL_TIMELINE_KEY: &str = "";
 
@@ -47,8 +52,16 @@ pub struct Data {
     pub conversations: im::HashMap<StatusId, Conversation>,
     pub notification_accounts: im::Vector<AccountUpdateViewModel>,
     pub notification_posts: im::HashMap<AccountId, Vec<NotificationViewModel>>,
+    /// Boosts, favourites, follows, etc. Newest first
+    pub notification_groups: im::Vector<NotificationGroupViewModel>,
 
     pub selected_notifications: Option<AccountViewModel>,
+    pub selected_notification_group: Option<String>,
+
+    pub direct_conversations: im::Vector<DirectConversationViewModel>,
+    pub direct_posts: im::HashMap<String, Vec<StatusViewModel>>,
+    /// The id of the selected direct conversation
+    pub selected_direct_conversation: Option<String>,
 
     // The content of different lists.
     pub timelines: HashMap<String, TimelineEntry>,
@@ -64,9 +77,42 @@ pub struct Data {
     pub public_timeline: Vec<StatusViewModel>,
     pub classic_timeline: Vec<StatusViewModel>,
     pub account_timeline: im::HashMap<AccountId, Vec<StatusViewModel>>,
+    /// Hashtag timelines, keyed by the tag name (without `#`)
+    pub tag_timelines: im::HashMap<String, Vec<StatusViewModel>>,
+    /// The hashtag that should be shown in the hashtags section
+    pub selected_hashtag: Option<String>,
+
+    /// The list that should be created or edited in the lists section
+    pub list_request: Option<ListRequest>,
+
+    /// The accounts that asked to follow us
+    pub follow_requests: Vec<AccountViewModel>,
+
+    /// The statuses quoted by other statuses, keyed by the quoting status.
+    /// `None` if the quoted status could not be found
+    pub quotes: im::HashMap<StatusId, Option<StatusViewModel>>,
+
+    /// The term that should be searched in the search section
+    pub search_term: Option<String>,
+    pub search_results: Option<SearchResultsViewModel>,
 
     // did we load the maximum history for something?
     pub accounts_no_older_data: im::HashSet<AccountId>,
+
+    /// The filters and keyword mutes that are applied to new posts
+    pub content_filter: ContentFilter,
+
+    /// The read positions of the home timeline and the notifications,
+    /// as other clients of the account left them
+    pub markers: ServerMarkers,
+    /// The newest home post that was loaded here, which is the read
+    /// position this client sends to the server
+    pub home_read_id: Option<String>,
+
+    /// Loading the timeline failed, what is shown comes from the cache
+    pub is_offline: bool,
+    /// The connection of the user stream
+    pub stream_state: StreamState,
 }
 
 impl std::fmt::Debug for Data {
@@ -80,13 +126,24 @@ impl std::fmt::Debug for Data {
             .field("conversations", &self.conversations.len())
             .field("notification_accounts", &self.notification_accounts.len())
             .field("notification_posts", &self.notification_posts.len())
+            .field("notification_groups", &self.notification_groups.len())
             .field(
                 "selected_notifications",
                 &self.selected_notifications.as_ref().map(|e| &e.id),
             )
+            .field("direct_conversations", &self.direct_conversations.len())
+            .field(
+                "selected_direct_conversation",
+                &self.selected_direct_conversation,
+            )
             .field("timelines", &self.timelines)
             .field("active_tab", &self.active_tab)
             .field("bookmarks", &self.bookmarks.len())
+            .field("tag_timelines", &self.tag_timelines.len())
+            .field("selected_hashtag", &self.selected_hashtag)
+            .field("follow_requests", &self.follow_requests.len())
+            .field("quotes", &self.quotes.len())
+            .field("search_term", &self.search_term)
             .field("accounts_no_older_data", &self.accounts_no_older_data)
             .finish()
     }
@@ -108,7 +165,12 @@ impl Default for Data {
             conversations: Default::default(),
             notification_accounts: Default::default(),
             notification_posts: Default::default(),
+            notification_groups: Default::default(),
             selected_notifications: Default::default(),
+            selected_notification_group: Default::default(),
+            direct_conversations: Default::default(),
+            direct_posts: Default::default(),
+            selected_direct_conversation: Default::default(),
             timelines,
             active_tab: Default::default(),
             bookmarks: Default::default(),
@@ -117,11 +179,31 @@ impl Default for Data {
             public_timeline: Default::default(),
             classic_timeline: Default::default(),
             account_timeline: Default::default(),
+            tag_timelines: Default::default(),
+            selected_hashtag: Default::default(),
+            list_request: Default::default(),
+            follow_requests: Default::default(),
+            quotes: Default::default(),
+            search_term: Default::default(),
+            search_results: Default::default(),
             accounts_no_older_data: Default::default(),
+            content_filter: Default::default(),
+            markers: Default::default(),
+            home_read_id: Default::default(),
+            is_offline: Default::default(),
+            stream_state: Default::default(),
         }
     }
 }
 
+/// Lists are edited in the more section, but the sidebar can ask for it
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ListRequest {
+    New,
+    /// The id of the list
+    Edit(String),
+}
+
 #[derive(Clone, Eq, PartialEq, Default)]
 pub struct TimelineEntry {
     pub title: String,
@@ -144,6 +226,70 @@ impl std::fmt::Debug for TimelineEntry {
     }
 }
 
+/// The part of `Data` that is kept on disk between launches, so that
+/// the app has content right away and can be read offline.
+/// It is stored unencrypted, which is why direct messages are left out
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CachedData {
+    pub saved: DateTime<Utc>,
+    pub entries: im::Vector<AccountUpdateViewModel>,
+    pub posts: HashMap<AccountId, Vec<StatusViewModel>>,
+    pub classic_timeline: Vec<StatusViewModel>,
+    pub account_timeline: im::HashMap<AccountId, Vec<StatusViewModel>>,
+    pub conversations: im::HashMap<StatusId, Conversation>,
+    pub notification_accounts: im::Vector<AccountUpdateViewModel>,
+    pub notification_posts: im::HashMap<AccountId, Vec<NotificationViewModel>>,
+    pub notification_groups: im::Vector<NotificationGroupViewModel>,
+}
+
+impl CachedData {
+    pub fn is_empty(&self) -> bool {
+        self.posts.is_empty()
+            && self.classic_timeline.is_empty()
+            && self.notification_posts.is_empty()
+            && self.notification_groups.is_empty()
+    }
+
+    /// Remove everything that was posted before `since`. Direct messages
+    /// are never kept on disk
+    pub fn retain_since(&mut self, since: DateTime<Utc>) {
+        let keep =
+            |p: &StatusViewModel| p.created >= since && p.visibility != Some(Visibility::Direct);
+        for (_, posts) in self.posts.iter_mut() {
+            posts.retain(keep);
+        }
+        self.posts.retain(|_, posts| !posts.is_empty());
+        let posts = &self.posts;
+        self.entries.retain(|e| posts.contains_key(&e.id));
+
+        self.classic_timeline.retain(keep);
+        for (_, posts) in self.account_timeline.iter_mut() {
+            posts.retain(keep);
+        }
+        self.account_timeline.retain(|_, posts| !posts.is_empty());
+        self.conversations.retain(|_, c| {
+            c.root().map(|r| r.created >= since).unwrap_or_default()
+                && !c.contains(|p| p.visibility == Some(Visibility::Direct))
+        });
+
+        for (_, notifications) in self.notification_posts.iter_mut() {
+            notifications.retain(|n| n.date >= since && keep(&n.status));
+        }
+        self.notification_posts
+            .retain(|_, notifications| !notifications.is_empty());
+        let notification_posts = &self.notification_posts;
+        self.notification_accounts
+            .retain(|e| notification_posts.contains_key(&e.id));
+        self.notification_groups.retain(|g| {
+            g.date >= since
+                && g.status
+                    .as_ref()
+                    .map(|s| s.visibility != Some(Visibility::Direct))
+                    .unwrap_or(true)
+        });
+    }
+}
+
 // Data Operations
 
 impl Data {
@@ -156,29 +302,57 @@ impl Data {
     }
 
     pub fn merge_bookmarks(&mut self, bookmarks: &[Status], is_reload: bool) {
-        Self::general_merge(&mut self.bookmarks, bookmarks, is_reload, None);
+        Self::general_merge(&mut self.bookmarks, bookmarks, is_reload, None, None);
     }
 
     pub fn merge_favorites(&mut self, favorites: &[Status], is_reload: bool) {
-        Self::general_merge(&mut self.favorites, favorites, is_reload, None);
+        Self::general_merge(&mut self.favorites, favorites, is_reload, None, None);
     }
 
     pub fn merge_localtimeline(&mut self, posts: &[Status], is_reload: bool) {
-        Self::general_merge(&mut self.local_timeline, posts, is_reload, Some(350));
+        let filter = Some((&self.content_filter, FilterContext::Public));
+        Self::general_merge(
+            &mut self.local_timeline,
+            posts,
+            is_reload,
+            Some(350),
+            filter,
+        );
     }
 
     pub fn merge_publictimeline(&mut self, posts: &[Status], is_reload: bool) {
-        Self::general_merge(&mut self.public_timeline, posts, is_reload, Some(350));
+        let filter = Some((&self.content_filter, FilterContext::Public));
+        Self::general_merge(
+            &mut self.public_timeline,
+            posts,
+            is_reload,
+            Some(350),
+            filter,
+        );
     }
 
     pub fn merge_classictimeline(&mut self, posts: &[Status], is_reload: bool) {
-        Self::general_merge(&mut self.classic_timeline, posts, is_reload, Some(350));
+        let filter = Some((&self.content_filter, FilterContext::Home));
+        Self::general_merge(
+            &mut self.classic_timeline,
+            posts,
+            is_reload,
+            Some(350),
+            filter,
+        );
     }
 
     pub fn merge_account(&mut self, posts: &[Status], id: &AccountId, is_reload: bool) {
         // if we don't have an entry yet, insert a new one
         let g = self.account_timeline.entry(id.clone()).or_default();
-        Self::general_merge(g, posts, is_reload, None);
+        let filter = Some((&self.content_filter, FilterContext::Account));
+        Self::general_merge(g, posts, is_reload, None, filter);
+    }
+
+    pub fn merge_tag_timeline(&mut self, posts: &[Status], tag: &str, is_reload: bool) {
+        let g = self.tag_timelines.entry(tag.to_string()).or_default();
+        let filter = Some((&self.content_filter, FilterContext::Public));
+        Self::general_merge(g, posts, is_reload, Some(350), filter);
     }
 
     fn general_merge(
@@ -186,14 +360,21 @@ impl Data {
         items: &[Status],
         is_reload: bool,
         limit_count: Option<usize>,
+        filter: Option<(&ContentFilter, FilterContext)>,
     ) {
         let existing: HashSet<_> = into.iter().map(|e| e.id.0.clone()).collect();
         for entry in items.iter() {
             if !existing.contains(entry.id.as_str()) {
+                let mut status = StatusViewModel::new(entry);
+                if let Some((filter, context)) = filter {
+                    if !filter.apply(&mut status, context) {
+                        continue;
+                    }
+                }
                 if is_reload {
-                    into.insert(0, StatusViewModel::new(entry))
+                    into.insert(0, status)
                 } else {
-                    into.push(StatusViewModel::new(entry));
+                    into.push(status);
                 }
             }
         }
@@ -212,6 +393,47 @@ impl Data {
         self.conversations.get(id)
     }
 
+    /// Is there anything to show, either loaded or from the cache
+    pub fn has_content(&self) -> bool {
+        !self.accounts().is_empty() || !self.notification_posts.is_empty()
+    }
+
+    /// The cacheable data. It still has to be trimmed with `CachedData::retain_since`
+    pub fn cache(&self) -> CachedData {
+        let timeline = self.timelines.get(LOCAL_TIMELINE_KEY);
+        CachedData {
+            saved: Utc::now(),
+            entries: timeline.map(|t| t.entries.clone()).unwrap_or_default(),
+            posts: timeline.map(|t| t.posts.clone()).unwrap_or_default(),
+            classic_timeline: self.classic_timeline.clone(),
+            account_timeline: self.account_timeline.clone(),
+            conversations: self.conversations.clone(),
+            notification_accounts: self.notification_accounts.clone(),
+            notification_posts: self.notification_posts.clone(),
+            notification_groups: self.notification_groups.clone(),
+        }
+    }
+
+    /// Fill the empty data with the cache of a previous launch.
+    /// Returns `false` if there already was data
+    pub fn hydrate(&mut self, cache: CachedData) -> bool {
+        if self.has_content() {
+            return false;
+        }
+        if let Some(timeline) = self.timelines.get_mut(LOCAL_TIMELINE_KEY) {
+            timeline.entries = cache.entries;
+            timeline.posts = cache.posts;
+            timeline.last_update = cache.saved;
+        }
+        self.classic_timeline = cache.classic_timeline;
+        self.account_timeline = cache.account_timeline;
+        self.conversations = cache.conversations;
+        self.notification_accounts = cache.notification_accounts;
+        self.notification_posts = cache.notification_posts;
+        self.notification_groups = cache.notification_groups;
+        true
+    }
+
     pub fn clear_reload(&mut self) -> bool {
         self.timelines.iter_mut().for_each(|l| {
             l.1.entries.clear();
@@ -225,6 +447,7 @@ impl Data {
         self.public_timeline.clear();
         self.account_timeline.clear();
         self.classic_timeline.clear();
+        self.tag_timelines.clear();
         true
     }
 
@@ -232,20 +455,29 @@ impl Data {
         match message {
             Message::Update(status) => {
                 log::debug!("update classic timeline data");
-                self.classic_timeline
-                    .insert(0, StatusViewModel::new(&status));
+                let mut model = StatusViewModel::new(&status);
+                if !self.content_filter.apply(&mut model, FilterContext::Home) {
+                    return;
+                }
+                self.classic_timeline.insert(0, model);
                 self.update_account_historical_data(&[status], &direction);
             }
             Message::Notification(notification) => {
+                if notification.r#type == NotificationType::FollowRequest {
+                    self.add_follow_request(&notification.account.clone().into());
+                }
                 self.update_notifications(&[notification]);
             }
-            Message::Conversation(_) => {
-                //
+            Message::Conversation(conversation) => {
+                if let Some(conversation) = DirectConversation::new(conversation) {
+                    self.update_direct_conversations(&[conversation]);
+                }
             }
-            Message::Delete(_) => {
-                //
+            Message::Delete(id) => {
+                self.remove_post(&StatusId(id));
             }
             Message::StatusUpdate(status) => {
+                self.replace_post(&status);
                 self.update_account_historical_data(&[status], &direction);
             }
             Message::Heartbeat() => {
@@ -254,6 +486,97 @@ impl Data {
         }
     }
 
+    pub fn add_follow_request(&mut self, account: &Account) {
+        if self.follow_requests.iter().any(|e| e.id.0 == account.id) {
+            return;
+        }
+        self.follow_requests
+            .insert(0, AccountViewModel::new(account));
+    }
+
+    /// Remove a follow request once it was accepted or rejected
+    pub fn remove_follow_request(&mut self, id: &AccountId) {
+        self.follow_requests.retain(|e| &e.id != id);
+    }
+
+    /// Attach the loaded quoted statuses to the posts that quote them
+    pub fn attach_quotes(&self, posts: &mut [StatusViewModel]) {
+        for post in posts.iter_mut() {
+            match post.reblog_status.as_mut() {
+                Some(reblog) => self.attach_quote(reblog),
+                None => self.attach_quote(post),
+            }
+        }
+    }
+
+    fn attach_quote(&self, post: &mut StatusViewModel) {
+        if !post.is_quote {
+            return;
+        }
+        if let Some(Some(quote)) = self.quotes.get(&post.id) {
+            post.quote = Some(Box::new(quote.clone()));
+        }
+    }
+
+    /// The ids of the quoting posts whose quoted status was not loaded yet
+    pub fn missing_quotes(&self, posts: &[StatusViewModel]) -> Vec<StatusId> {
+        posts
+            .iter()
+            .map(|e| e.reblog_status.as_deref().unwrap_or(e))
+            .filter(|e| e.is_quote && !self.quotes.contains_key(&e.id))
+            .map(|e| e.id.clone())
+            .collect()
+    }
+
+    pub fn add_quotes(&mut self, quotes: &[(StatusId, Option<Status>)]) {
+        for (id, status) in quotes {
+            self.quotes
+                .insert(id.clone(), status.as_ref().map(StatusViewModel::new));
+        }
+    }
+
+    /// Messages of the user stream go everywhere, the other streams
+    /// only update their own timeline
+    pub fn handle_stream_message(
+        &mut self,
+        kind: &StreamKind,
+        message: Message,
+        direction: TimelineDirection,
+    ) {
+        match (kind, message) {
+            (StreamKind::User, message) => self.handle_push_message(message, direction),
+            (_, Message::Update(status)) => self.merge_stream_statuses(kind, &[status], direction),
+            (_, Message::StatusUpdate(status)) => {
+                self.replace_post(&status);
+            }
+            (_, Message::Delete(id)) => {
+                self.remove_post(&StatusId(id));
+            }
+            _ => (),
+        }
+    }
+
+    /// Merge the newest posts (newest first) into the timeline of a stream
+    pub fn merge_stream_statuses(
+        &mut self,
+        kind: &StreamKind,
+        statuses: &[Status],
+        direction: TimelineDirection,
+    ) {
+        // Every post is inserted at the top, so the oldest has to go first
+        let oldest_first: Vec<_> = statuses.iter().rev().cloned().collect();
+        match kind {
+            StreamKind::User => {
+                self.merge_classictimeline(&oldest_first, true);
+                self.update_account_historical_data(statuses, &direction);
+            }
+            StreamKind::Public => self.merge_publictimeline(&oldest_first, true),
+            StreamKind::Local => self.merge_localtimeline(&oldest_first, true),
+            StreamKind::Hashtag(tag) => self.merge_tag_timeline(&oldest_first, tag, true),
+            StreamKind::List(id) => self.update_timeline_historical_data(id, statuses, &direction),
+        }
+    }
+
     pub fn mutate_post(
         &mut self,
         id: StatusId,
@@ -299,6 +622,32 @@ impl Data {
             }
         }
 
+        for (_, posts) in self.direct_posts.iter_mut() {
+            for p in posts.iter_mut() {
+                if p.id == id {
+                    action(p);
+                    found = true;
+                }
+            }
+        }
+
+        let search_posts = self.search_results.as_mut().map(|r| &mut r.statuses);
+        let tag_posts = self.tag_timelines.iter_mut().map(|(_, posts)| posts);
+        for posts in tag_posts.chain(search_posts) {
+            for p in posts.iter_mut() {
+                if p.id == id {
+                    action(p);
+                    found = true;
+                }
+                if let Some(o) = p.reblog_status.as_mut() {
+                    if o.id == id {
+                        action(o);
+                        found = true;
+                    }
+                }
+            }
+        }
+
         // bookmarks, favorites, and so on
         for posts in [
             self.bookmarks.iter_mut(),
@@ -332,6 +681,175 @@ impl Data {
         found_conv || found
     }
 
+    /// Replace all copies of a status with a newer version (e.g. after an edit)
+    pub fn replace_post(&mut self, status: &Status) -> bool {
+        let updated = StatusViewModel::new(status);
+        self.mutate_post(updated.id.clone(), updated.account.id.clone(), |post| {
+            *post = updated.clone();
+        })
+    }
+
+    /// Remove a deleted status (and boosts of it) from all the
+    /// collections that `mutate_post` walks
+    pub fn remove_post(&mut self, id: &StatusId) -> bool {
+        let mut found = false;
+        self.retain_posts(|p| {
+            let matches = &p.id == id
+                || p
+                    .reblog_status
+                    .as_ref()
+                    .map(|o| &o.id == id)
+                    .unwrap_or_default();
+            found |= matches;
+            !matches
+        });
+
+        // A conversation for a deleted status is gone completely
+        let conversation_count = self.conversations.len();
+        self.conversations
+            .retain(|key, c| key != id && c.root().map(|r| &r.id != id).unwrap_or(true));
+        found |= conversation_count != self.conversations.len();
+        for (_, c) in self.conversations.iter_mut() {
+            found |= c.remove_post(id);
+        }
+        found
+    }
+
+    /// Remove everything by (or boosted from) accounts we muted or blocked.
+    /// `matches` decides whether an account is affected
+    pub fn remove_accounts(&mut self, matches: impl Fn(&AccountViewModel) -> bool) -> bool {
+        let mut found = false;
+        self.retain_posts(|p| {
+            let remove = matches(&p.account)
+                || p
+                    .reblog_status
+                    .as_ref()
+                    .map(|o| matches(&o.account))
+                    .unwrap_or_default();
+            found |= remove;
+            !remove
+        });
+
+        let lengths = |s: &Self| {
+            (
+                s.notification_accounts.len(),
+                s.notification_groups.len(),
+                s.direct_conversations.len(),
+            )
+        };
+        let before = lengths(self);
+        self.notification_accounts.retain(|a| !matches(&a.account));
+        self.notification_posts.retain(|_, posts| !posts.is_empty());
+        for group in self.notification_groups.iter_mut() {
+            group.accounts.retain(|a| !matches(a));
+        }
+        self.notification_groups.retain(|group| !group.accounts.is_empty());
+        self.direct_conversations.retain(|c| !matches(&c.account));
+        found |= before != lengths(self);
+
+        for (_, timeline) in self.timelines.iter_mut() {
+            timeline.entries.retain(|e| !matches(&e.account));
+            timeline.posts.retain(|_, posts| !posts.is_empty());
+        }
+        found
+    }
+
+    /// Remove the posts of accounts we just muted or blocked
+    pub fn apply_moderation(
+        &mut self,
+        account: &AccountViewModel,
+        moderation: AccountModeration,
+    ) -> bool {
+        match (moderation, account.domain()) {
+            (AccountModeration::BlockDomain, Some(domain)) => {
+                self.remove_accounts(|a| a.domain() == Some(domain))
+            }
+            (m, _) if m.hides() => self.remove_accounts(|a| a.id == account.id),
+            _ => false,
+        }
+    }
+
+    /// Use new filters and re-apply them to the posts we already have
+    pub fn set_content_filter(&mut self, filter: ContentFilter) {
+        self.content_filter = filter;
+        let filter = &self.content_filter;
+
+        for (_, timeline) in self.timelines.iter_mut() {
+            for (_, posts) in timeline.posts.iter_mut() {
+                posts.retain_mut(|p| filter.apply(p, FilterContext::Home));
+            }
+            timeline.posts.retain(|_, posts| !posts.is_empty());
+            let posts = &timeline.posts;
+            timeline.entries.retain(|e| posts.contains_key(&e.id));
+        }
+        self.classic_timeline
+            .retain_mut(|p| filter.apply(p, FilterContext::Home));
+
+        for posts in [&mut self.local_timeline, &mut self.public_timeline] {
+            posts.retain_mut(|p| filter.apply(p, FilterContext::Public));
+        }
+        for (_, posts) in self.tag_timelines.iter_mut() {
+            posts.retain_mut(|p| filter.apply(p, FilterContext::Public));
+        }
+
+        for (_, posts) in self.account_timeline.iter_mut() {
+            posts.retain_mut(|p| filter.apply(p, FilterContext::Account));
+        }
+
+        for (_, posts) in self.notification_posts.iter_mut() {
+            posts.retain_mut(|n| filter.apply(&mut n.status, FilterContext::Notifications));
+        }
+        self.notification_posts.retain(|_, posts| !posts.is_empty());
+        let posts = &self.notification_posts;
+        self.notification_accounts
+            .retain(|a| posts.contains_key(&a.id));
+
+        for (_, conversation) in self.conversations.iter_mut() {
+            conversation.apply_filter(filter);
+        }
+    }
+
+    fn retain_posts(&mut self, mut keep: impl FnMut(&StatusViewModel) -> bool) {
+        for (_, posts) in self.notification_posts.iter_mut() {
+            posts.retain(|item| keep(&item.status));
+        }
+
+        self.notification_groups
+            .retain(|group| group.status.as_ref().map(&mut keep).unwrap_or(true));
+
+        for (_, posts) in self.account_timeline.iter_mut() {
+            posts.retain(|p| keep(p));
+        }
+
+        for (_, posts) in self.direct_posts.iter_mut() {
+            posts.retain(|p| keep(p));
+        }
+
+        for (_, posts) in self.tag_timelines.iter_mut() {
+            posts.retain(|p| keep(p));
+        }
+
+        if let Some(results) = self.search_results.as_mut() {
+            results.statuses.retain(|p| keep(p));
+        }
+
+        for (_, timeline) in self.timelines.iter_mut() {
+            for (_, posts) in timeline.posts.iter_mut() {
+                posts.retain(|p| keep(p));
+            }
+        }
+
+        for posts in [
+            &mut self.bookmarks,
+            &mut self.favorites,
+            &mut self.local_timeline,
+            &mut self.public_timeline,
+            &mut self.classic_timeline,
+        ] {
+            posts.retain(|p| keep(p));
+        }
+    }
+
     /// Remove lists we don't have anymore, add new lists
     pub fn update_timelines(&mut self, timelines: &[(String, String)]) {
         let mut unknown: HashSet<_> = self.timelines.keys().cloned().collect();
@@ -428,6 +946,7 @@ impl Data {
             updates,
             self.timelines.get_mut(LOCAL_TIMELINE_KEY).unwrap(),
             direction,
+            &self.content_filter,
         );
     }
 
@@ -440,7 +959,7 @@ impl Data {
         let Some(timeline) = self.timelines.get_mut(id) else {
             return
         };
-        Self::update_historical_data(updates, timeline, direction);
+        Self::update_historical_data(updates, timeline, direction, &self.content_filter);
     }
 
     /// can't have to &mut. Proper solution is to abstract timelines into a struct
@@ -449,15 +968,19 @@ impl Data {
         updates: &[Status],
         timeline: &mut TimelineEntry,
         direction: &TimelineDirection,
+        filter: &ContentFilter,
     ) -> bool {
         let posts = &mut timeline.posts;
         let accounts = &mut timeline.entries;
         let mut updated = HashSet::new();
         for update in updates.iter() {
+            let mut new_status = StatusViewModel::new(update);
+            if !filter.apply(&mut new_status, FilterContext::Home) {
+                continue;
+            }
             let id = AccountId(update.account.id.clone());
             updated.insert(id.clone());
             let exists = posts.contains_key(&id);
-            let new_status = StatusViewModel::new(update);
             if exists {
                 // this should never fail, but still
                 if let Some(account_idx) = accounts.iter().position(|o| o.id == id) {
@@ -502,15 +1025,39 @@ impl Data {
         let accounts = &mut self.notification_accounts;
 
         let mut updated = HashSet::new();
+        let mut updated_groups = false;
         for notification in notifications.iter() {
+            let kind = NotificationKind::new(&notification.r#type);
+            if kind.is_grouped() {
+                let group_id = NotificationGroupViewModel::group_id(kind, notification);
+                let groups = &mut self.notification_groups;
+                match groups.iter_mut().find(|g| g.id == group_id) {
+                    Some(group) => {
+                        updated_groups |= group.insert(notification);
+                    }
+                    None => {
+                        if let Some(group) = NotificationGroupViewModel::new(notification) {
+                            groups.push_back(group);
+                            updated_groups = true;
+                        }
+                    }
+                }
+                continue;
+            }
             let id = AccountId(notification.account.id.clone());
             let exists = posts.contains_key(&id);
             let Some(ref status) = notification.status else {
                 continue
             };
-            let Some(nm) = NotificationViewModel::new(notification) else {
+            let Some(mut nm) = NotificationViewModel::new(notification) else {
                 continue
             };
+            if !self
+                .content_filter
+                .apply(&mut nm.status, FilterContext::Notifications)
+            {
+                continue;
+            }
             updated.insert(id.clone());
             let new_status = StatusViewModel::new(status);
             if exists {
@@ -535,7 +1082,132 @@ impl Data {
         }
         // Sort the accounts by date
         accounts.sort_by(|a, b| b.last_updated.cmp(&a.last_updated));
+        self.notification_groups.sort_by(|a, b| b.date.cmp(&a.date));
+
+        !updated.is_empty() || updated_groups
+    }
+
+    /// Add the last messages of the conversations from the server. Unread
+    /// conversations mark the conversation of their participants as unread
+    pub fn update_direct_conversations(&mut self, conversations: &[DirectConversation]) -> bool {
+        for conversation in conversations {
+            self.add_direct_message(
+                &conversation.last_status,
+                Some((&conversation.id, conversation.unread)),
+            );
+        }
+        self.sort_direct_conversations();
+        !conversations.is_empty()
+    }
+
+    /// Add direct messages that are read, e.g. our own
+    pub fn update_direct_messages(&mut self, statuses: &[Status]) -> bool {
+        for status in statuses {
+            self.add_direct_message(status, None);
+        }
+        self.sort_direct_conversations();
+        !statuses.is_empty()
+    }
+
+    /// Add the other messages of the thread of a conversation
+    pub fn update_direct_thread(&mut self, id: &str, statuses: &[Status]) {
+        let posts = self.direct_posts.entry(id.to_string()).or_default();
+        for status in statuses {
+            let status = StatusViewModel::new(status);
+            match posts.iter().position(|p| p.id == status.id) {
+                Some(pos) => posts[pos] = status,
+                None => posts.push(status),
+            }
+        }
+        posts.sort_by(|a, b| b.created.cmp(&a.created));
+    }
+
+    /// Group a direct message into a conversation by its participants.
+    /// `server` is the id of the conversation on the server and whether it is unread
+    fn add_direct_message(&mut self, status: &Status, server: Option<(&String, bool)>) {
+        let own_acct = self
+            .user_account
+            .as_ref()
+            .map(|e| e.acct.clone())
+            .unwrap_or_default();
+        let status = StatusViewModel::new(status);
+        let id = direct_conversation_id(&status, &own_acct);
+        // Messages from others in the conversation we're looking at are read
+        let is_visible = self.active_tab.is_messages()
+            && self.selected_direct_conversation.as_ref() == Some(&id);
+        let is_own = status.account.acct == own_acct;
+
+        let posts = self.direct_posts.entry(id.clone()).or_default();
+        match posts.iter().position(|p| p.id == status.id) {
+            Some(pos) => posts[pos] = status.clone(),
+            None => {
+                posts.push(status.clone());
+                posts.sort_by(|a, b| b.created.cmp(&a.created));
+            }
+        }
+
+        let conversations = &mut self.direct_conversations;
+        let mut conversation = match conversations.iter().position(|c| c.id == id) {
+            Some(pos) => {
+                let existing = conversations.remove(pos);
+                if status.created >= existing.last_updated {
+                    let mut conversation =
+                        DirectConversationViewModel::new(id, &status, existing.unread);
+                    conversation.unread_ids = existing.unread_ids;
+                    // Show the other participant, not ourselves
+                    if is_own {
+                        conversation.account = existing.account;
+                    }
+                    conversation
+                } else {
+                    existing
+                }
+            }
+            None => DirectConversationViewModel::new(id, &status, false),
+        };
+        if let Some((server_id, unread)) = server {
+            conversation.unread_ids.retain(|e| e != server_id);
+            if unread {
+                conversation.unread_ids.push(server_id.clone());
+            }
+            conversation.unread = !conversation.unread_ids.is_empty() && !is_visible;
+        }
+        conversations.push_back(conversation);
+    }
+
+    fn sort_direct_conversations(&mut self) {
+        self.direct_conversations
+            .sort_by(|a, b| b.last_updated.cmp(&a.last_updated));
+    }
+
+    /// Returns the conversations on the server that have to be marked as read
+    pub fn mark_direct_conversation_read(&mut self, id: &str) -> Vec<String> {
+        let mut unread_ids = Vec::new();
+        for conversation in self.direct_conversations.iter_mut() {
+            if conversation.id == id {
+                conversation.unread = false;
+                unread_ids.append(&mut conversation.unread_ids);
+            }
+        }
+        unread_ids
+    }
+
+    pub fn has_unread_direct_messages(&self) -> bool {
+        self.direct_conversations.iter().any(|c| c.unread)
+    }
+}
 
-        !updated.is_empty()
+/// The participants of a direct message, without ourselves
+fn direct_conversation_id(status: &StatusViewModel, own_acct: &str) -> String {
+    let mut participants: Vec<&str> = std::iter::once(status.account.acct.as_str())
+        .chain(status.mentions.iter().map(|m| m.trim_start_matches('@')))
+        .filter(|acct| *acct != own_acct)
+        .collect();
+    // A message to ourselves
+    if participants.is_empty() {
+        participants.push(own_acct);
     }
+    participants.sort_unstable();
+    participants.dedup();
+    participants.join(",")
 }
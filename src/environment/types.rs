@@ -1,6 +1,10 @@
 use chrono::{DateTime, Utc};
 use enumset::EnumSet;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::environment::model::StatusVisibility;
+use crate::view_model::StatusViewModel;
 
 // Repository Types
 
@@ -24,6 +28,10 @@ pub struct User {
     pub app_client_secret: String,
     pub app_auth_url: Option<String>,
     pub app_redirect_uri: String,
+
+    /// The server software, detected during login
+    #[serde(default)]
+    pub backend: Backend,
 }
 
 impl User {
@@ -32,6 +40,7 @@ impl User {
         account: super::model::Account,
         token: super::model::TokenData,
         data: super::model::AppData,
+        backend: Backend,
     ) -> Self {
         Self {
             id: account.id.clone(),
@@ -50,10 +59,167 @@ impl User {
             app_client_secret: data.client_secret.clone(),
             app_auth_url: data.url.clone(),
             app_redirect_uri: data.redirect_uri.clone(),
+            backend,
+        }
+    }
+}
+
+/// The server software of an instance, as reported by nodeinfo
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub enum Backend {
+    /// Also used for unknown software, most of it implements the Mastodon API
+    #[default]
+    Mastodon,
+    Pleroma,
+    Akkoma,
+    GoToSocial,
+    /// Firefish implements the Mastodon API
+    Firefish,
+    /// Misskey only has its own API, which megalodon has no client for
+    Misskey,
+}
+
+impl Backend {
+    /// Map the nodeinfo `software.name`
+    pub fn new(software: &str) -> Self {
+        match software.to_lowercase().as_str() {
+            "pleroma" => Backend::Pleroma,
+            "akkoma" => Backend::Akkoma,
+            "gotosocial" => Backend::GoToSocial,
+            "firefish" | "calckey" | "iceshrimp" => Backend::Firefish,
+            "misskey" | "foundkey" | "sharkey" => Backend::Misskey,
+            _ => Backend::Mastodon,
+        }
+    }
+
+    /// Can we log in at all
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, Backend::Misskey)
+    }
+
+    /// Everything but Misskey implements the Mastodon API
+    pub fn has_mastodon_api(&self) -> bool {
+        !matches!(self, Backend::Misskey)
+    }
+
+    pub fn supports_streaming(&self) -> bool {
+        !matches!(self, Backend::Firefish | Backend::Misskey)
+    }
+
+    /// Can statuses be posted with a `quote_id`
+    pub fn supports_quotes(&self) -> bool {
+        matches!(self, Backend::Pleroma | Backend::Akkoma | Backend::Firefish)
+    }
+}
+
+impl std::fmt::Display for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Backend::Mastodon => write!(f, "Mastodon"),
+            Backend::Pleroma => write!(f, "Pleroma"),
+            Backend::Akkoma => write!(f, "Akkoma"),
+            Backend::GoToSocial => write!(f, "GoToSocial"),
+            Backend::Firefish => write!(f, "Firefish"),
+            Backend::Misskey => write!(f, "Misskey"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PostKind {
+    Post,
+    Reply(StatusViewModel),
+    ReplyPrivate(StatusViewModel),
+    /// Edit one of our own statuses
+    Edit(StatusViewModel),
+    /// Post a deleted status again
+    Redraft(StatusViewModel),
+    /// Post a new status that quotes this one
+    Quote(StatusViewModel),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Visibility {
+    Public,
+    Unlisted,
+    Private,
+    Direct,
+}
+
+impl FromStr for Visibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "public" => Ok(Visibility::Public),
+            "unlisted" => Ok(Visibility::Unlisted),
+            "private" => Ok(Visibility::Private),
+            "direct" => Ok(Visibility::Direct),
+            _ => Err("INVALID".to_string()),
+        }
+    }
+}
+
+impl From<&StatusVisibility> for Visibility {
+    fn from(value: &StatusVisibility) -> Self {
+        match value {
+            StatusVisibility::Unlisted => Visibility::Unlisted,
+            StatusVisibility::Private => Visibility::Private,
+            StatusVisibility::Direct => Visibility::Direct,
+            _ => Visibility::Public,
+        }
+    }
+}
+
+impl From<&Visibility> for StatusVisibility {
+    fn from(value: &Visibility) -> Self {
+        match value {
+            Visibility::Public => StatusVisibility::Public,
+            Visibility::Unlisted => StatusVisibility::Unlisted,
+            Visibility::Private => StatusVisibility::Private,
+            Visibility::Direct => StatusVisibility::Direct,
         }
     }
 }
 
+/// A post that was not sent yet
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Draft {
+    pub id: String,
+    /// The account that wrote the draft
+    pub account_id: String,
+    pub kind: PostKind,
+    pub text: String,
+    pub visibility: Option<Visibility>,
+    pub spoiler_text: Option<String>,
+    pub sensitive: bool,
+    pub images: Vec<crate::view_model::AttachmentMedia>,
+    pub updated: DateTime<Utc>,
+    /// The composer was still open when the draft was saved. If this is
+    /// still set on the next start, the app didn't close properly and
+    /// the draft is restored
+    pub is_open: bool,
+}
+
+impl Draft {
+    pub fn new_id() -> String {
+        Utc::now().timestamp_millis().to_string()
+    }
+
+    /// The first line of the text, for lists of drafts
+    pub fn title(&self) -> String {
+        let line = self.text.lines().next().unwrap_or_default();
+        let mut title: String = line.chars().take(40).collect();
+        if line.chars().count() > 40 {
+            title.push('…');
+        }
+        if title.trim().is_empty() {
+            title = format!("{} Attachments", self.images.len());
+        }
+        title
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Marker {
     /// When was the marker set
@@ -73,6 +239,126 @@ pub struct UiConfig {
     pub direction: TimelineDirection,
     #[serde(default)]
     pub post_window_inline: bool,
+    /// Always expand posts with a content warning
+    #[serde(default)]
+    pub expand_content_warnings: bool,
+    /// Always show media that is marked as sensitive
+    #[serde(default)]
+    pub show_sensitive_media: bool,
+    /// The kinds of notifications that are not shown in the mentions tab
+    #[serde(default)]
+    pub hidden_notification_kinds: EnumSet<crate::view_model::NotificationKind>,
+    /// Local regex mutes, for servers without (v2) filters
+    #[serde(default)]
+    pub keyword_mutes: Vec<String>,
+    /// How long posts are kept in the offline cache
+    #[serde(default)]
+    pub cache_retention: CacheRetention,
+    /// Where access tokens and client secrets are kept
+    #[serde(default)]
+    pub credential_backend: CredentialBackend,
+}
+
+/// The streams of the streaming API
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum StreamKind {
+    User,
+    Public,
+    Local,
+    Hashtag(String),
+    /// The id of the list
+    List(String),
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum StreamState {
+    #[default]
+    Disconnected,
+    Connecting,
+    Connected,
+    /// The connection dropped, the next attempt is in this many seconds
+    Reconnecting(u64),
+}
+
+impl std::fmt::Display for StreamState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamState::Disconnected => write!(f, crate::loc!("Live updates are off")),
+            StreamState::Connecting => write!(f, crate::loc!("Connecting live updates")),
+            StreamState::Connected => write!(f, crate::loc!("Live updates are on")),
+            StreamState::Reconnecting(seconds) => {
+                write!(
+                    f,
+                    crate::loc!("Live updates interrupted, retrying in {}s"),
+                    seconds
+                )
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum StreamEvent {
+    State(StreamState),
+    /// The stream is listening again after it was disconnected.
+    /// Anything that was posted in between has to be fetched
+    Reconnected,
+    Message(super::model::Message),
+}
+
+/// How long posts are kept in the on-disk cache
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub enum CacheRetention {
+    /// Don't cache anything
+    Off,
+    Day,
+    #[default]
+    Week,
+    Month,
+}
+
+impl CacheRetention {
+    pub fn all() -> [CacheRetention; 4] {
+        [
+            CacheRetention::Off,
+            CacheRetention::Day,
+            CacheRetention::Week,
+            CacheRetention::Month,
+        ]
+    }
+
+    /// The maximum age of cached posts. `None` disables the cache
+    pub fn max_age(&self) -> Option<chrono::Duration> {
+        match self {
+            CacheRetention::Off => None,
+            CacheRetention::Day => Some(chrono::Duration::days(1)),
+            CacheRetention::Week => Some(chrono::Duration::weeks(1)),
+            CacheRetention::Month => Some(chrono::Duration::days(30)),
+        }
+    }
+}
+
+impl std::fmt::Display for CacheRetention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CacheRetention::Off => write!(f, crate::loc!("Don't keep posts")),
+            CacheRetention::Day => write!(f, crate::loc!("One day")),
+            CacheRetention::Week => write!(f, crate::loc!("One week")),
+            CacheRetention::Month => write!(f, crate::loc!("One month")),
+        }
+    }
+}
+
+/// The store for the secrets of the logged in users
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub enum CredentialBackend {
+    /// A file encrypted with a key derived from `EBOU_PASSPHRASE` or,
+    /// if that is not set, with a machine key file
+    #[default]
+    EncryptedFile,
+    /// The system keyring via the Secret Service API. Requires the
+    /// `secret-service` feature
+    SecretService,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
@@ -82,6 +368,242 @@ pub enum TimelineDirection {
     NewestTop,
 }
 
+/// Mute or block an account (or the domain of an account)
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AccountModeration {
+    /// The duration is in seconds, `None` mutes indefinitely
+    Mute {
+        duration: Option<u64>,
+        notifications: bool,
+    },
+    Unmute,
+    Block,
+    Unblock,
+    BlockDomain,
+    UnblockDomain,
+}
+
+impl AccountModeration {
+    /// Does this hide the account from our data?
+    pub fn hides(&self) -> bool {
+        matches!(
+            self,
+            AccountModeration::Mute { .. }
+                | AccountModeration::Block
+                | AccountModeration::BlockDomain
+        )
+    }
+
+    /// The confirmation message for destructive actions
+    pub fn confirmation(&self) -> Option<&'static str> {
+        match self {
+            AccountModeration::Block => {
+                Some(crate::loc!("Do you really want to block this account?"))
+            }
+            AccountModeration::BlockDomain => Some(crate::loc!(
+                "Do you really want to block all accounts of this domain?"
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Why an account is reported to the moderators
+#[derive(Copy, Clone, Debug, Default, Serialize, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportCategory {
+    Spam,
+    /// Violates one or more of the instance rules
+    Violation,
+    #[default]
+    Other,
+}
+
+impl ReportCategory {
+    pub fn all() -> [ReportCategory; 3] {
+        [
+            ReportCategory::Spam,
+            ReportCategory::Violation,
+            ReportCategory::Other,
+        ]
+    }
+}
+
+impl std::fmt::Display for ReportCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportCategory::Spam => write!(f, crate::loc!("Spam")),
+            ReportCategory::Violation => write!(f, crate::loc!("Violates Server Rules")),
+            ReportCategory::Other => write!(f, crate::loc!("Something Else")),
+        }
+    }
+}
+
+/// Where a server side filter applies
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterContext {
+    Home,
+    Notifications,
+    Public,
+    Thread,
+    Account,
+}
+
+impl FilterContext {
+    pub fn all() -> [FilterContext; 5] {
+        [
+            FilterContext::Home,
+            FilterContext::Notifications,
+            FilterContext::Public,
+            FilterContext::Thread,
+            FilterContext::Account,
+        ]
+    }
+}
+
+impl std::fmt::Display for FilterContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterContext::Home => write!(f, crate::loc!("Home & Lists")),
+            FilterContext::Notifications => write!(f, crate::loc!("Notifications")),
+            FilterContext::Public => write!(f, crate::loc!("Public Timelines")),
+            FilterContext::Thread => write!(f, crate::loc!("Conversations")),
+            FilterContext::Account => write!(f, crate::loc!("Profiles")),
+        }
+    }
+}
+
+/// What happens to posts that match a filter
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    /// Collapse the post behind a warning
+    #[default]
+    Warn,
+    Hide,
+}
+
+impl FilterAction {
+    pub fn all() -> [FilterAction; 2] {
+        [FilterAction::Warn, FilterAction::Hide]
+    }
+}
+
+impl std::fmt::Display for FilterAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterAction::Warn => write!(f, crate::loc!("Hide with a warning")),
+            FilterAction::Hide => write!(f, crate::loc!("Hide completely")),
+        }
+    }
+}
+
+/// The body to create or update a filter via `/api/v2/filters`
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct FilterInput {
+    pub title: String,
+    pub context: Vec<FilterContext>,
+    pub filter_action: FilterAction,
+    /// In seconds. `None` never expires
+    pub expires_in: Option<u64>,
+    pub keywords_attributes: Vec<FilterKeywordInput>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct FilterKeywordInput {
+    /// Only for existing keywords
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub keyword: String,
+    pub whole_word: bool,
+    #[serde(rename = "_destroy")]
+    pub destroy: bool,
+}
+
+/// Which replies are shown in a list
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ListRepliesPolicy {
+    /// Replies to any followed user
+    Followed,
+    /// Replies to members of the list
+    #[default]
+    List,
+    None,
+}
+
+impl ListRepliesPolicy {
+    pub fn all() -> [ListRepliesPolicy; 3] {
+        [
+            ListRepliesPolicy::Followed,
+            ListRepliesPolicy::List,
+            ListRepliesPolicy::None,
+        ]
+    }
+}
+
+impl std::fmt::Display for ListRepliesPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListRepliesPolicy::Followed => write!(f, crate::loc!("Replies to any followed user")),
+            ListRepliesPolicy::List => write!(f, crate::loc!("Replies to list members")),
+            ListRepliesPolicy::None => write!(f, crate::loc!("No replies")),
+        }
+    }
+}
+
+/// The body to create or update a list via `/api/v1/lists`
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ListInput {
+    pub title: String,
+    pub replies_policy: ListRepliesPolicy,
+    /// Posts of the members are only shown in the list, not in the home timeline
+    pub exclusive: bool,
+}
+
+/// The body of a report for `/api/v1/reports`
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ReportInput {
+    pub account_id: String,
+    pub status_ids: Vec<String>,
+    pub comment: String,
+    /// Also send the report to the instance of the account
+    pub forward: bool,
+    pub category: ReportCategory,
+    pub rule_ids: Vec<String>,
+}
+
+/// The media limits of an instance, from `configuration.media_attachments`
+/// of `/api/v1/instance`. Defaults to the Mastodon defaults
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct MediaLimits {
+    pub supported_mime_types: Vec<String>,
+    /// In bytes
+    pub image_size_limit: u64,
+    /// The maximum number of pixels (width * height)
+    pub image_matrix_limit: u64,
+}
+
+impl Default for MediaLimits {
+    fn default() -> Self {
+        Self {
+            supported_mime_types: ["image/jpeg", "image/png", "image/gif", "image/webp"]
+                .map(String::from)
+                .to_vec(),
+            image_size_limit: 16 * 1024 * 1024,
+            image_matrix_limit: 33_177_600,
+        }
+    }
+}
+
+impl MediaLimits {
+    pub fn supports(&self, mime_type: &str) -> bool {
+        self.supported_mime_types.iter().any(|e| e == mime_type)
+    }
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, FromRepr)]
 #[repr(u8)]
 #[derive(Default)]
@@ -148,9 +670,12 @@ use crate::view_model::StatusId;
 pub enum MainMenuEvent {
     NewPost,
     Logout,
+    AddAccount,
+    NextAccount,
     Reload,
     ScrollUp,
     ScrollDown,
+    JumpToLastRead,
     TextSizeIncrease,
     TextSizeDecrease,
     TextSizeReset,
@@ -22,6 +22,22 @@ pub fn parse_user_url(url: &str) -> Option<String> {
     Some(format!("{username}@{host}"))
 }
 
+/// Parse the value of a `datetime-local` input (e.g. `2023-04-01T13:30`)
+/// in the local timezone
+pub fn parse_local_datetime(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::{Local, NaiveDateTime, TimeZone, Utc};
+    let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M").ok()?;
+    let local = Local.from_local_datetime(&naive).single()?;
+    Some(local.with_timezone(&Utc))
+}
+
+/// Format a date as the value of a `datetime-local` input
+pub fn format_local_datetime(date: &chrono::DateTime<chrono::Utc>) -> String {
+    date.with_timezone(&chrono::Local)
+        .format("%Y-%m-%dT%H:%M")
+        .to_string()
+}
+
 mod clean_html_content {
     use html5gum::{HtmlString, Token, Tokenizer};
     use serde::{Deserialize, Serialize};
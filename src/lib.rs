@@ -43,7 +43,7 @@ impl Drop for Defer {
     }
 }
 
-use components::post::PostKind;
+use environment::types::PostKind;
 use view_model::{AccountViewModel, StatusViewModel};
 
 #[allow(clippy::large_enum_variant)]
@@ -60,8 +60,18 @@ pub enum PublicAction {
     OpenProfileLink(String),
     Copy(String),
     Post(PostKind),
+    /// Vote in the poll of a status
+    VotePoll(StatusViewModel, Vec<u32>),
+    /// Delete one of our statuses. If `true`, it is opened in the composer again
+    DeleteStatus(StatusViewModel, bool),
     /// Close the current conversation
     Close,
+    /// Mute or block an account or its domain
+    Moderate(AccountViewModel, environment::types::AccountModeration),
+    /// Report an account, optionally starting from one of its posts
+    Report(AccountViewModel, Option<StatusViewModel>),
+    /// Add an account to (`true`) or remove it from a list
+    UpdateListMembership(AccountViewModel, String, bool),
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -1,4 +1,5 @@
 use crate::environment::model::*;
+use crate::environment::types::Visibility;
 use crate::helper::HtmlItem;
 use chrono::{DateTime, Utc};
 use enumset::EnumSetType;
@@ -89,6 +90,11 @@ impl AccountViewModel {
             fields,
         }
     }
+
+    /// The domain of remote accounts. `None` for accounts on our instance
+    pub fn domain(&self) -> Option<&str> {
+        self.acct.split_once('@').map(|(_, domain)| domain)
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -222,6 +228,33 @@ pub struct StatusViewModel {
     pub text: String,
     #[serde(default)]
     pub media: Vec<VideoMedia>,
+    #[serde(default)]
+    pub poll: Option<PollViewModel>,
+    /// The content warning, empty if there is none
+    #[serde(default)]
+    pub spoiler_text: String,
+    /// Is the media marked as sensitive
+    #[serde(default)]
+    pub sensitive: bool,
+    /// The server ids of the attachments, kept when editing
+    #[serde(default)]
+    pub media_ids: Vec<String>,
+    /// Who can see the status, kept when redrafting
+    #[serde(default)]
+    pub visibility: Option<Visibility>,
+    /// The status this one replies to, kept when redrafting
+    #[serde(default)]
+    pub in_reply_to_id: Option<String>,
+    /// The title of the filter that matched this post, if it should
+    /// only be shown behind a warning
+    #[serde(default)]
+    pub filtered: Option<String>,
+    /// Does this status quote another status
+    #[serde(default)]
+    pub is_quote: bool,
+    /// The quoted status, once it was loaded
+    #[serde(default)]
+    pub quote: Option<Box<StatusViewModel>>,
 }
 
 impl PartialEq for StatusViewModel {
@@ -237,6 +270,12 @@ impl PartialEq for StatusViewModel {
             && self.reblog == other.reblog
             && self.favourited_count == other.favourited_count
             && self.favourited == other.favourited
+            && self.poll == other.poll
+            // edited statuses have a different text
+            && self.text == other.text
+            && self.spoiler_text == other.spoiler_text
+            && self.filtered == other.filtered
+            && self.quote == other.quote
     }
 }
 
@@ -364,6 +403,19 @@ impl StatusViewModel {
             has_conversation,
             text,
             media,
+            poll: status.poll.as_ref().map(PollViewModel::new),
+            spoiler_text: status.spoiler_text.clone(),
+            sensitive: status.sensitive,
+            media_ids: status
+                .media_attachments
+                .iter()
+                .map(|e| e.id.clone())
+                .collect(),
+            visibility: Some(Visibility::from(&status.visibility)),
+            in_reply_to_id: status.in_reply_to_id.clone(),
+            filtered: None,
+            is_quote: status.quote,
+            quote: None,
         }
     }
 
@@ -431,41 +483,113 @@ pub fn status_images(status: &Status) -> Vec<(String, String, String)> {
         .collect()
 }
 
+/// The kinds of notifications we know how to show
+#[derive(EnumSetType, Debug, Default, Serialize, Deserialize)]
+pub enum NotificationKind {
+    #[default]
+    Mention,
+    Status,
+    Reblog,
+    Favourite,
+    Follow,
+    FollowRequest,
+    PollVote,
+    PollExpired,
+    EmojiReaction,
+    /// Everything else, e.g. moved accounts or admin notifications
+    Other,
+}
+
+impl NotificationKind {
+    pub fn new(kind: &NotificationType) -> Self {
+        match kind {
+            NotificationType::Mention => Self::Mention,
+            NotificationType::Status => Self::Status,
+            NotificationType::Reblog => Self::Reblog,
+            NotificationType::Favourite => Self::Favourite,
+            NotificationType::Follow => Self::Follow,
+            NotificationType::FollowRequest => Self::FollowRequest,
+            NotificationType::PollVote => Self::PollVote,
+            NotificationType::PollExpired => Self::PollExpired,
+            NotificationType::EmojiReaction => Self::EmojiReaction,
+            _ => Self::Other,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Mention => crate::loc!("Mentions"),
+            Self::Status => crate::loc!("New Posts"),
+            Self::Reblog => crate::loc!("Boosts"),
+            Self::Favourite => crate::loc!("Favourites"),
+            Self::Follow => crate::loc!("Follows"),
+            Self::FollowRequest => crate::loc!("Follow Requests"),
+            Self::PollVote => crate::loc!("Poll Votes"),
+            Self::PollExpired => crate::loc!("Ended Polls"),
+            Self::EmojiReaction => crate::loc!("Reactions"),
+            Self::Other => crate::loc!("Other"),
+        }
+    }
+
+    /// Interactions with our posts (and follows) are grouped
+    /// instead of being listed per account
+    pub fn is_grouped(&self) -> bool {
+        matches!(
+            self,
+            Self::Reblog
+                | Self::Favourite
+                | Self::Follow
+                | Self::FollowRequest
+                | Self::PollVote
+                | Self::EmojiReaction
+                | Self::Other
+        )
+    }
+
+    fn action(&self) -> &'static str {
+        match self {
+            Self::Mention => crate::loc!("mentioned you"),
+            Self::Status => crate::loc!("shared an update"),
+            Self::Reblog => crate::loc!("boosted your post"),
+            Self::Favourite => crate::loc!("favourited your post"),
+            Self::Follow => crate::loc!("followed you"),
+            Self::FollowRequest => crate::loc!("requested to follow you"),
+            Self::PollVote => crate::loc!("voted in your poll"),
+            Self::PollExpired => crate::loc!("had a poll that ended"),
+            Self::EmojiReaction => crate::loc!("reacted to your post"),
+            Self::Other => crate::loc!("sent you a notification"),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Default, Serialize, Deserialize)]
 pub struct NotificationViewModel {
     pub id: String,
+    #[serde(default)]
+    pub kind: NotificationKind,
     pub message: String,
     pub status: StatusViewModel,
     pub date: DateTime<Utc>,
 }
 
 impl NotificationViewModel {
+    /// Notifications that refer to a status
     pub fn new(notification: &Notification) -> Option<Self> {
+        let kind = NotificationKind::new(&notification.r#type);
         let status = notification.status.as_ref()?;
-        let mut content = status
-            .plain_content
-            .clone()
-            .unwrap_or_else(|| clean_html(&status.content).0);
-        if content.len() > 140 {
-            content = content.chars().take(140).collect();
-            content.push('…');
-        }
-        let message = match notification.r#type {
-            NotificationType::Mention => {
-                format!("{} mentioned you: {content}", notification.account.username)
-            }
-            NotificationType::Status => {
-                format!(
-                    "{} shared an update: {content}",
-                    notification.account.username
-                )
+        let content = notification_content(status);
+        let username = &notification.account.username;
+        let message = match kind {
+            NotificationKind::PollExpired => {
+                format!("A poll has ended: {content}")
             }
-            _ => return None,
+            _ => format!("{username} {}: {content}", kind.action()),
         };
         let status = StatusViewModel::new(status);
         let id = notification.id.clone();
         Some(Self {
             id,
+            kind,
             message,
             status,
             date: notification.created_at,
@@ -473,6 +597,151 @@ impl NotificationViewModel {
     }
 }
 
+/// Notifications of the same kind for the same status (or, for follows,
+/// all of them) grouped together: "5 people boosted your post"
+#[derive(Debug, Eq, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationGroupViewModel {
+    pub id: String,
+    pub kind: NotificationKind,
+    /// The accounts of this group, newest first
+    pub accounts: Vec<AccountViewModel>,
+    pub status: Option<StatusViewModel>,
+    pub notification_ids: Vec<String>,
+    pub message: String,
+    pub content: String,
+    pub date: DateTime<Utc>,
+    pub date_human: String,
+}
+
+impl NotificationGroupViewModel {
+    pub fn group_id(kind: NotificationKind, notification: &Notification) -> String {
+        // The unknown kinds are not mixed with each other
+        let kind = match kind {
+            NotificationKind::Other => format!("{:?}", notification.r#type),
+            _ => format!("{kind:?}"),
+        };
+        match notification.status {
+            Some(ref status) => format!("{kind}-{}", status.id),
+            None => kind,
+        }
+    }
+
+    pub fn new(notification: &Notification) -> Option<Self> {
+        let kind = NotificationKind::new(&notification.r#type);
+        let content = notification
+            .status
+            .as_ref()
+            .map(notification_content)
+            .unwrap_or_default();
+        let mut group = Self {
+            id: Self::group_id(kind, notification),
+            kind,
+            status: notification.status.as_ref().map(StatusViewModel::new),
+            content,
+            ..Default::default()
+        };
+        group.insert(notification);
+        Some(group)
+    }
+
+    /// Add a notification to the group. Returns `false` if it was
+    /// already part of it
+    pub fn insert(&mut self, notification: &Notification) -> bool {
+        if self.notification_ids.contains(&notification.id) {
+            return false;
+        }
+        self.notification_ids.push(notification.id.clone());
+        let wrapped_account: Account = notification.account.clone().into();
+        let account = AccountViewModel::new(&wrapped_account);
+        self.accounts.retain(|a| a.id != account.id);
+        if notification.created_at >= self.date {
+            self.accounts.insert(0, account);
+            let (h, _) = crate::environment::platform::format_datetime(&notification.created_at);
+            self.date = notification.created_at;
+            self.date_human = h;
+        } else {
+            self.accounts.push(account);
+        }
+        let action = self.kind.action();
+        self.message = match self.accounts.as_slice() {
+            [] => String::new(),
+            [one] => format!(
+                crate::loc!("{name} {action}"),
+                name = one.username,
+                action = action
+            ),
+            [one, two] => format!(
+                crate::loc!("{name} and {other} {action}"),
+                name = one.username,
+                other = two.username,
+                action = action
+            ),
+            [one, others @ ..] => format!(
+                crate::loc!("{name} and {count} others {action}"),
+                name = one.username,
+                count = others.len(),
+                action = action
+            ),
+        };
+        true
+    }
+}
+
+fn notification_content(status: &Status) -> String {
+    let mut content = status
+        .plain_content
+        .clone()
+        .unwrap_or_else(|| clean_html(&status.content).0);
+    if content.len() > 140 {
+        content = content.chars().take(140).collect();
+        content.push('…');
+    }
+    content
+}
+
+/// A direct message conversation. Messages are grouped by the
+/// participants of the conversation (without ourselves)
+#[derive(Default, Clone, Debug, Eq, PartialEq)]
+pub struct DirectConversationViewModel {
+    /// The sorted accts of the participants
+    pub id: String,
+    /// The last participant who wrote in this conversation
+    pub account: AccountViewModel,
+    pub participants: String,
+    pub content: String,
+    pub last_updated_human: String,
+    pub last_updated: DateTime<Utc>,
+    pub unread: bool,
+    /// The conversations on the server with unread messages, they are
+    /// marked as read when this conversation is opened
+    pub unread_ids: Vec<String>,
+}
+
+impl DirectConversationViewModel {
+    pub fn new(id: String, status: &StatusViewModel, unread: bool) -> Self {
+        let participants = id
+            .split(',')
+            .map(|acct| format!("@{acct}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut content = status.text.clone();
+        if content.len() > 140 {
+            content = content.chars().take(140).collect();
+            content.push('…');
+        }
+        Self {
+            id,
+            account: status.account.clone(),
+            participants,
+            content,
+            last_updated_human: status.created_human.clone(),
+            last_updated: status.created,
+            unread,
+            unread_ids: Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Default, Serialize, Deserialize)]
 pub struct AccountId(pub String);
 
@@ -501,16 +770,224 @@ pub struct AttachmentMedia {
     pub description: Option<String>,
     pub is_uploaded: bool,
     pub server_id: Option<String>,
+    /// What was done to the file before uploading it, e.g. resizing
+    #[serde(default)]
+    pub processing: Vec<String>,
+    #[serde(default)]
+    pub upload_state: UploadState,
+    /// The upload this attachment is waiting for
+    #[serde(skip)]
+    pub upload: u64,
+    /// How often we asked the server if it finished processing
+    #[serde(skip)]
+    pub processing_checks: u32,
 }
 
 impl PartialEq for AttachmentMedia {
     fn eq(&self, other: &Self) -> bool {
-        self.path == other.path && self.server_id == other.server_id
+        self.path == other.path
+            && self.server_id == other.server_id
+            && self.upload_state == other.upload_state
     }
 }
 
 impl Eq for AttachmentMedia {}
 
+impl AttachmentMedia {
+    pub fn is_ready(&self) -> bool {
+        self.upload_state == UploadState::Ready
+    }
+}
+
+#[derive(Clone, Default, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum UploadState {
+    #[default]
+    Uploading,
+    /// The server is still processing (e.g. transcoding) the attachment
+    Processing,
+    Ready,
+    Failed(String),
+}
+
+impl std::fmt::Display for UploadState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UploadState::Uploading => write!(f, "Uploading…"),
+            UploadState::Processing => write!(f, "Processing…"),
+            UploadState::Ready => write!(f, "Ready"),
+            UploadState::Failed(e) => write!(f, "Failed: {e}"),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct PollViewModel {
+    pub id: String,
+    pub options: Vec<PollOptionViewModel>,
+    /// Can multiple options be selected
+    pub multiple: bool,
+    pub expired: bool,
+    /// Has the *current user* voted in this poll
+    pub voted: bool,
+    pub votes_count: u32,
+    pub votes: String,
+    /// e.g. "2 days left" or "Closed"
+    pub expiry: String,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct PollOptionViewModel {
+    pub title: String,
+    pub votes_count: u32,
+    /// The share of all votes, 0 - 100
+    pub percentage: u32,
+}
+
+impl PollViewModel {
+    pub fn new(poll: &Poll) -> Self {
+        let total = poll.votes_count;
+        // In polls with multiple choices, every voter can pick several options.
+        // The shares are of the people who voted, like in the web interface
+        let voters = poll.voters_count.filter(|_| poll.multiple);
+        let base = voters.unwrap_or(total);
+        let options = poll
+            .options
+            .iter()
+            .map(|option| {
+                let votes_count = option.votes_count.unwrap_or_default();
+                let percentage = if base > 0 {
+                    ((votes_count as f64 / base as f64) * 100.0).round() as u32
+                } else {
+                    0
+                };
+                PollOptionViewModel {
+                    title: option.title.clone(),
+                    votes_count,
+                    percentage,
+                }
+            })
+            .collect();
+        let expired = poll.expired
+            || poll
+                .expires_at
+                .map(|e| e <= Utc::now())
+                .unwrap_or_default();
+        let expiry = match poll.expires_at {
+            _ if expired => "Closed".to_string(),
+            Some(date) => {
+                let left = date.signed_duration_since(Utc::now());
+                if left.num_days() > 0 {
+                    format!("{} days left", left.num_days())
+                } else if left.num_hours() > 0 {
+                    format!("{} hours left", left.num_hours())
+                } else {
+                    format!("{} minutes left", left.num_minutes().max(1))
+                }
+            }
+            None => String::new(),
+        };
+        PollViewModel {
+            id: poll.id.clone(),
+            options,
+            multiple: poll.multiple,
+            expired,
+            voted: poll.voted,
+            votes_count: total,
+            votes: match voters {
+                Some(voters) => format!("{} people", format_number(voters as i64)),
+                None => format!("{} votes", format_number(total as i64)),
+            },
+            expiry,
+            expires_at: poll.expires_at,
+        }
+    }
+
+    /// Results are shown once we voted or the poll is closed
+    pub fn show_results(&self) -> bool {
+        self.voted || self.expired
+    }
+}
+
+/// A status that will be posted at a later date
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ScheduledStatusViewModel {
+    pub id: String,
+    pub text: String,
+    pub scheduled_at: DateTime<Utc>,
+    pub scheduled_full: String,
+    pub media_count: usize,
+}
+
+impl ScheduledStatusViewModel {
+    pub fn new(status: &ScheduledStatus) -> Self {
+        let (_, full) = crate::environment::platform::format_datetime(&status.scheduled_at);
+        Self {
+            id: status.id.clone(),
+            text: status.params.text.clone(),
+            scheduled_at: status.scheduled_at,
+            scheduled_full: full,
+            media_count: status.media_attachments.len(),
+        }
+    }
+}
+
+/// A hashtag with its recent activity (usually the last week)
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct TagViewModel {
+    pub name: String,
+    pub url: String,
+    pub following: bool,
+    pub uses: String,
+    pub accounts: String,
+    /// Activity per day, newest first
+    pub activity: Vec<usize>,
+}
+
+impl TagViewModel {
+    pub fn new(tag: &Tag) -> Self {
+        let uses: usize = tag.history.iter().map(|h| h.uses).sum();
+        let accounts: usize = tag.history.iter().map(|h| h.accounts).sum();
+        Self {
+            name: tag.name.clone(),
+            url: tag.url.clone(),
+            following: tag.following.unwrap_or_default(),
+            uses: format_number(uses as i64),
+            accounts: format_number(accounts as i64),
+            activity: tag.history.iter().map(|h| h.uses).collect(),
+        }
+    }
+
+    pub fn activity_description(&self) -> String {
+        format!("{} posts by {} people", self.uses, self.accounts)
+    }
+}
+
+/// The results of a search, grouped by kind
+#[derive(Debug, Clone, Default)]
+pub struct SearchResultsViewModel {
+    pub term: String,
+    pub accounts: Vec<AccountViewModel>,
+    pub statuses: Vec<StatusViewModel>,
+    pub hashtags: Vec<TagViewModel>,
+}
+
+impl SearchResultsViewModel {
+    pub fn new(term: String, results: &SearchResults) -> Self {
+        Self {
+            term,
+            accounts: results.accounts.iter().map(AccountViewModel::new).collect(),
+            statuses: results.statuses.iter().map(StatusViewModel::new).collect(),
+            hashtags: results.hashtags.iter().map(TagViewModel::new).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.statuses.is_empty() && self.hashtags.is_empty()
+    }
+}
+
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct VideoMedia {
     pub preview_url: Option<String>,
@@ -21,6 +21,9 @@ pub use textcontent::*;
 mod status;
 pub use status::*;
 
+mod poll;
+pub use poll::*;
+
 mod sidebar_text;
 pub use sidebar_text::*;
 
@@ -0,0 +1,88 @@
+use dioxus::prelude::*;
+
+use crate::loc;
+use crate::view_model::PollViewModel;
+use crate::widgets::*;
+
+/// Renders a poll. Before voting (and while the poll is running) the options
+/// can be selected, afterwards the results are shown
+#[inline_props]
+pub fn PollComponent<'a>(
+    cx: Scope<'a>,
+    poll: &'a PollViewModel,
+    onvote: EventHandler<'a, Vec<u32>>,
+) -> Element<'a> {
+    let selection = use_ref(cx, Vec::<u32>::new);
+
+    if poll.show_results() {
+        return cx.render(rsx!(
+            div { class: "poll-object enable-pointer-events",
+                poll.options.iter().map(|option| rsx!(div {
+                    class: "poll-result",
+                    div {
+                        class: "poll-result-bar",
+                        style: "width: {option.percentage}%"
+                    }
+                    HStack { class: "poll-result-label justify-content-between",
+                        Label { style: TextStyle::Primary, "{option.title}" }
+                        Label { style: TextStyle::Secondary, "{option.percentage}%" }
+                    }
+                }))
+                PollFooter { poll: poll }
+            }
+        ));
+    }
+
+    let input_type = if poll.multiple { "checkbox" } else { "radio" };
+    let has_selection = !selection.read().is_empty();
+
+    cx.render(rsx!(
+        div { class: "poll-object enable-pointer-events",
+            poll.options.iter().enumerate().map(|(index, option)| {
+                let index = index as u32;
+                let checked = selection.read().contains(&index);
+                rsx!(label {
+                    class: "poll-option",
+                    input {
+                        r#type: "{input_type}",
+                        name: "poll-{poll.id}",
+                        checked: "{checked}",
+                        onclick: move |_| {
+                            let mut selection = selection.write();
+                            if !poll.multiple {
+                                selection.clear();
+                                selection.push(index);
+                            } else if let Some(position) = selection.iter().position(|e| *e == index) {
+                                selection.remove(position);
+                            } else {
+                                selection.push(index);
+                            }
+                        }
+                    }
+                    "{option.title}"
+                })
+            })
+            HStack { class: "align-items-center gap-3",
+                button {
+                    class: "button",
+                    disabled: "{!has_selection}",
+                    onclick: move |_| {
+                        let choices = selection.read().clone();
+                        onvote.call(choices)
+                    },
+                    loc!("Vote")
+                }
+                PollFooter { poll: poll }
+            }
+        }
+    ))
+}
+
+#[inline_props]
+fn PollFooter<'a>(cx: Scope<'a>, poll: &'a PollViewModel) -> Element<'a> {
+    let voted = if poll.voted { loc!(" · You voted") } else { "" };
+    cx.render(rsx!(Label {
+        style: TextStyle::Tertiary,
+        "{poll.votes} · {poll.expiry}{voted}"
+    }))
+}
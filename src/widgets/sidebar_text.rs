@@ -17,6 +17,8 @@ pub fn SidebarTextEntry<'a>(
     icon: &'a str,
     text: &'a str,
     selected: bool,
+    /// A count that needs attention, e.g. pending follow requests
+    badge: usize,
     onclick: EventHandler<'a, ()>,
 ) -> Element<'a> {
     let class = selected.then(|| "selected").unwrap_or_default();
@@ -35,6 +37,10 @@ pub fn SidebarTextEntry<'a>(
                 pointer_style: PointerStyle::Pointer,
                 "{text}"
             }
+            (*badge > 0).then(|| rsx!(span {
+                class: "badge",
+                "{badge}"
+            }))
         }
     }
 }
@@ -8,8 +8,35 @@ use crate::icons;
 use crate::widgets::*;
 
 use crate::environment::menu::{self};
+use crate::environment::types::{Backend, UiConfig};
 use crate::loc;
-use crate::view_model::StatusViewModel;
+use crate::view_model::{AccountId, StatusViewModel};
+
+/// The ui settings shared by the logged in app. Windows that don't
+/// share them use the defaults
+pub fn shared_ui_config(cx: &ScopeState) -> UiConfig {
+    use_shared_state::<UiConfig>(cx)
+        .map(|e| e.read().clone())
+        .unwrap_or_default()
+}
+
+/// The account of the logged in user, shared so that the own
+/// statuses can offer to be edited or deleted
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct CurrentAccountId(pub Option<AccountId>);
+
+pub fn is_own_status(cx: &ScopeState, status: &StatusViewModel) -> bool {
+    use_shared_state::<CurrentAccountId>(cx)
+        .map(|e| e.read().0.as_ref() == Some(&status.account.id))
+        .unwrap_or_default()
+}
+
+/// Can statuses be quoted on the instance of the logged in user
+pub fn supports_quotes(cx: &ScopeState) -> bool {
+    use_shared_state::<Backend>(cx)
+        .map(|e| e.read().supports_quotes())
+        .unwrap_or_default()
+}
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub enum StatusAction {
@@ -24,6 +51,17 @@ pub enum StatusAction {
     OpenImage(String),
     OpenVideo(String),
     Copy(String),
+    /// Vote for the given (zero-based) poll options
+    VotePoll(Vec<u32>),
+    Edit,
+    Delete,
+    /// Delete and open the text in the composer again
+    Redraft,
+    Report,
+    /// Open the composer with this status quoted
+    Quote,
+    /// Open the conversation of the quoted status
+    OpenQuote(StatusViewModel),
 }
 
 #[inline_props]
@@ -36,11 +74,37 @@ pub fn StatusComponent<'a>(
     children: Element<'a>,
 ) -> Element<'a> {
     let window = AppWindow::retrieve(cx);
+    let settings = shared_ui_config(cx);
+    let expanded = use_state(cx, || false);
+    // Posts matching a `warn` filter are collapsed like a content warning,
+    // but never expanded automatically
+    let warning = match status.filtered.as_ref() {
+        Some(title) => format!("{}: {title}", loc!("Filtered")),
+        None => status.spoiler_text.clone(),
+    };
+    let has_spoiler = !warning.is_empty();
+    let auto_expand = settings.expand_content_warnings && status.filtered.is_none();
+    let is_expanded = !has_spoiler || *expanded.get() || auto_expand;
+    let is_own = is_own_status(cx, status);
+    let can_quote = supports_quotes(cx);
     cx.render(rsx! {
         div {
             class: "enable-pointer-events",
             onclick: move |_| onclick.call(StatusAction::Clicked),
             children,
+        }
+
+        // The content warning is outside of the clickable area so that
+        // toggling it doesn't open the conversation
+        has_spoiler.then(|| rsx!(ContentWarning {
+            spoiler_text: "{warning}",
+            expanded: is_expanded,
+            ontoggle: move |_| expanded.set(!is_expanded)
+        }))
+
+        is_expanded.then(|| rsx!(div {
+            class: "enable-pointer-events",
+            onclick: move |_| onclick.call(StatusAction::Clicked),
             TextContent {
                 content: Cow::from(&status.content),
                 onclick: move |action| match action {
@@ -50,13 +114,29 @@ pub fn StatusComponent<'a>(
                 },
                 class: ""
             }
-        }
+        }))
 
-        ContentCellMedia { 
-            status: Cow::Borrowed(status),
-            onclick: move |evt| onclick.call(evt),
-            sender: sender.clone()
-        }
+        is_expanded.then(|| rsx!(
+            status.poll.as_ref().map(|poll| rsx!(PollComponent {
+                poll: poll,
+                onvote: move |choices| onclick.call(StatusAction::VotePoll(choices))
+            }))
+
+            ContentCellMedia {
+                status: Cow::Borrowed(status),
+                hide_sensitive: status.sensitive && !settings.show_sensitive_media,
+                onclick: move |evt| onclick.call(evt),
+                sender: sender.clone()
+            }
+
+            status.quote.as_ref().map(|quote| rsx!(div {
+                class: "m-2",
+                QuotedStatusCard {
+                    status: quote,
+                    onclick: move |_| onclick.call(StatusAction::OpenQuote(quote.as_ref().clone()))
+                }
+            }))
+        ))
 
         HStack { class: "justify-content-between m-2 gap-3 wrap enable-pointer-events",
             IconButton {
@@ -117,6 +197,25 @@ pub fn StatusComponent<'a>(
                                 ),
                             );
                     }
+                    if can_quote {
+                        items.push(menu::ContextMenuItem::item(loc!("Quote"), StatusAction::Quote));
+                    }
+                    if is_own {
+                        items.extend([
+                            menu::ContextMenuItem::separator(),
+                            menu::ContextMenuItem::item(loc!("Edit"), StatusAction::Edit),
+                            menu::ContextMenuItem::item(loc!("Delete"), StatusAction::Delete),
+                            menu::ContextMenuItem::item(
+                                loc!("Delete & Redraft"),
+                                StatusAction::Redraft
+                            ),
+                        ]);
+                    } else {
+                        items.extend([
+                            menu::ContextMenuItem::separator(),
+                            menu::ContextMenuItem::item(loc!("Report..."), StatusAction::Report),
+                        ]);
+                    }
                     context_menu(cx, sender.clone(), window, &e.data, menu::ContextMenu::new(loc!("Post Options"), true, items))
                 }
             }
@@ -124,22 +223,126 @@ pub fn StatusComponent<'a>(
     })
 }
 
+/// A quoted status, embedded in the status that quotes it
+/// or in the composer
+#[inline_props]
+pub fn QuotedStatusCard<'a>(
+    cx: Scope<'a>,
+    status: &'a StatusViewModel,
+    onclick: Option<EventHandler<'a, ()>>,
+) -> Element<'a> {
+    // Don't reveal the text behind a content warning
+    let mut text = match status.spoiler_text.is_empty() {
+        true => status.text.clone(),
+        false => status.spoiler_text.clone(),
+    };
+    if text.chars().count() > 300 {
+        text = text.chars().take(300).collect();
+        text.push('…');
+    }
+    let pointer_class = onclick
+        .is_some()
+        .then_some("quote-card-clickable")
+        .unwrap_or_default();
+    cx.render(rsx!(
+        div {
+            class: "quote-card {pointer_class}",
+            onclick: move |_| {
+                if let Some(onclick) = onclick {
+                    onclick.call(())
+                }
+            },
+            HStack { class: "align-items-center gap-2",
+                img {
+                    class: "quote-card-image",
+                    src: "{status.account.image}",
+                }
+                Label {
+                    style: TextStyle::Primary,
+                    "{status.account.display_name}"
+                }
+                Label {
+                    class: "me-auto",
+                    style: TextStyle::Tertiary,
+                    "@{status.account.acct}"
+                }
+                Label {
+                    style: TextStyle::Tertiary,
+                    title: "{status.created_full}",
+                    "{status.created_human}"
+                }
+            }
+            Paragraph {
+                style: TextStyle::Secondary,
+                "{text}"
+            }
+        }
+    ))
+}
+
+/// The collapsible header of a post with a content warning
+#[inline_props]
+pub fn ContentWarning<'a>(
+    cx: Scope<'a>,
+    spoiler_text: &'a str,
+    expanded: bool,
+    ontoggle: EventHandler<'a, ()>,
+) -> Element<'a> {
+    let title = if *expanded {
+        loc!("Show less")
+    } else {
+        loc!("Show more")
+    };
+    cx.render(rsx!(
+        HStack { class: "content-warning align-items-center justify-content-between gap-2",
+            Label {
+                style: TextStyle::Primary,
+                "{spoiler_text}"
+            }
+            button {
+                class: "button",
+                onclick: move |_| ontoggle.call(()),
+                "{title}"
+            }
+        }
+    ))
+}
+
 #[inline_props]
 fn ContentCellMedia<'a>(
     cx: Scope<'a>,
     status: Cow<'a, StatusViewModel>,
+    hide_sensitive: bool,
     onclick: EventHandler<'a, StatusAction>,
     sender: Arc<dyn Fn(StatusAction) + Send + Sync>,
 ) -> Element<'a> {
     let window = AppWindow::retrieve(cx);
+    let revealed = use_state(cx, || false);
+    let is_hidden = *hide_sensitive && !*revealed.get();
+    let has_media = !status.status_images.is_empty() || !status.media.is_empty();
+    let blur = if is_hidden { "sensitive-hidden" } else { "" };
     cx.render(rsx!(
 
+        (is_hidden && has_media).then(|| rsx!(div {
+            class: "sensitive-object enable-pointer-events",
+            onclick: move |_| revealed.set(true),
+            Label {
+                style: TextStyle::Secondary,
+                pointer_style: PointerStyle::Pointer,
+                loc!("Sensitive content. Click to show")
+            }
+        }))
+
         { status.status_images.iter().map(|(description, preview, url)| rsx!(div {
-            class: "media-object",
+            class: "media-object {blur}",
             img {
                 src: "{preview}",
                 alt: "{description}",
-                onclick: move |_| onclick.call(StatusAction::OpenImage(url.to_string())),
+                onclick: move |_| if is_hidden {
+                    revealed.set(true)
+                } else {
+                    onclick.call(StatusAction::OpenImage(url.to_string()))
+                },
                 prevent_default: "oncontextmenu",
                 oncontextmenu: move |e| {
                     context_menu(cx, sender.clone(), window, &e.data, menu::ContextMenu::new(loc!("Image"), true, vec![
@@ -163,7 +366,7 @@ fn ContentCellMedia<'a>(
         { status.media.iter().map(|video| {
             let preview = video.preview_url.as_ref().cloned().unwrap_or_default();
             rsx!(div {
-                class: "enable-pointer-events",
+                class: "enable-pointer-events {blur}",
                 video {
                     // FIXME: MOVE TO CSS
                     style: "width: 448px;",
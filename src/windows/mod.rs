@@ -3,3 +3,5 @@ pub mod image_window;
 pub mod post_window;
 
 pub mod preferences_window;
+
+pub mod report_window;
@@ -4,7 +4,10 @@ use std::path::PathBuf;
 use std::rc::Rc;
 
 use crate::environment::model::Account;
-use crate::environment::{types::AppEvent, Environment};
+use crate::environment::{
+    types::{AppEvent, Draft},
+    Environment,
+};
 
 use crate::components::post::*;
 
@@ -58,6 +61,15 @@ impl OpenWindowState for PostWindowState {
             text: String::new(),
             validity: (false, 0, 500),
             config,
+            poll: None,
+            poll_limits: Default::default(),
+            spoiler_text: None,
+            sensitive: false,
+            scheduled_at: None,
+            info_message: None,
+            draft_id: Draft::new_id(),
+            draft_dirty: false,
+            drafts: Vec::new(),
         };
 
         let store: navicula::ViewStore<PostReducer> =
@@ -1,6 +1,6 @@
 #![allow(unused)]
 use crate::components::loggedin::Action;
-use crate::environment::types::{AppEvent, TimelineDirection};
+use crate::environment::types::{AppEvent, CacheRetention, TimelineDirection};
 use crate::environment::{Environment, OpenWindowState};
 use crate::loc;
 use crate::widgets::*;
@@ -12,6 +12,8 @@ use std::str::FromStr;
 pub enum PreferencesChange {
     Direction,
     PostWindow,
+    SensitiveContent,
+    Cache,
 }
 
 #[derive(Clone)]
@@ -45,12 +47,21 @@ impl OpenWindowState for PreferencesWindowState {
         let direction = current.direction;
 
         let inline_postwindow = current.post_window_inline;
+        let expand_content_warnings = current.expand_content_warnings;
+        let show_sensitive_media = current.show_sensitive_media;
+        let cache_retention = current.cache_retention;
 
         let e1 = environment.clone();
         let e2 = environment.clone();
+        let e3 = environment.clone();
+        let e4 = environment.clone();
+        let e5 = environment.clone();
 
         let p1 = parent_handler.clone();
         let p2 = parent_handler.clone();
+        let p3 = parent_handler.clone();
+        let p4 = parent_handler.clone();
+        let p5 = parent_handler.clone();
 
         cx.render(rsx!(div {
             class: "settings-container",
@@ -78,6 +89,43 @@ impl OpenWindowState for PreferencesWindowState {
                         p2(PreferencesChange::PostWindow);
                     }
                 }
+                CheckboxSetting {
+                    id: "expand_content_warnings",
+                    title: loc!("Always expand posts with content warnings"),
+                    checked: expand_content_warnings,
+                    onchange: move |v| {
+                        let Ok(mut current) = e3.repository.config() else {
+                            return
+                        };
+                        current.expand_content_warnings = v;
+                        e3.repository.set_config(&current);
+                        p3(PreferencesChange::SensitiveContent);
+                    }
+                }
+                CheckboxSetting {
+                    id: "show_sensitive_media",
+                    title: loc!("Always show media marked as sensitive"),
+                    checked: show_sensitive_media,
+                    onchange: move |v| {
+                        let Ok(mut current) = e4.repository.config() else {
+                            return
+                        };
+                        current.show_sensitive_media = v;
+                        e4.repository.set_config(&current);
+                        p4(PreferencesChange::SensitiveContent);
+                    }
+                }
+                CacheSetting {
+                    retention: cache_retention,
+                    onchange: move |retention| {
+                        let Ok(mut current) = e5.repository.config() else {
+                            return
+                        };
+                        current.cache_retention = retention;
+                        e5.repository.set_config(&current);
+                        p5(PreferencesChange::Cache);
+                    }
+                }
             }
         }))
     }
@@ -129,6 +177,39 @@ fn TimelineSetting<'a>(
     }
 }
 
+#[inline_props]
+fn CacheSetting<'a>(
+    cx: Scope<'a>,
+    retention: CacheRetention,
+    onchange: EventHandler<'a, CacheRetention>,
+) -> Element<'a> {
+    render! {
+        HStack {
+            class: "justify-content-between align-items-center",
+            Label {
+                style: TextStyle::Secondary,
+                loc!("Offline Cache")
+            }
+            select {
+                onchange: move |evt| {
+                    let Some(retention) = evt.value.parse::<usize>().ok()
+                        .and_then(|i| CacheRetention::all().get(i).copied()) else {
+                        return
+                    };
+                    onchange.call(retention);
+                },
+                for (index, choice) in CacheRetention::all().iter().enumerate() {
+                    option {
+                        value: "{index}",
+                        selected: "{retention == choice}",
+                        "{choice}"
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[inline_props]
 fn PostInlineSetting<'a>(
     cx: Scope<'a>,
@@ -155,6 +236,34 @@ fn PostInlineSetting<'a>(
     }
 }
 
+#[inline_props]
+fn CheckboxSetting<'a>(
+    cx: Scope<'a>,
+    id: &'a str,
+    title: &'a str,
+    checked: bool,
+    onchange: EventHandler<'a, bool>,
+) -> Element<'a> {
+    render! {
+        HStack {
+            class: "justify-content-between align-items-center",
+            input {
+                r#type: "checkbox",
+                id: "{id}",
+                checked: "{checked}",
+                oninput: move |evt| {
+                    onchange.call(evt.value.parse().unwrap_or_default());
+                },
+            }
+            label {
+                class: "label-secondary",
+                r#for: "{id}",
+                "{title}"
+            }
+        }
+    }
+}
+
 impl FromStr for TimelineDirection {
     type Err = String;
 
@@ -0,0 +1,45 @@
+use std::rc::Rc;
+
+use crate::components::report::{ReportAction, ReportComponent, ReportReducer, ReportState};
+use crate::environment::{types::AppEvent, Environment, OpenWindowState};
+use crate::view_model::{AccountViewModel, StatusViewModel};
+use dioxus::prelude::*;
+
+#[derive(Clone)]
+pub struct ReportWindowState {
+    account: AccountViewModel,
+    status: Option<StatusViewModel>,
+}
+
+impl ReportWindowState {
+    pub fn new(account: AccountViewModel, status: Option<StatusViewModel>) -> Self {
+        Self { account, status }
+    }
+}
+
+impl OpenWindowState for ReportWindowState {
+    type Action = ();
+    fn window<'a, 'b>(
+        &'a self,
+        cx: Scope<'b>,
+        environment: &'a Environment,
+        receiver: flume::Receiver<AppEvent>,
+        _parent_handler: Rc<dyn Fn(Self::Action)>,
+    ) -> Element<'b>
+    where
+        'a: 'b,
+    {
+        let state = ReportState::new(self.account.clone(), self.status.clone());
+        let store: navicula::ViewStore<ReportReducer> =
+            navicula::root(cx, &[], environment, || state);
+
+        // The report window doesn't react to menu events
+        for _ in receiver.try_iter() {}
+
+        render! {
+            ReportComponent {
+                store: store
+            }
+        }
+    }
+}